@@ -25,8 +25,14 @@
 //!
 //! These primitives form the backbone of MQTT communication and can be converted to their equivalents in
 //! various MQTT libraries, making this module a flexible foundation for MQTT client implementations.
+//!
+//! With the `rumqttc` feature enabled, the `client::rumqttc_ext` module provides
+//! these conversions for the rumqttc client.
+
+use std::{collections::HashSet, fmt::Display, string::FromUtf8Error};
 
-use std::string::FromUtf8Error;
+#[cfg(feature = "rumqttc")]
+pub mod rumqttc_ext;
 
 use serde::{Deserialize, Serialize};
 
@@ -35,7 +41,7 @@ use serde::{Deserialize, Serialize};
 /// The Last Will message is a feature in MQTT that ensures a device can notify others of an unexpected disconnection.
 /// When a device loses connection to the MQTT broker unexpectedly, the broker will publish this "last will" message
 /// on behalf of the disconnected client, typically indicating the device is "lost" or "offline".
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct LastWill {
     /// The MQTT topic where the Last Will message will be published.
     ///
@@ -108,7 +114,7 @@ pub enum QoS {
 ///
 /// - `topic`: The topic filter specifying which messages the client is interested in receiving.
 /// - `qos`: The Quality of Service level that dictates how the broker delivers messages to the client.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Subscription {
     /// The topic filter for the subscription.
     ///
@@ -133,7 +139,7 @@ pub struct Subscription {
 /// - `retain`: A flag indicating whether the message should be retained by the broker.
 /// - `payload`: The actual data being sent in the message, as a binary payload (vector of bytes).
 /// - `qos`: The Quality of Service level, which determines the reliability of the message delivery.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Publish {
     /// The topic for this publish message.
     ///
@@ -163,7 +169,7 @@ pub struct Publish {
 ///
 /// Unsubscribing from a topic stops the client from receiving messages that are published to that topic.
 /// Once unsubscribed, the client will no longer receive updates from the broker for the specified topic.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Unsubscribe {
     /// The MQTT topic the client wishes to unsubscribe from.
     ///
@@ -174,6 +180,95 @@ pub struct Unsubscribe {
     pub topic: String,
 }
 
+impl Display for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SUB {} ({:?})", self.topic, self.qos)
+    }
+}
+
+impl Display for Publish {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PUB {} ({:?}, retain: {}, {} bytes)",
+            self.topic,
+            self.qos,
+            self.retain,
+            self.payload.len()
+        )
+    }
+}
+
+impl Display for Unsubscribe {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UNSUB {}", self.topic)
+    }
+}
+
+/// Computes the subscriptions and unsubscribes needed to get from the `current` set of subscribed
+/// topics to the `required` set.
+///
+/// Topics only in `required` are subscribed (with `QoS::ExactlyOnce`, as all homie
+/// subscriptions), topics only in `current` are unsubscribed. Both lists are sorted by topic.
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashSet;
+/// use homie5::client::subscription_delta;
+///
+/// let current = HashSet::from(["homie/5/a/$state".to_owned(), "homie/5/b/$state".to_owned()]);
+/// let required = HashSet::from(["homie/5/b/$state".to_owned(), "homie/5/c/$state".to_owned()]);
+///
+/// let (subs, unsubs) = subscription_delta(&current, &required);
+/// assert_eq!(subs[0].topic, "homie/5/c/$state");
+/// assert_eq!(unsubs[0].topic, "homie/5/a/$state");
+/// ```
+pub fn subscription_delta(
+    current: &HashSet<String>,
+    required: &HashSet<String>,
+) -> (Vec<Subscription>, Vec<Unsubscribe>) {
+    let mut subscriptions: Vec<_> = required
+        .difference(current)
+        .map(|topic| Subscription {
+            topic: topic.clone(),
+            qos: QoS::ExactlyOnce,
+        })
+        .collect();
+    subscriptions.sort_by(|a, b| a.topic.cmp(&b.topic));
+
+    let mut unsubscribes: Vec<_> = current
+        .difference(required)
+        .map(|topic| Unsubscribe { topic: topic.clone() })
+        .collect();
+    unsubscribes.sort_by(|a, b| a.topic.cmp(&b.topic));
+
+    (subscriptions, unsubscribes)
+}
+
+/// Creates the publishes clearing the retained messages of the given topics.
+///
+/// A retained message is cleared by publishing a zero-length payload with the retain flag set,
+/// which is done with `QoS::ExactlyOnce` like all other homie clears (e.g. when removing a device).
+/// The broker's topics cannot be enumerated by this crate, so the topics have to be known, e.g.
+/// from a subscription to the whole domain.
+///
+/// # Example
+/// ```rust
+/// use homie5::client::{clear_topics, QoS};
+///
+/// let publishes: Vec<_> = clear_topics(["homie/5/old-device/$state".to_owned()]).collect();
+/// assert_eq!(publishes[0].topic, "homie/5/old-device/$state");
+/// assert!(publishes[0].retain && publishes[0].payload.is_empty());
+/// ```
+pub fn clear_topics(topics: impl IntoIterator<Item = String>) -> impl Iterator<Item = Publish> {
+    topics.into_iter().map(|topic| Publish {
+        topic,
+        qos: QoS::ExactlyOnce,
+        retain: true,
+        payload: Vec::new(),
+    })
+}
+
 /// Attempt to parse the payload as a UTF-8 string
 /// special case:
 /// accoring to the homie convention a string with a 0 value byte as first value constitues an
@@ -0,0 +1,67 @@
+//! Conversions and helpers for the [rumqttc](https://docs.rs/rumqttc) mqtt client.
+//!
+//! This module is only available with the `rumqttc` feature enabled. It converts the library
+//! agnostic mqtt primitives of this crate into their rumqttc counterparts and sends them with an
+//! [`AsyncClient`].
+//!
+//! The module is not named `rumqttc` so that it does not shadow the rumqttc crate for users
+//! importing `homie5::client::*`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use homie5::client::QoS;
+//!
+//! assert_eq!(rumqttc::QoS::from(QoS::AtMostOnce), rumqttc::QoS::AtMostOnce);
+//! assert_eq!(rumqttc::QoS::from(QoS::AtLeastOnce), rumqttc::QoS::AtLeastOnce);
+//! assert_eq!(rumqttc::QoS::from(QoS::ExactlyOnce), rumqttc::QoS::ExactlyOnce);
+//! ```
+
+use ::rumqttc::{AsyncClient, ClientError};
+
+use super::{LastWill, Publish, QoS, Subscription, Unsubscribe};
+
+impl From<QoS> for ::rumqttc::QoS {
+    fn from(value: QoS) -> Self {
+        match value {
+            QoS::AtMostOnce => ::rumqttc::QoS::AtMostOnce,
+            QoS::AtLeastOnce => ::rumqttc::QoS::AtLeastOnce,
+            QoS::ExactlyOnce => ::rumqttc::QoS::ExactlyOnce,
+        }
+    }
+}
+
+impl From<LastWill> for ::rumqttc::LastWill {
+    fn from(value: LastWill) -> Self {
+        ::rumqttc::LastWill {
+            topic: value.topic,
+            message: value.message.into(),
+            qos: value.qos.into(),
+            retain: value.retain,
+        }
+    }
+}
+
+/// Publishes the message with the client.
+pub async fn publish(client: &AsyncClient, p: Publish) -> Result<(), ClientError> {
+    client.publish(p.topic, p.qos.into(), p.retain, p.payload).await
+}
+
+/// Subscribes to all topics with the client.
+pub async fn subscribe(client: &AsyncClient, subs: impl IntoIterator<Item = Subscription>) -> Result<(), ClientError> {
+    for sub in subs {
+        client.subscribe(sub.topic, sub.qos.into()).await?;
+    }
+    Ok(())
+}
+
+/// Unsubscribes from all topics with the client.
+pub async fn unsubscribe(
+    client: &AsyncClient,
+    unsubs: impl IntoIterator<Item = Unsubscribe>,
+) -> Result<(), ClientError> {
+    for unsub in unsubs {
+        client.unsubscribe(unsub.topic).await?;
+    }
+    Ok(())
+}
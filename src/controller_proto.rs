@@ -15,17 +15,67 @@
 //! 4. after this you will start receiving [`Homie5Message::PropertyValue`] and
 //!    ['Homie5Message::PropertyTarget`] messages for the properties of the device
 //!
+//! [`DiscoveryStage`] can be used to determine how far the discovery of a device has progressed.
+//!
 
 use std::iter;
 
 use crate::{
     client::{Publish, QoS, Subscription, Unsubscribe},
     device_description::{HomieDeviceDescription, HomiePropertyIterator},
-    DeviceLogLevel, DeviceRef, HomieDomain, HomieID, HomieValue, PropertyRef, ToTopic, TopicBuilder, DEVICE_ATTRIBUTES,
-    DEVICE_ATTRIBUTE_ALERT, DEVICE_ATTRIBUTE_LOG, DEVICE_ATTRIBUTE_STATE, DEVICE_LOG_LEVELS, HOMIE_TOPIC_BROADCAST,
-    PROPERTY_ATTRIBUTE_TARGET, PROPERTY_SET_TOPIC,
+    error::Homie5ProtocolError,
+    DeviceAttribute, DeviceLogLevel, DeviceRef, HomieDeviceStatus, HomieDomain, HomieID, HomieValue, PreparedProperty,
+    PropertyRef, ToTopic, TopicBuilder, DEVICE_ATTRIBUTES, DEVICE_ATTRIBUTE_ALERT, DEVICE_ATTRIBUTE_LOG,
+    DEVICE_ATTRIBUTE_STATE, DEVICE_LOG_LEVELS, HOMIE_TOPIC_BROADCAST, HOMIE_VERSION, PROPERTY_ATTRIBUTE_TARGET,
+    PROPERTY_SET_TOPIC,
 };
 
+/// The stage the discovery of a device is in, following the [device discovery flow](self).
+///
+/// The stages are ordered, so `stage >= DiscoveryStage::DescriptionKnown` can be used to check if
+/// a stage was reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiscoveryStage {
+    /// No `$state` was received for the device yet
+    Undiscovered,
+    /// The `$state` of the device was received, the device needs to be subscribed to receive its
+    /// description
+    StateKnown,
+    /// The `$description` of the device was received, its properties need to be subscribed
+    DescriptionKnown,
+    /// The properties of the device are subscribed, discovery is complete
+    PropertiesSubscribed,
+}
+
+impl DiscoveryStage {
+    /// Determines the discovery stage from what a controller has received for a device.
+    ///
+    /// # Parameters
+    /// - `state`: The last `$state` received for the device.
+    /// - `description`: The last `$description` received for the device.
+    /// - `props_subscribed`: Whether the properties of the current description are subscribed.
+    ///
+    /// A stage is only reached if all previous stages were reached as well, e.g. subscribed
+    /// properties without a known description are not considered.
+    pub fn from_progress(
+        state: Option<HomieDeviceStatus>,
+        description: Option<&HomieDeviceDescription>,
+        props_subscribed: bool,
+    ) -> Self {
+        match (state, description, props_subscribed) {
+            (None, _, _) => DiscoveryStage::Undiscovered,
+            (Some(_), None, _) => DiscoveryStage::StateKnown,
+            (Some(_), Some(_), false) => DiscoveryStage::DescriptionKnown,
+            (Some(_), Some(_), true) => DiscoveryStage::PropertiesSubscribed,
+        }
+    }
+
+    /// Returns true if the discovery of the device is complete.
+    pub fn is_complete(&self) -> bool {
+        *self == DiscoveryStage::PropertiesSubscribed
+    }
+}
+
 /// The `Homie5ControllerProtocol` struct provides the core functionality for generating MQTT subscription and publish commands required for interacting with Homie 5 devices.
 ///
 /// This struct simplifies the process of discovering devices, subscribing to device attributes, handling device property changes, and sending commands or broadcasts to devices in the Homie MQTT protocol. It supports managing device discovery, subscriptions to device states and properties, and sending set commands and broadcast messages.
@@ -50,7 +100,10 @@ use crate::{
 ///
 /// The struct is intended for use in controller applications interacting with multiple Homie devices, enabling efficient subscription management and MQTT communication.
 #[derive(Debug, Default, Clone)]
-pub struct Homie5ControllerProtocol {}
+pub struct Homie5ControllerProtocol {
+    version: Option<String>,
+    subscription_qos: QoS,
+}
 
 impl Homie5ControllerProtocol {
     /// Creates a new `Homie5ControllerProtocol` instance.
@@ -61,6 +114,32 @@ impl Homie5ControllerProtocol {
         Default::default()
     }
 
+    /// Overrides the homie version segment used in all generated topics (`HOMIE_VERSION` by default).
+    ///
+    /// This is intended for testing and simulating other protocol versions only.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Returns the homie version segment used in the generated topics.
+    pub fn version(&self) -> &str {
+        self.version.as_deref().unwrap_or(HOMIE_VERSION)
+    }
+
+    /// Sets the QoS of all generated subscriptions (`QoS::ExactlyOnce` by default).
+    ///
+    /// E.g. a read-only monitor of many devices may prefer `QoS::AtMostOnce` for throughput.
+    pub fn with_subscription_qos(mut self, qos: QoS) -> Self {
+        self.subscription_qos = qos;
+        self
+    }
+
+    /// Returns the QoS used for the generated subscriptions.
+    pub fn subscription_qos(&self) -> QoS {
+        self.subscription_qos.clone()
+    }
+
     /// Generates a subscription to discover Homie devices by subscribing to the `$state` attribute of all devices.
     ///
     /// # Parameters
@@ -74,10 +153,11 @@ impl Homie5ControllerProtocol {
     ) -> impl Iterator<Item = Subscription> + 'a {
         iter::once(Subscription {
             topic: TopicBuilder::new(homie_domain)
+                .with_version(self.version())
                 .add_attr("+")
                 .add_attr(DEVICE_ATTRIBUTE_STATE)
                 .build(),
-            qos: QoS::ExactlyOnce,
+            qos: self.subscription_qos(),
         })
     }
 
@@ -94,6 +174,7 @@ impl Homie5ControllerProtocol {
     ) -> impl Iterator<Item = Unsubscribe> + 'a {
         iter::once(Unsubscribe {
             topic: TopicBuilder::new(homie_domain)
+                .with_version(self.version())
                 .add_attr("+")
                 .add_attr(DEVICE_ATTRIBUTE_STATE)
                 .build(),
@@ -102,13 +183,22 @@ impl Homie5ControllerProtocol {
 
     /// Generates subscriptions for all attributes of a specified device, excluding `$state`.
     ///
+    /// Alerts are subscribed via the `$alert/+` wildcard, see [`Self::subscribe_alerts`].
+    ///
     /// # Parameters
     /// - `device`: A reference to the `DeviceRef` that identifies the device.
     ///
     /// # Returns
     /// An iterator over `Subscription` objects for the device's attributes (e.g., `$log`, `$description`, `$alert`).
     pub fn subscribe_device<'a>(&'a self, device: &'a DeviceRef) -> impl Iterator<Item = Subscription> + 'a {
-        DeviceSubscriptionIterator::new(device, &DEVICE_ATTRIBUTES[1..]).map(|(topic, qos)| Subscription { topic, qos })
+        #[cfg(feature = "tracing")]
+        tracing::debug!(device_id = device.device_id().as_str(), "subscribe device");
+        DeviceSubscriptionIterator::new(device, &DEVICE_ATTRIBUTES[1..])
+            .with_version(self.version())
+            .map(|(topic, _)| Subscription {
+                topic,
+                qos: self.subscription_qos(),
+            })
     }
 
     /// Generates unsubscribe requests for all attributes of a specified device, excluding `$state`.
@@ -119,7 +209,66 @@ impl Homie5ControllerProtocol {
     /// # Returns
     /// An iterator over `Unsubscribe` objects for the device's attributes (e.g., `$log`, `$description`, `$alert`).
     pub fn unsubscribe_device<'a>(&'a self, device: &'a DeviceRef) -> impl Iterator<Item = Unsubscribe> + 'a {
-        DeviceSubscriptionIterator::new(device, &DEVICE_ATTRIBUTES[1..]).map(|(topic, _)| Unsubscribe { topic })
+        DeviceSubscriptionIterator::new(device, &DEVICE_ATTRIBUTES[1..])
+            .with_version(self.version())
+            .map(|(topic, _)| Unsubscribe { topic })
+    }
+
+    /// Generates the subscription for all alerts of a specified device.
+    ///
+    /// Alert ids are not part of the device description, so alerts can only be subscribed via a
+    /// wildcard (`$alert/+`). This subscription is also part of [`Self::subscribe_device`], use this
+    /// method to manage the alert subscription independently.
+    ///
+    /// # Parameters
+    /// - `device`: A reference to the `DeviceRef` that identifies the device.
+    ///
+    /// # Returns
+    /// An iterator over the `Subscription` for the `$alert/+` topic of the device.
+    pub fn subscribe_alerts<'a>(&'a self, device: &'a DeviceRef) -> impl Iterator<Item = Subscription> + 'a {
+        self.subscribe_device_attrs(device, &[DeviceAttribute::Alert])
+    }
+
+    /// Generates subscriptions for a selected set of attributes of a specified device.
+    ///
+    /// # Parameters
+    /// - `device`: A reference to the `DeviceRef` that identifies the device.
+    /// - `attrs`: The device attributes to subscribe to (e.g. only `DeviceAttribute::Description`).
+    ///
+    /// # Returns
+    /// An iterator over `Subscription` objects for the selected attributes. `$alert` is subscribed via
+    /// wildcard and `$log` for all log levels.
+    pub fn subscribe_device_attrs<'a>(
+        &'a self,
+        device: &'a DeviceRef,
+        attrs: &'a [DeviceAttribute],
+    ) -> impl Iterator<Item = Subscription> + 'a {
+        attrs
+            .iter()
+            .flat_map(move |attr| DeviceSubscriptionIterator::new(device, attr.as_slice()).with_version(self.version()))
+            .map(move |(topic, _)| Subscription {
+                topic,
+                qos: self.subscription_qos(),
+            })
+    }
+
+    /// Generates unsubscribe requests for a selected set of attributes of a specified device.
+    ///
+    /// # Parameters
+    /// - `device`: A reference to the `DeviceRef` that identifies the device.
+    /// - `attrs`: The device attributes to unsubscribe from.
+    ///
+    /// # Returns
+    /// An iterator over `Unsubscribe` objects for the selected attributes.
+    pub fn unsubscribe_device_attrs<'a>(
+        &'a self,
+        device: &'a DeviceRef,
+        attrs: &'a [DeviceAttribute],
+    ) -> impl Iterator<Item = Unsubscribe> + 'a {
+        attrs
+            .iter()
+            .flat_map(move |attr| DeviceSubscriptionIterator::new(device, attr.as_slice()).with_version(self.version()))
+            .map(|(topic, _)| Unsubscribe { topic })
     }
 
     /// Subscribes to all properties of a device as described in the provided `HomieDeviceDescription`.
@@ -135,22 +284,85 @@ impl Homie5ControllerProtocol {
         device: &'a DeviceRef,
         description: &'a HomieDeviceDescription,
     ) -> impl Iterator<Item = Subscription> + 'a {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            device_id = device.device_id().as_str(),
+            count = description.iter().count(),
+            "subscribe properties"
+        );
         let prop_iter = HomiePropertyIterator::new(description);
 
         prop_iter.flat_map(move |(node_id, _, prop_id, _)| {
             [
                 Subscription {
-                    topic: device.to_topic().add_id(node_id).add_id(prop_id).build(),
-                    qos: QoS::ExactlyOnce,
+                    topic: device
+                        .to_topic()
+                        .with_version(self.version())
+                        .add_id(node_id)
+                        .add_id(prop_id)
+                        .build(),
+                    qos: self.subscription_qos(),
                 },
                 Subscription {
                     topic: device
                         .to_topic()
+                        .with_version(self.version())
                         .add_id(node_id)
                         .add_id(prop_id)
                         .add_attr(PROPERTY_ATTRIBUTE_TARGET)
                         .build(),
-                    qos: QoS::ExactlyOnce,
+                    qos: self.subscription_qos(),
+                },
+            ]
+        })
+    }
+
+    /// Returns all topics a controller subscribes to for a fully described device, sorted.
+    ///
+    /// These are the topics of [`Self::subscribe_device`] (`$log`, `$description` and `$alert/+`)
+    /// and of [`Self::subscribe_props`] (value and `$target` of each property). `$state` is not
+    /// included, it is part of the device discovery (see [`Self::subscribe_device_discovery`]).
+    ///
+    /// # Parameters
+    /// - `device`: A reference to the `DeviceRef` that identifies the device.
+    /// - `description`: A reference to the `HomieDeviceDescription` that describes the device's properties.
+    pub fn all_subscription_topics(&self, device: &DeviceRef, description: &HomieDeviceDescription) -> Vec<String> {
+        let mut topics: Vec<_> = self
+            .subscribe_device(device)
+            .chain(self.subscribe_props(device, description))
+            .map(|sub| sub.topic)
+            .collect();
+        topics.sort();
+        topics
+    }
+
+    /// Subscribes to a selected set of properties, e.g. the properties a controller monitors.
+    ///
+    /// Unlike [`Self::subscribe_props`] this does not require the device description, the
+    /// properties can belong to different devices.
+    ///
+    /// # Parameters
+    /// - `props`: The properties to subscribe to.
+    ///
+    /// # Returns
+    /// An iterator over `Subscription` objects for the value and the `$target` of each property.
+    pub fn subscribe_properties<'a>(
+        &'a self,
+        props: impl IntoIterator<Item = &'a PropertyRef> + 'a,
+    ) -> impl Iterator<Item = Subscription> + 'a {
+        props.into_iter().flat_map(move |prop| {
+            [
+                Subscription {
+                    topic: prop.to_topic().with_version(self.version()).build(),
+                    qos: self.subscription_qos(),
+                },
+                Subscription {
+                    topic: prop
+                        .to_topic()
+                        .with_version(self.version())
+                        .add_attr(PROPERTY_ATTRIBUTE_TARGET)
+                        .build(),
+                    qos: self.subscription_qos(),
                 },
             ]
         })
@@ -169,9 +381,20 @@ impl Homie5ControllerProtocol {
         device: &'a DeviceRef,
         description: &'a HomieDeviceDescription,
     ) -> impl Iterator<Item = Unsubscribe> + 'a {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            device_id = device.device_id().as_str(),
+            count = description.iter().count(),
+            "unsubscribe properties"
+        );
         let prop_iter = HomiePropertyIterator::new(description);
         prop_iter.map(move |(node_id, _, prop_id, _)| Unsubscribe {
-            topic: device.to_topic().add_id(node_id).add_id(prop_id).build(),
+            topic: device
+                .to_topic()
+                .with_version(self.version())
+                .add_id(node_id)
+                .add_id(prop_id)
+                .build(),
         })
     }
 
@@ -196,6 +419,7 @@ impl Homie5ControllerProtocol {
     ) -> Publish {
         Publish {
             topic: TopicBuilder::new_for_property(homie_domain, device_id, node_id, prop_id)
+                .with_version(self.version())
                 .add_attr(PROPERTY_SET_TOPIC)
                 .build(),
             qos: QoS::ExactlyOnce,
@@ -222,6 +446,61 @@ impl Homie5ControllerProtocol {
         )
     }
 
+    /// Builds the topics of the property referenced by `prop` once, for use with
+    /// [`Self::set_command_prepared`].
+    ///
+    /// The version override of the protocol (see [`Self::with_version`]) is applied to the topics.
+    pub fn prepare_property(&self, prop: PropertyRef) -> PreparedProperty {
+        PreparedProperty::new_for_version(prop, self.version())
+    }
+
+    /// Publishes a set command for a property with prebuilt topics.
+    ///
+    /// # Parameters
+    /// - `prop`: The `PreparedProperty` for the property, see [`Self::prepare_property`].
+    /// - `value`: The value to set for the property.
+    ///
+    /// # Returns
+    /// A `Publish` object representing the set command.
+    pub fn set_command_prepared(&self, prop: &PreparedProperty, value: &HomieValue) -> Publish {
+        Publish {
+            topic: prop.set_topic().to_owned(),
+            qos: QoS::ExactlyOnce,
+            retain: false,
+            payload: value.into(),
+        }
+    }
+
+    /// Publishes a set command for a property from a raw value validated against the device description.
+    ///
+    /// # Parameters
+    /// - `prop`: A reference to the `PropertyRef` identifying the property.
+    /// - `raw`: The raw value to set (e.g. as entered in a UI).
+    /// - `description`: The description of the device the property belongs to.
+    ///
+    /// # Returns
+    /// A `Publish` object containing the set command to be sent to the MQTT broker.
+    ///
+    /// # Errors
+    /// - `PropertyNotFound` if the property does not exist in the description.
+    /// - `PropertyNotSettable` if the property is not settable.
+    /// - `InvalidHomieValue` if the value is not valid for the property.
+    pub fn set_command_validated(
+        &self,
+        prop: &PropertyRef,
+        raw: &str,
+        description: &HomieDeviceDescription,
+    ) -> Result<Publish, Homie5ProtocolError> {
+        let Some(prop_desc) = description.get_property(prop.prop_pointer()) else {
+            return Err(Homie5ProtocolError::PropertyNotFound);
+        };
+        if !prop_desc.settable {
+            return Err(Homie5ProtocolError::PropertyNotSettable);
+        }
+        let value = HomieValue::parse(raw, prop_desc)?;
+        Ok(self.set_command(prop, &value))
+    }
+
     /// Sends a broadcast message to all devices in the specified Homie domain.
     ///
     /// # Parameters
@@ -239,6 +518,7 @@ impl Homie5ControllerProtocol {
     ) -> Publish {
         Publish {
             topic: TopicBuilder::new(homie_domain)
+                .with_version(self.version())
                 .add_attr(HOMIE_TOPIC_BROADCAST)
                 .add_attr(broadcast_topic)
                 .build(),
@@ -259,13 +539,82 @@ impl Homie5ControllerProtocol {
     pub fn subscribe_broadcast<'a>(&'a self, homie_domain: &HomieDomain) -> impl Iterator<Item = Subscription> + 'a {
         iter::once(Subscription {
             topic: TopicBuilder::new(homie_domain)
+                .with_version(self.version())
                 .add_attr(HOMIE_TOPIC_BROADCAST)
                 .add_attr("#")
                 .build(),
-            qos: QoS::ExactlyOnce,
+            qos: self.subscription_qos(),
         })
     }
 
+    /// Subscribes to the broadcast messages of a single subtopic in the specified Homie domain.
+    ///
+    /// Nested subtopics are given with slashes (e.g. `alarm/fire`) and become separate topic
+    /// levels. To also receive all nested broadcasts, pass a subtopic ending in the `#` wildcard
+    /// (e.g. `alarm/#`).
+    ///
+    /// # Parameters
+    /// - `homie_domain`: The Homie domain in which to subscribe to broadcasts.
+    /// - `subtopic`: The broadcast subtopic to subscribe to.
+    ///
+    /// # Returns
+    /// An iterator over a `Subscription` object for `<domain>/5/$broadcast/<subtopic>`.
+    ///
+    /// # Errors
+    /// Returns `InvalidTopicSegment` if a level of the subtopic is empty (e.g. leading, trailing or
+    /// double slashes) or contains a wildcard, other than a trailing `#` level.
+    pub fn subscribe_broadcast_subtopic<'a>(
+        &'a self,
+        homie_domain: &HomieDomain,
+        subtopic: &str,
+    ) -> Result<impl Iterator<Item = Subscription> + 'a, Homie5ProtocolError> {
+        Ok(iter::once(Subscription {
+            topic: self.broadcast_subtopic(homie_domain, subtopic)?,
+            qos: self.subscription_qos(),
+        }))
+    }
+
+    /// Unsubscribes from the broadcast messages of a single subtopic in the specified Homie domain.
+    ///
+    /// See [`Self::subscribe_broadcast_subtopic`] for how the subtopic is handled.
+    ///
+    /// # Parameters
+    /// - `homie_domain`: The Homie domain in which to unsubscribe from broadcasts.
+    /// - `subtopic`: The broadcast subtopic to unsubscribe from.
+    ///
+    /// # Returns
+    /// An iterator over an `Unsubscribe` object for `<domain>/5/$broadcast/<subtopic>`.
+    ///
+    /// # Errors
+    /// Returns `InvalidTopicSegment` if the subtopic is invalid (see
+    /// [`Self::subscribe_broadcast_subtopic`]).
+    pub fn unsubscribe_broadcast_subtopic<'a>(
+        &'a self,
+        homie_domain: &HomieDomain,
+        subtopic: &str,
+    ) -> Result<impl Iterator<Item = Unsubscribe> + 'a, Homie5ProtocolError> {
+        Ok(iter::once(Unsubscribe {
+            topic: self.broadcast_subtopic(homie_domain, subtopic)?,
+        }))
+    }
+
+    /// Builds the broadcast topic filter for `subtopic`, only the last level may be the `#`
+    /// wildcard.
+    fn broadcast_subtopic(&self, homie_domain: &HomieDomain, subtopic: &str) -> Result<String, Homie5ProtocolError> {
+        let mut topic = TopicBuilder::new(homie_domain)
+            .with_version(self.version())
+            .add_attr(HOMIE_TOPIC_BROADCAST);
+        let mut segments = subtopic.split('/').peekable();
+        while let Some(segment) = segments.next() {
+            topic = if segment == "#" && segments.peek().is_none() {
+                topic.add_attr(segment)
+            } else {
+                topic.try_add_attr(segment)?
+            };
+        }
+        Ok(topic.build())
+    }
+
     /// Unsubscribes from broadcast messages in the specified Homie domain.
     ///
     /// # Parameters
@@ -276,6 +625,7 @@ impl Homie5ControllerProtocol {
     pub fn unsubscribe_broadcast<'a>(&'a self, homie_domain: &HomieDomain) -> impl Iterator<Item = Unsubscribe> + 'a {
         iter::once(Unsubscribe {
             topic: TopicBuilder::new(homie_domain)
+                .with_version(self.version())
                 .add_attr(HOMIE_TOPIC_BROADCAST)
                 .add_attr("#")
                 .build(),
@@ -287,6 +637,7 @@ pub struct DeviceSubscriptionIterator<'a> {
     device: &'a DeviceRef,
     attributes: std::slice::Iter<'a, &'static str>,
     current_log_lvl: Option<std::slice::Iter<'a, DeviceLogLevel>>,
+    version: &'a str,
 }
 
 impl<'a> DeviceSubscriptionIterator<'a> {
@@ -295,8 +646,15 @@ impl<'a> DeviceSubscriptionIterator<'a> {
             device,
             attributes: attributes.iter(),
             current_log_lvl: None,
+            version: HOMIE_VERSION,
         }
     }
+
+    /// Overrides the homie version segment used in the generated topics.
+    pub fn with_version(mut self, version: &'a str) -> Self {
+        self.version = version;
+        self
+    }
 }
 
 impl Iterator for DeviceSubscriptionIterator<'_> {
@@ -309,6 +667,7 @@ impl Iterator for DeviceSubscriptionIterator<'_> {
                 return Some((
                     self.device
                         .to_topic()
+                        .with_version(self.version)
                         .add_attr(DEVICE_ATTRIBUTE_LOG)
                         .add_attr(level.as_str())
                         .build(),
@@ -322,14 +681,26 @@ impl Iterator for DeviceSubscriptionIterator<'_> {
         if let Some(&attribute) = self.attributes.next() {
             if attribute == DEVICE_ATTRIBUTE_ALERT {
                 return Some((
-                    self.device.to_topic().add_attr(attribute).add_attr("+").build(),
+                    self.device
+                        .to_topic()
+                        .with_version(self.version)
+                        .add_attr(attribute)
+                        .add_attr("+")
+                        .build(),
                     QoS::ExactlyOnce,
                 ));
             } else if attribute == DEVICE_ATTRIBUTE_LOG {
                 self.current_log_lvl = Some(DEVICE_LOG_LEVELS.iter());
                 return self.next(); // Recurse to process the first log level
             } else {
-                return Some((self.device.to_topic().add_attr(attribute).build(), QoS::ExactlyOnce));
+                return Some((
+                    self.device
+                        .to_topic()
+                        .with_version(self.version)
+                        .add_attr(attribute)
+                        .build(),
+                    QoS::ExactlyOnce,
+                ));
             }
         }
 
@@ -0,0 +1,137 @@
+//! Builds a model of all devices from a snapshot of retained mqtt messages.
+//!
+//! When a controller starts, the broker delivers the retained messages of all devices at once and
+//! in no particular order. [`Homie5ControllerProtocol::load_snapshot`] parses these messages and
+//! applies them in the correct order (states and descriptions before the property values, which
+//! can only be parsed with the description of their device).
+//!
+//! # Example
+//!
+//! ```rust
+//! use homie5::*;
+//!
+//! let protocol = Homie5ControllerProtocol::new();
+//! let devices = protocol.load_snapshot([
+//!     ("homie/5/device-1/$state".to_owned(), b"ready".to_vec()),
+//! ]);
+//!
+//! let device = &devices[&DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-1"))];
+//! assert_eq!(device.state, Some(HomieDeviceStatus::Ready));
+//! assert!(device.description.is_none());
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    device_description::HomieDeviceDescription, parse_mqtt_message, DeviceRef, Homie5ControllerProtocol, Homie5Message,
+    HomieDeviceStatus, HomieID, HomieValue, PropertyPointer, PropertyRef,
+};
+
+/// The state of a single device as loaded by [`Homie5ControllerProtocol::load_snapshot`].
+#[derive(Debug, Clone)]
+pub struct DeviceSnapshot {
+    /// The device the snapshot is for
+    pub device: DeviceRef,
+    /// The `$state` of the device, `None` if no state was part of the snapshot
+    pub state: Option<HomieDeviceStatus>,
+    /// The `$description` of the device, `None` if no description was part of the snapshot
+    pub description: Option<HomieDeviceDescription>,
+    /// The property values, parsed according to the description
+    pub values: BTreeMap<PropertyPointer, HomieValue>,
+    /// The property `$target` values, parsed according to the description
+    pub targets: BTreeMap<PropertyPointer, HomieValue>,
+    /// The active alerts of the device (alert id -> message)
+    pub alerts: BTreeMap<HomieID, String>,
+}
+
+impl DeviceSnapshot {
+    fn new(device: DeviceRef) -> Self {
+        Self {
+            device,
+            state: None,
+            description: None,
+            values: BTreeMap::new(),
+            targets: BTreeMap::new(),
+            alerts: BTreeMap::new(),
+        }
+    }
+}
+
+impl Homie5ControllerProtocol {
+    /// Parses a snapshot of retained mqtt messages (topic, payload) into a model of all devices.
+    ///
+    /// The devices are identified by their `DeviceRef`, so devices with the same id in different
+    /// homie domains are kept apart.
+    ///
+    /// - states, descriptions and alerts are applied first
+    /// - property values and `$target` values are parsed with the description of their device,
+    ///   values for devices without a description, for unknown properties and invalid values are
+    ///   skipped
+    /// - devices removed via an empty `$state` are not part of the result
+    ///
+    /// Messages which are not homie messages or cannot be parsed are ignored.
+    pub fn load_snapshot(
+        &self,
+        messages: impl IntoIterator<Item = (String, Vec<u8>)>,
+    ) -> HashMap<DeviceRef, DeviceSnapshot> {
+        let mut devices: HashMap<DeviceRef, DeviceSnapshot> = HashMap::new();
+        let mut values: Vec<(PropertyRef, String, bool)> = Vec::new();
+
+        let mut removed = Vec::new();
+        for (topic, payload) in messages {
+            let Ok(message) = parse_mqtt_message(&topic, &payload) else {
+                continue;
+            };
+            match message {
+                Homie5Message::DeviceState { device, state } => device_entry(&mut devices, device).state = Some(state),
+                Homie5Message::DeviceDescription { device, description } => {
+                    device_entry(&mut devices, device).description = Some(description)
+                }
+                Homie5Message::DeviceAlert {
+                    device,
+                    alert_id,
+                    alert_msg,
+                } => {
+                    device_entry(&mut devices, device).alerts.insert(alert_id, alert_msg);
+                }
+                Homie5Message::PropertyValue { property, value } => values.push((property, value, false)),
+                Homie5Message::PropertyTarget { property, target } => values.push((property, target, true)),
+                Homie5Message::DeviceRemoval { device } => removed.push(device),
+                _ => {}
+            }
+        }
+        for device in removed {
+            devices.remove(&device);
+        }
+
+        for (property, raw, is_target) in values {
+            let Some(device) = devices.get_mut(property.device_ref()) else {
+                continue;
+            };
+            let Some(prop_desc) = device
+                .description
+                .as_ref()
+                .and_then(|description| description.get_property_by_id(property.node_id(), property.prop_id()))
+            else {
+                continue;
+            };
+            let Ok(value) = HomieValue::parse(&raw, prop_desc) else {
+                continue;
+            };
+            let pointer = property.prop_pointer().clone();
+            if is_target {
+                device.targets.insert(pointer, value);
+            } else {
+                device.values.insert(pointer, value);
+            }
+        }
+
+        devices
+    }
+}
+
+fn device_entry(devices: &mut HashMap<DeviceRef, DeviceSnapshot>, device: DeviceRef) -> &mut DeviceSnapshot {
+    devices
+        .entry(device.clone())
+        .or_insert_with(|| DeviceSnapshot::new(device))
+}
@@ -0,0 +1,109 @@
+//! Tracks when a controller last heard from its devices, e.g. for presence detection.
+//!
+//! The crate does not read any clock, the timestamps are provided by the caller when recording
+//! messages and when checking for staleness.
+//!
+//! # Example
+//!
+//! ```rust
+//! use homie5::*;
+//!
+//! let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-1"));
+//! let mut activity = DeviceActivity::new();
+//!
+//! let start = chrono::Utc::now();
+//! activity.record_value(&device, start);
+//!
+//! let max_age = chrono::Duration::minutes(5);
+//! assert!(!activity.is_stale(&device, start + chrono::Duration::minutes(1), max_age));
+//! assert!(activity.is_stale(&device, start + chrono::Duration::minutes(10), max_age));
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{DeviceRef, Homie5Message};
+
+/// The last activity recorded for a single device.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActivityRecord {
+    /// Time of the last `$state` message
+    pub last_state: Option<DateTime<Utc>>,
+    /// Time of the last property value or `$target` message
+    pub last_value: Option<DateTime<Utc>>,
+}
+
+impl ActivityRecord {
+    /// Returns the time of the latest recorded message of any kind.
+    pub fn last_seen(&self) -> Option<DateTime<Utc>> {
+        self.last_state.max(self.last_value)
+    }
+}
+
+/// Records the last state and value message times per device.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceActivity {
+    devices: HashMap<DeviceRef, ActivityRecord>,
+}
+
+impl DeviceActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a `$state` message of the device at `now`.
+    pub fn record_state(&mut self, device: &DeviceRef, now: DateTime<Utc>) {
+        self.devices.entry(device.clone()).or_default().last_state = Some(now);
+    }
+
+    /// Records a property value or `$target` message of the device at `now`.
+    pub fn record_value(&mut self, device: &DeviceRef, now: DateTime<Utc>) {
+        self.devices.entry(device.clone()).or_default().last_value = Some(now);
+    }
+
+    /// Records a received message at `now`.
+    ///
+    /// Only state, property value and property target messages are recorded. Returns true if the
+    /// message was recorded.
+    pub fn record_message(&mut self, msg: &Homie5Message, now: DateTime<Utc>) -> bool {
+        match msg {
+            Homie5Message::DeviceState { device, .. } => self.record_state(device, now),
+            Homie5Message::PropertyValue { property, .. } | Homie5Message::PropertyTarget { property, .. } => {
+                self.record_value(property.device_ref(), now)
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Returns the recorded activity for the device.
+    pub fn get(&self, device: &DeviceRef) -> Option<&ActivityRecord> {
+        self.devices.get(device)
+    }
+
+    /// Returns the time of the latest recorded message of the device.
+    pub fn last_seen(&self, device: &DeviceRef) -> Option<DateTime<Utc>> {
+        self.devices.get(device).and_then(ActivityRecord::last_seen)
+    }
+
+    /// Returns true if nothing was heard from the device for longer than `max_age` at `now`.
+    ///
+    /// Devices without any recorded activity are always stale.
+    pub fn is_stale(&self, device: &DeviceRef, now: DateTime<Utc>, max_age: Duration) -> bool {
+        self.last_seen(device)
+            .is_none_or(|last_seen| now.signed_duration_since(last_seen) > max_age)
+    }
+
+    /// Returns all tracked devices which are stale at `now`.
+    pub fn stale_devices(&self, now: DateTime<Utc>, max_age: Duration) -> impl Iterator<Item = &DeviceRef> + '_ {
+        self.devices
+            .keys()
+            .filter(move |device| self.is_stale(device, now, max_age))
+    }
+
+    /// Stops tracking the device, e.g. after it was removed.
+    pub fn remove(&mut self, device: &DeviceRef) -> Option<ActivityRecord> {
+        self.devices.remove(device)
+    }
+}
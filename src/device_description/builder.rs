@@ -11,10 +11,11 @@
 //! ```
 use super::property_format::HomiePropertyFormat;
 use super::{
-    HomieDeviceDescription, HomieNodeDescription, HomiePropertyDescription, RETAINTED_DEFAULT, SETTABLE_DEFAULT,
+    HomieDeviceDescription, HomieNodeDescription, HomieNodes, HomiePropertyDescription, RETAINTED_DEFAULT,
+    SETTABLE_DEFAULT,
 };
-use crate::{HomieDataType, HomieID, HOMIE_VERSION_FULL};
-use std::collections::{BTreeMap, btree_map};
+use crate::{Homie5ProtocolError, HomieDataType, HomieID, HOMIE_VERSION_FULL};
+use std::collections::{btree_map, BTreeMap};
 
 /// Builder for constructing `HomieDeviceDescription` objects.
 ///
@@ -45,8 +46,10 @@ impl Default for DeviceDescriptionBuilder {
                 children: Vec::new(),
                 extensions: Vec::new(),
                 nodes: BTreeMap::new(),
+                node_order: Vec::new(),
                 parent: None,
                 root: None,
+                extra: serde_json::Map::new(),
             },
         }
     }
@@ -105,11 +108,21 @@ impl DeviceDescriptionBuilder {
         self
     }
 
+    /// Adds (or replaces) a node, new nodes are appended to the `node_order`.
     pub fn add_node(mut self, node_id: HomieID, node_desc: HomieNodeDescription) -> Self {
+        push_order(&mut self.description.node_order, &self.description.nodes, &node_id);
         self.description.nodes.insert(node_id, node_desc);
         self
     }
 
+    /// Replaces all nodes of the description with the given nodes map, the `node_order` is set
+    /// to the (sorted) order of the map.
+    pub fn nodes(mut self, nodes: HomieNodes) -> Self {
+        self.description.node_order = nodes.keys().cloned().collect();
+        self.description.nodes = nodes;
+        self
+    }
+
     pub fn do_if(self, condition: bool, cb: impl FnOnce(Self) -> Self) -> Self {
         if condition {
             cb(self)
@@ -118,8 +131,27 @@ impl DeviceDescriptionBuilder {
         }
     }
 
+    /// Applies `cb` with the contained value if `value` is `Some`.
+    ///
+    /// ```rust
+    /// use homie5::device_description::DeviceDescriptionBuilder;
+    ///
+    /// let name: Option<&str> = Some("My Device");
+    /// let description = DeviceDescriptionBuilder::new()
+    ///     .do_if_some(name, |builder, name| builder.name(name))
+    ///     .build();
+    /// assert_eq!(description.name.as_deref(), Some("My Device"));
+    /// ```
+    pub fn do_if_some<T>(self, value: Option<T>, cb: impl FnOnce(Self, T) -> Self) -> Self {
+        match value {
+            Some(value) => cb(self, value),
+            None => self,
+        }
+    }
+
     pub fn remove_node(mut self, node_id: &HomieID) -> Self {
         self.description.nodes.remove(node_id);
+        self.description.node_order.retain(|id| id != node_id);
         self
     }
 
@@ -128,6 +160,7 @@ impl DeviceDescriptionBuilder {
         node_id: HomieID,
         f: impl FnOnce(Option<&HomieNodeDescription>) -> HomieNodeDescription,
     ) -> Self {
+        push_order(&mut self.description.node_order, &self.description.nodes, &node_id);
         let entry = self.description.nodes.entry(node_id);
         match entry {
             btree_map::Entry::Occupied(mut oe) => {
@@ -169,6 +202,7 @@ impl Default for NodeDescriptionBuilder {
                 name: None,
                 r#type: None,
                 properties: BTreeMap::new(),
+                property_order: Vec::new(),
             },
         }
     }
@@ -200,7 +234,13 @@ impl NodeDescriptionBuilder {
         self
     }
 
+    /// Adds (or replaces) a property, new properties are appended to the `property_order`.
     pub fn add_property(mut self, prop_id: HomieID, property_desc: HomiePropertyDescription) -> Self {
+        push_order(
+            &mut self.description.property_order,
+            &self.description.properties,
+            &prop_id,
+        );
         self.description.properties.insert(prop_id, property_desc);
         self
     }
@@ -213,6 +253,24 @@ impl NodeDescriptionBuilder {
         }
     }
 
+    /// Applies `cb` with the contained value if `value` is `Some`.
+    ///
+    /// ```rust
+    /// use homie5::device_description::NodeDescriptionBuilder;
+    ///
+    /// let node_type: Option<&str> = None;
+    /// let node = NodeDescriptionBuilder::new()
+    ///     .do_if_some(node_type, |builder, node_type| builder.r#type(node_type))
+    ///     .build();
+    /// assert_eq!(node.r#type, None);
+    /// ```
+    pub fn do_if_some<T>(self, value: Option<T>, cb: impl FnOnce(Self, T) -> Self) -> Self {
+        match value {
+            Some(value) => cb(self, value),
+            None => self,
+        }
+    }
+
     pub fn add_property_cond(
         mut self,
         prop_id: HomieID,
@@ -220,13 +278,14 @@ impl NodeDescriptionBuilder {
         property_desc: impl FnOnce() -> HomiePropertyDescription,
     ) -> Self {
         if condition {
-            self.description.properties.insert(prop_id, property_desc());
+            self = self.add_property(prop_id, property_desc());
         }
         self
     }
 
     pub fn remove_property(mut self, prop_id: &HomieID) -> Self {
         self.description.properties.remove(prop_id);
+        self.description.property_order.retain(|id| id != prop_id);
         self
     }
 
@@ -235,6 +294,11 @@ impl NodeDescriptionBuilder {
         prop_id: HomieID,
         f: impl FnOnce(Option<&HomiePropertyDescription>) -> HomiePropertyDescription,
     ) -> Self {
+        push_order(
+            &mut self.description.property_order,
+            &self.description.properties,
+            &prop_id,
+        );
         let entry = self.description.properties.entry(prop_id);
         match entry {
             btree_map::Entry::Occupied(mut oe) => {
@@ -251,6 +315,19 @@ impl NodeDescriptionBuilder {
     }
 }
 
+/// Appends `id` to an order list unless it is already part of it.
+///
+/// Without a declared order the existing entries are presented in id order, they are recorded
+/// first so that `id` is appended after them.
+fn push_order<V>(order: &mut Vec<HomieID>, map: &BTreeMap<HomieID, V>, id: &HomieID) {
+    if order.is_empty() {
+        order.extend(map.keys().cloned());
+    }
+    if !order.contains(id) {
+        order.push(id.clone());
+    }
+}
+
 /// Builder for constructing `HomiePropertyDescription` objects.
 ///
 /// The `PropertyDescriptionBuilder` is designed for constructing `HomiePropertyDescription`
@@ -300,10 +377,57 @@ impl PropertyDescriptionBuilder {
             self
         }
     }
+
+    /// Applies `cb` with the contained value if `value` is `Some`.
+    ///
+    /// ```rust
+    /// use homie5::device_description::PropertyDescriptionBuilder;
+    /// use homie5::{HomieDataType, HOMIE_UNIT_DEGREE_CELSIUS};
+    ///
+    /// let maybe_unit = Some(HOMIE_UNIT_DEGREE_CELSIUS);
+    /// let property = PropertyDescriptionBuilder::new(HomieDataType::Float)
+    ///     .do_if_some(maybe_unit, |builder, unit| builder.unit(unit))
+    ///     .build();
+    /// assert_eq!(property.unit.as_deref(), Some(HOMIE_UNIT_DEGREE_CELSIUS));
+    /// ```
+    pub fn do_if_some<T>(self, value: Option<T>, cb: impl FnOnce(Self, T) -> Self) -> Self {
+        match value {
+            Some(value) => cb(self, value),
+            None => self,
+        }
+    }
+    /// Builds the property description.
+    ///
+    /// # Panics
+    /// In debug builds this panics if the datatype requires a format which is not set (see
+    /// [`Self::try_build`]). Release builds return the description unchecked.
     pub fn build(self) -> HomiePropertyDescription {
+        if cfg!(debug_assertions) {
+            return self.try_build().unwrap_or_else(|err| panic!("{}", err));
+        }
         self.description
     }
 
+    /// Builds the property description, checking that a format is set for datatypes requiring
+    /// one (`enum` and `color`).
+    ///
+    /// # Errors
+    /// Returns `Homie5ProtocolError::MissingRequiredFormat` if the datatype requires a format
+    /// but none is set.
+    ///
+    /// ```rust
+    /// use homie5::device_description::PropertyDescriptionBuilder;
+    /// use homie5::HomieDataType;
+    ///
+    /// assert!(PropertyDescriptionBuilder::new(HomieDataType::Enum).try_build().is_err());
+    /// ```
+    pub fn try_build(self) -> Result<HomiePropertyDescription, Homie5ProtocolError> {
+        if self.description.is_missing_required_format() {
+            return Err(Homie5ProtocolError::MissingRequiredFormat(self.description.datatype));
+        }
+        Ok(self.description)
+    }
+
     pub fn format(mut self, format: HomiePropertyFormat) -> Self {
         self.description.format = format;
         self
@@ -333,4 +457,24 @@ impl PropertyDescriptionBuilder {
         self.description.datatype = datatype;
         self
     }
+
+    /// Presets a controllable property: the given `datatype` and `unit`, settable and retained.
+    ///
+    /// ```rust
+    /// use homie5::device_description::PropertyDescriptionBuilder;
+    /// use homie5::{HomieDataType, HOMIE_UNIT_PERCENT};
+    ///
+    /// let brightness = PropertyDescriptionBuilder::new(HomieDataType::Integer)
+    ///     .controllable(HomieDataType::Integer, HOMIE_UNIT_PERCENT)
+    ///     .build();
+    /// assert!(brightness.settable && brightness.retained);
+    /// ```
+    pub fn controllable(self, datatype: HomieDataType, unit: impl Into<String>) -> Self {
+        self.datatype(datatype).settable(true).retained(true).unit(unit.into())
+    }
+
+    /// Presets a read-only property: the given `datatype` and `unit`, not settable and retained.
+    pub fn readonly(self, datatype: HomieDataType, unit: impl Into<String>) -> Self {
+        self.datatype(datatype).settable(false).retained(true).unit(unit.into())
+    }
 }
@@ -0,0 +1,99 @@
+//! Comparison of two versions of a device description, e.g. before and after a reconfiguration.
+
+use super::HomieDeviceDescription;
+use crate::{HomieID, PropertyPointer};
+
+/// The differences between two device descriptions as returned by [`HomieDeviceDescription::diff`].
+///
+/// All lists are sorted by id.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DescriptionDiff {
+    /// Nodes which only exist in the new description
+    pub added_nodes: Vec<HomieID>,
+    /// Nodes which only exist in the old description
+    pub removed_nodes: Vec<HomieID>,
+    /// Nodes which exist in both descriptions but have a different name or type
+    pub changed_nodes: Vec<HomieID>,
+    /// Properties which only exist in the new description (including those of added nodes)
+    pub added_properties: Vec<PropertyPointer>,
+    /// Properties which only exist in the old description (including those of removed nodes)
+    pub removed_properties: Vec<PropertyPointer>,
+    /// Properties which exist in both descriptions but with a different description
+    pub changed_properties: Vec<PropertyPointer>,
+}
+
+impl DescriptionDiff {
+    /// Returns true if the descriptions have the same nodes and properties.
+    ///
+    /// Differences in device attributes (e.g. the name or the version) are not part of the diff.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_properties.is_empty()
+            && self.removed_properties.is_empty()
+            && self.changed_properties.is_empty()
+    }
+}
+
+impl HomieDeviceDescription {
+    /// Returns true if the set of node and property ids differs from the `other` description.
+    ///
+    /// This is a cheaper check than [`Self::diff`] for the question whether the property topics
+    /// of a device changed at all. Changes of names, formats or other attributes are ignored.
+    pub fn topics_changed(&self, other: &HomieDeviceDescription) -> bool {
+        self.nodes.len() != other.nodes.len()
+            || self
+                .nodes
+                .iter()
+                .zip(other.nodes.iter())
+                .any(|((id, node), (other_id, other_node))| {
+                    id != other_id || !node.properties.keys().eq(other_node.properties.keys())
+                })
+    }
+
+    /// Compares the nodes and properties of this (old) description with the `other` (new)
+    /// description.
+    pub fn diff(&self, other: &HomieDeviceDescription) -> DescriptionDiff {
+        let mut diff = DescriptionDiff::default();
+
+        for (node_id, node) in self.nodes.iter() {
+            match other.nodes.get(node_id) {
+                None => diff.removed_nodes.push(node_id.clone()),
+                Some(other_node) if node.name != other_node.name || node.r#type != other_node.r#type => {
+                    diff.changed_nodes.push(node_id.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added_nodes = other
+            .nodes
+            .keys()
+            .filter(|node_id| !self.nodes.contains_key(*node_id))
+            .cloned()
+            .collect();
+
+        for (node_id, _, prop_id, prop) in self.iter() {
+            match other.get_property_by_id(node_id, prop_id) {
+                None => diff
+                    .removed_properties
+                    .push(PropertyPointer::new(node_id.clone(), prop_id.clone())),
+                Some(other_prop) if other_prop != prop => diff
+                    .changed_properties
+                    .push(PropertyPointer::new(node_id.clone(), prop_id.clone())),
+                Some(_) => {}
+            }
+        }
+        diff.added_properties = other
+            .iter()
+            .filter(|(node_id, _, prop_id, _)| self.get_property_by_id(node_id, prop_id).is_none())
+            .map(|(node_id, _, prop_id, _)| PropertyPointer::new(node_id.clone(), prop_id.clone()))
+            .collect();
+
+        // the property iterators follow the declared order, the diff is sorted by id
+        diff.removed_properties.sort();
+        diff.changed_properties.sort();
+        diff.added_properties.sort();
+        diff
+    }
+}
@@ -0,0 +1,192 @@
+//! Best-effort parsing of device descriptions for diagnostic tooling.
+//!
+//! Real world devices do not always publish descriptions conforming to the homie convention. For
+//! tools like broker inspectors it is more useful to display everything that can be parsed and
+//! report the problems than to reject the whole description.
+
+use std::fmt::Display;
+
+use serde_json::Value;
+
+use super::{HomieDeviceDescription, HomieNodeDescription, HomiePropertyDescription, HomiePropertyFormat};
+use crate::{HomieDataType, HomieID};
+
+/// A problem found while leniently parsing a device description.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DescriptionIssue {
+    /// The node the issue was found in, `None` for device level issues
+    pub node_id: Option<String>,
+    /// The property the issue was found in, `None` for device and node level issues
+    pub prop_id: Option<String>,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl DescriptionIssue {
+    fn new(node_id: Option<&str>, prop_id: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            node_id: node_id.map(|id| id.to_owned()),
+            prop_id: prop_id.map(|id| id.to_owned()),
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for DescriptionIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.node_id, &self.prop_id) {
+            (Some(node_id), Some(prop_id)) => write!(f, "{}/{}: {}", node_id, prop_id, self.message),
+            (Some(node_id), None) => write!(f, "{}: {}", node_id, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl HomieDeviceDescription {
+    /// Parses a `$description` payload on a best-effort basis.
+    ///
+    /// Unlike deserializing with `serde_json` this does not fail on the first error:
+    /// - properties with an invalid format are kept with the raw format as `HomiePropertyFormat::Custom`
+    /// - properties with an unknown datatype (e.g. introduced by a newer version of the convention)
+    ///   are kept as `string` properties with the raw format as `HomiePropertyFormat::Custom`, so
+    ///   their values are available as raw strings
+    /// - properties and nodes which cannot be parsed at all, or have an invalid id, are skipped
+    /// - if the device level attributes are invalid a default description is used
+    ///
+    /// All problems encountered are returned alongside the description.
+    pub fn from_json_lenient(json: &str) -> (Self, Vec<DescriptionIssue>) {
+        let mut issues = Vec::new();
+
+        let mut root = match serde_json::from_str::<Value>(json) {
+            Ok(Value::Object(root)) => root,
+            Ok(_) => {
+                issues.push(DescriptionIssue::new(None, None, "description is not a json object"));
+                return (Self::default(), issues);
+            }
+            Err(err) => {
+                issues.push(DescriptionIssue::new(None, None, err.to_string()));
+                return (Self::default(), issues);
+            }
+        };
+
+        let nodes = root.remove("nodes");
+        let mut description =
+            serde_json::from_value::<HomieDeviceDescription>(Value::Object(root)).unwrap_or_else(|err| {
+                issues.push(DescriptionIssue::new(None, None, err.to_string()));
+                Self::default()
+            });
+
+        match nodes {
+            Some(Value::Object(nodes)) => {
+                for (node_id, node) in nodes {
+                    if let Some((node_id, node)) = parse_node_lenient(node_id, node, &mut issues) {
+                        description.nodes.insert(node_id, node);
+                    }
+                }
+            }
+            Some(Value::Null) | None => {}
+            Some(_) => issues.push(DescriptionIssue::new(None, None, "nodes is not a json object")),
+        }
+
+        (description, issues)
+    }
+}
+
+fn parse_node_lenient(
+    node_id: String,
+    node: Value,
+    issues: &mut Vec<DescriptionIssue>,
+) -> Option<(HomieID, HomieNodeDescription)> {
+    let node_id = match HomieID::try_from(node_id.clone()) {
+        Ok(id) => id,
+        Err(err) => {
+            issues.push(DescriptionIssue::new(Some(&node_id), None, err.to_string()));
+            return None;
+        }
+    };
+    let Value::Object(mut node) = node else {
+        issues.push(DescriptionIssue::new(
+            Some(node_id.as_str()),
+            None,
+            "node is not a json object",
+        ));
+        return None;
+    };
+
+    let properties = node.remove("properties");
+    let mut node_desc = match serde_json::from_value::<HomieNodeDescription>(Value::Object(node)) {
+        Ok(node_desc) => node_desc,
+        Err(err) => {
+            issues.push(DescriptionIssue::new(Some(node_id.as_str()), None, err.to_string()));
+            return None;
+        }
+    };
+
+    match properties {
+        Some(Value::Object(properties)) => {
+            for (prop_id, prop) in properties {
+                if let Some((prop_id, prop)) = parse_property_lenient(&node_id, prop_id, prop, issues) {
+                    node_desc.properties.insert(prop_id, prop);
+                }
+            }
+        }
+        Some(Value::Null) | None => {}
+        Some(_) => issues.push(DescriptionIssue::new(
+            Some(node_id.as_str()),
+            None,
+            "properties is not a json object",
+        )),
+    }
+
+    Some((node_id, node_desc))
+}
+
+fn parse_property_lenient(
+    node_id: &HomieID,
+    prop_id: String,
+    prop: Value,
+    issues: &mut Vec<DescriptionIssue>,
+) -> Option<(HomieID, HomiePropertyDescription)> {
+    let node_id = node_id.as_str();
+    let prop_id = match HomieID::try_from(prop_id.clone()) {
+        Ok(id) => id,
+        Err(err) => {
+            issues.push(DescriptionIssue::new(Some(node_id), Some(&prop_id), err.to_string()));
+            return None;
+        }
+    };
+    let Value::Object(mut prop) = prop else {
+        issues.push(DescriptionIssue::new(
+            Some(node_id),
+            Some(prop_id.as_str()),
+            "property is not a json object",
+        ));
+        return None;
+    };
+
+    match serde_json::from_value::<HomiePropertyDescription>(Value::Object(prop.clone())) {
+        Ok(prop_desc) => return Some((prop_id, prop_desc)),
+        Err(err) => issues.push(DescriptionIssue::new(
+            Some(node_id),
+            Some(prop_id.as_str()),
+            err.to_string(),
+        )),
+    }
+
+    // keep properties of an unknown datatype as string properties
+    if let Some(Value::String(datatype)) = prop.get("datatype") {
+        if datatype.parse::<HomieDataType>().is_err() {
+            prop.insert("datatype".to_owned(), Value::String(HomieDataType::String.to_string()));
+        }
+    }
+
+    // retry without the format and keep the raw format as custom format
+    let format = prop.remove("format");
+    let Ok(mut prop_desc) = serde_json::from_value::<HomiePropertyDescription>(Value::Object(prop)) else {
+        return None;
+    };
+    if let Some(Value::String(format)) = format {
+        prop_desc.format = HomiePropertyFormat::Custom(format);
+    }
+    Some((prop_id, prop_desc))
+}
@@ -1,9 +1,7 @@
 //! This module provides all types and tools to create (builders) and manage homie device, node and property
 //! descriptions.
-use std::collections::hash_map::DefaultHasher;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::hash::Hash;
-use std::hash::Hasher;
 use std::iter::Iterator;
 
 use serde::{Deserialize, Deserializer, Serialize};
@@ -11,15 +9,25 @@ use serde::{Deserialize, Deserializer, Serialize};
 use crate::AsNodeId;
 use crate::AsPropPointer;
 use crate::PropertyPointer;
-use crate::{HomieDataType, HomieID, PropertyRef};
+use crate::{
+    DeviceFingerprint, DeviceRef, Homie5ProtocolError, HomieColorValue, HomieDataType, HomieDomain, HomieID,
+    HomieValue, PropertyRef, ToTopic, DEVICE_ATTRIBUTE_DESCRIPTION, DEVICE_ATTRIBUTE_STATE, HOMIE_UNITS, HOMIE_VERSION,
+    PROPERTY_ATTRIBUTE_TARGET, PROPERTY_SET_TOPIC,
+};
 
 mod builder;
-mod number_ranges;
+mod diff;
+mod lenient;
+pub mod number_ranges;
 mod property_format;
+mod snapshot;
 
 pub use builder::*;
+pub use diff::*;
+pub use lenient::*;
 pub use number_ranges::*;
 pub use property_format::*;
+pub use snapshot::*;
 
 pub const SETTABLE_DEFAULT: bool = false;
 pub const RETAINTED_DEFAULT: bool = true;
@@ -135,6 +143,81 @@ impl<'de> Deserialize<'de> for HomiePropertyDescription {
         })
     }
 }
+
+impl HomiePropertyDescription {
+    /// Compares all attributes of the descriptions except for the friendly `name`.
+    ///
+    /// Properties which only differ in their name behave identically for controlling purposes.
+    pub fn eq_ignoring_name(&self, other: &Self) -> bool {
+        self.datatype == other.datatype
+            && self.format == other.format
+            && self.settable == other.settable
+            && self.retained == other.retained
+            && self.unit == other.unit
+    }
+
+    /// Returns true if the property has no unit or one of the units recommended by the homie
+    /// convention (see [`crate::HOMIE_UNITS`]).
+    ///
+    /// This is advisory only, custom units are allowed by the convention. It helps to catch
+    /// typos like `celsius` instead of `°C`.
+    pub fn is_recommended_unit(&self) -> bool {
+        self.unit.as_deref().is_none_or(|unit| HOMIE_UNITS.contains(&unit))
+    }
+
+    /// Returns true if the datatype of the property requires a format, but none is set.
+    ///
+    /// The convention requires a format for `enum` (the list of values) and `color` (the
+    /// supported color formats) properties.
+    pub fn is_missing_required_format(&self) -> bool {
+        matches!(
+            (&self.datatype, &self.format),
+            (HomieDataType::Enum | HomieDataType::Color, HomiePropertyFormat::Empty)
+        )
+    }
+
+    /// Returns a sensible value to display for the property before any value was received, e.g.
+    /// to pre-populate a form.
+    ///
+    /// - integer/float: the `min` of the range, the `max` if only that is set, `0` otherwise
+    /// - boolean: `false`
+    /// - string: an empty string
+    /// - enum: the first enum value
+    /// - color: black in the first supported color format (white for xyz)
+    /// - duration: a zero duration
+    ///
+    /// Returns `None` if there is no sensible placeholder (datetime, json or formats without
+    /// values).
+    pub fn placeholder_value(&self) -> Option<HomieValue> {
+        match (&self.datatype, &self.format) {
+            (HomieDataType::Integer, HomiePropertyFormat::IntegerRange(range)) => {
+                Some(HomieValue::Integer(range.min.or(range.max).unwrap_or(0)))
+            }
+            (HomieDataType::Integer, _) => Some(HomieValue::Integer(0)),
+            (HomieDataType::Float, HomiePropertyFormat::FloatRange(range)) => {
+                Some(HomieValue::Float(range.min.or(range.max).unwrap_or(0.0)))
+            }
+            (HomieDataType::Float, _) => Some(HomieValue::Float(0.0)),
+            (HomieDataType::Boolean, _) => Some(HomieValue::Bool(false)),
+            (HomieDataType::String, _) => Some(HomieValue::String(String::new())),
+            (HomieDataType::Enum, HomiePropertyFormat::Enum(values)) => {
+                values.first().map(|value| HomieValue::Enum(value.clone()))
+            }
+            (HomieDataType::Color, HomiePropertyFormat::Color(formats)) => {
+                property_format::canonical_color_formats(formats).next().map(|format| {
+                    HomieValue::Color(match format {
+                        ColorFormat::Rgb => HomieColorValue::RGB(0, 0, 0),
+                        ColorFormat::Hsv => HomieColorValue::HSV(0, 0, 0),
+                        // xyz has no brightness, use the D65 white point instead
+                        ColorFormat::Xyz => HomieColorValue::new_xyz(0.3127, 0.329),
+                    })
+                })
+            }
+            (HomieDataType::Duration, _) => Some(HomieValue::Duration(chrono::Duration::zero())),
+            _ => None,
+        }
+    }
+}
 /// HomieNodeDescription
 ///
 /// The Node object has the following fields:
@@ -166,8 +249,32 @@ pub struct HomieNodeDescription {
     pub r#type: Option<String>,
     #[serde(default, skip_serializing_if = "serde_skip_if_properties")]
     pub properties: BTreeMap<HomieID, HomiePropertyDescription>,
+    /// Order in which the properties should be presented, the properties map itself is sorted by
+    /// id. Properties not listed follow the listed ones in id order.
+    ///
+    /// The order is published as optional `property_order` field, but it is not part of the
+    /// content hash (see [`HomieDeviceDescription::content_hash`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    property_order: Vec<HomieID>,
 }
 impl HomieNodeDescription {
+    /// Returns the declared order of the properties, empty if none is declared.
+    pub fn property_order(&self) -> &[HomieID] {
+        &self.property_order
+    }
+
+    /// Sets the declared order of the properties, ids of unknown properties and duplicates are
+    /// dropped. An empty order presents the properties in id order.
+    pub fn set_property_order(&mut self, order: impl IntoIterator<Item = HomieID>) {
+        self.property_order = declared_order(&self.properties, order);
+    }
+
+    /// Returns the properties of the node in the order of `property_order`, followed by all
+    /// properties not listed there in id order.
+    pub fn ordered_properties(&self) -> impl Iterator<Item = (&HomieID, &HomiePropertyDescription)> {
+        DeclaredOrder::new(&self.properties, &self.property_order)
+    }
+
     pub fn with_property<T>(
         &self,
         property: &PropertyRef,
@@ -192,16 +299,87 @@ impl Hash for HomieNodeDescription {
         self.name.hash(state);
         self.r#type.hash(state);
 
-        // Hashing HashMap contents in a deterministic order
-        let mut keys: Vec<_> = self.properties.keys().collect();
-        keys.sort();
-        for key in keys {
+        // BTreeMap iterates in key order, so the hash is deterministic
+        for (key, value) in self.properties.iter() {
             key.hash(state);
-            self.properties.get(key).unwrap().hash(state);
+            value.hash(state);
+        }
+    }
+}
+
+/// Returns the ids of `order` which are keys of `map`, without duplicates.
+fn declared_order<V>(map: &BTreeMap<HomieID, V>, order: impl IntoIterator<Item = HomieID>) -> Vec<HomieID> {
+    let mut declared: Vec<HomieID> = Vec::new();
+    for id in order {
+        if map.contains_key(&id) && !declared.contains(&id) {
+            declared.push(id);
+        }
+    }
+    declared
+}
+
+/// The 64 bit FNV-1a hash, see <http://www.isthe.com/chongo/tech/comp/fnv/>.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl std::io::Write for Fnv1a {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for byte in buf {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Iterates the entries of `map` listed in `order` (skipping duplicates and unknown ids) followed
+/// by all other entries in key order.
+struct DeclaredOrder<'a, V> {
+    map: &'a BTreeMap<HomieID, V>,
+    order: &'a [HomieID],
+    position: usize,
+    rest: std::collections::btree_map::Iter<'a, HomieID, V>,
+}
+
+impl<'a, V> DeclaredOrder<'a, V> {
+    fn new(map: &'a BTreeMap<HomieID, V>, order: &'a [HomieID]) -> Self {
+        Self {
+            map,
+            order,
+            position: 0,
+            rest: map.iter(),
         }
     }
 }
 
+impl<'a, V> Iterator for DeclaredOrder<'a, V> {
+    type Item = (&'a HomieID, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(id) = self.order.get(self.position) {
+            let seen = &self.order[..self.position];
+            self.position += 1;
+            if seen.contains(id) {
+                continue;
+            }
+            if let Some(entry) = self.map.get_key_value(id) {
+                return Some(entry);
+            }
+        }
+        let order = self.order;
+        self.rest.find(|(id, _)| !order.contains(id))
+    }
+}
+
 #[allow(dead_code)]
 fn null_to_default<'de, D, T>(de: D) -> Result<T, D::Error>
 where
@@ -212,11 +390,13 @@ where
     Ok(key.unwrap_or_default())
 }
 
-/// If the properties HashMap is empty, skip serializing the field
+/// If the properties map is empty, skip serializing the field
 fn serde_skip_if_properties(properties: &BTreeMap<HomieID, HomiePropertyDescription>) -> bool {
     properties.is_empty()
 }
 
+/// Nodes of a device, kept sorted by id so that a serialized `$description` is deterministic. The
+/// order in which nodes should be presented is kept separately in `node_order`.
 pub type HomieNodes = BTreeMap<HomieID, HomieNodeDescription>;
 /// HomieDeviceDescription
 ///
@@ -266,6 +446,18 @@ pub struct HomieDeviceDescription {
     pub extensions: Vec<String>,
     #[serde(default, skip_serializing_if = "serde_skip_if_nodes")]
     pub nodes: HomieNodes,
+    /// Order in which the nodes should be presented, the nodes map itself is sorted by id. Nodes
+    /// not listed follow the listed ones in id order.
+    ///
+    /// The order is published as optional `node_order` field, but it is not part of the content
+    /// hash (see [`HomieDeviceDescription::content_hash`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    node_order: Vec<HomieID>,
+    /// Top-level fields which are not known to this crate (e.g. added by a newer homie version).
+    ///
+    /// They are kept so that a description can be parsed and re-published without losing data.
+    #[serde(flatten, default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Default for HomieDeviceDescription {
@@ -279,11 +471,113 @@ impl Default for HomieDeviceDescription {
             parent: None,
             extensions: Vec::new(),
             nodes: BTreeMap::new(),
+            node_order: Vec::new(),
+            extra: serde_json::Map::new(),
         }
     }
 }
 
 impl HomieDeviceDescription {
+    /// Creates a description with default device attributes for a prebuilt nodes map.
+    ///
+    /// This is a shortcut for `DeviceDescriptionBuilder::new().nodes(nodes).build()`.
+    pub fn with_nodes(nodes: HomieNodes) -> Self {
+        DeviceDescriptionBuilder::new().nodes(nodes).build()
+    }
+
+    /// Serializes the description to its `$description` JSON document.
+    ///
+    /// # Errors
+    /// Returns `Homie5ProtocolError::InvalidDeviceDescription` if the description cannot be serialized.
+    pub fn to_json(&self) -> Result<String, Homie5ProtocolError> {
+        serde_json::to_string(self).map_err(|_| Homie5ProtocolError::InvalidDeviceDescription)
+    }
+
+    /// Parses a description from a `$description` JSON document.
+    ///
+    /// # Errors
+    /// Returns `Homie5ProtocolError::InvalidDeviceDescription` if the document cannot be parsed or
+    /// its `homie` field is not for major version `5` (e.g. `"4.0"`).
+    pub fn from_json(json: &str) -> Result<Self, Homie5ProtocolError> {
+        let description: Self =
+            serde_json::from_str(json).map_err(|_| Homie5ProtocolError::InvalidDeviceDescription)?;
+        if description.homie.split('.').next() != Some(HOMIE_VERSION) {
+            return Err(Homie5ProtocolError::InvalidDeviceDescription);
+        }
+        Ok(description)
+    }
+
+    /// Creates a fingerprint of the description for the given device (see [`DeviceRef::fingerprint`]).
+    pub fn fingerprint(&self, device: &DeviceRef) -> DeviceFingerprint {
+        device.fingerprint(self)
+    }
+
+    /// Returns true if the extension with the given id (e.g. `org.homie.meta`) is listed in the
+    /// `extensions` of the description.
+    ///
+    /// Extensions are advertised as `<id>:<version>:[<homie versions>]`, only the id is compared.
+    pub fn has_extension(&self, id: &str) -> bool {
+        self.extensions.iter().any(|ext| ext.split(':').next() == Some(id))
+    }
+
+    /// Returns true if none of the properties is missing a required format (see
+    /// [`HomiePropertyDescription::is_missing_required_format`]).
+    pub fn has_required_formats(&self) -> bool {
+        !self.iter().any(|(_, _, _, prop)| prop.is_missing_required_format())
+    }
+
+    /// Returns true if the description is for a child device (it has a `root` set).
+    pub fn is_child(&self) -> bool {
+        self.root.is_some()
+    }
+
+    /// Returns true if the description is for a root device (it has no `root` set).
+    pub fn is_root(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns a `DeviceRef` for each of the device's children in the given homie domain.
+    pub fn child_refs<'a>(&'a self, homie_domain: &'a HomieDomain) -> impl Iterator<Item = DeviceRef> + 'a {
+        self.children
+            .iter()
+            .map(move |child_id| DeviceRef::new(homie_domain.clone(), child_id.clone()))
+    }
+
+    /// Checks that no topic published for the device or its children is published twice.
+    ///
+    /// The topics checked are the `$state` and `$description` of the device and its children
+    /// (the children are published in the same homie domain) and the value, `$target` and `set`
+    /// topics of all properties. Collisions are only possible in device trees, e.g. when a child
+    /// is listed twice or has the same id as the device itself.
+    ///
+    /// # Errors
+    /// Returns the colliding topics (sorted) if there are any.
+    pub fn check_topic_collisions(&self, device: &DeviceRef) -> Result<(), Vec<String>> {
+        let device_topics = |device: &DeviceRef| {
+            [DEVICE_ATTRIBUTE_STATE, DEVICE_ATTRIBUTE_DESCRIPTION].map(|attr| device.to_topic().add_attr(attr).build())
+        };
+
+        let mut topics = Vec::from(device_topics(device));
+        for (prop_ref, prop) in self.property_refs(device) {
+            topics.push(prop_ref.to_topic().build());
+            topics.push(prop_ref.to_topic().add_attr(PROPERTY_ATTRIBUTE_TARGET).build());
+            if prop.settable {
+                topics.push(prop_ref.to_topic().add_attr(PROPERTY_SET_TOPIC).build());
+            }
+        }
+        for child in self.child_refs(device.homie_domain()) {
+            topics.extend(device_topics(&child));
+        }
+
+        let mut seen = HashSet::new();
+        let collisions: BTreeSet<String> = topics.into_iter().filter(|topic| !seen.insert(topic.clone())).collect();
+        if collisions.is_empty() {
+            Ok(())
+        } else {
+            Err(collisions.into_iter().collect())
+        }
+    }
+
     pub fn with_node<T>(&self, node: impl AsNodeId, f: impl FnOnce(&HomieNodeDescription) -> T) -> Option<T> {
         if let Some(node) = self.nodes.get(node.as_node_id()) {
             return Some(f(node));
@@ -326,10 +620,28 @@ impl HomieDeviceDescription {
     }
 
     pub fn update_version(&mut self) {
-        let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
-        let hash = hasher.finish();
-        self.version = i64::from_ne_bytes(hash.to_ne_bytes());
+        self.version = i64::from_ne_bytes(self.content_hash().to_ne_bytes());
+    }
+
+    /// Returns a hash of the content of the description, which does not include the `version`.
+    ///
+    /// The hash is the 64 bit FNV-1a hash of the serialized `$description` (with a `version` of
+    /// `0` and without the declared node and property order). The serialization is canonical
+    /// (nodes and properties sorted by id, color formats in canonical order), so the hash is
+    /// stable across runs, platforms and Rust toolchains and can be persisted.
+    pub fn content_hash(&self) -> u64 {
+        let mut canonical = self.clone();
+        canonical.version = 0;
+        canonical.node_order.clear();
+        canonical
+            .nodes
+            .values_mut()
+            .for_each(|node| node.property_order.clear());
+        let mut hasher = Fnv1a::default();
+        // serializing a description cannot fail, all map keys are strings and the writer is
+        // infallible
+        let _ = serde_json::to_writer(&mut hasher, &canonical);
+        hasher.0
     }
 
     pub fn add_child(&mut self, child_id: HomieID) {
@@ -344,9 +656,66 @@ impl HomieDeviceDescription {
         }
     }
 
-    pub fn iter(&self) -> HomiePropertyIterator {
+    /// Returns the declared order of the nodes, empty if none is declared.
+    pub fn node_order(&self) -> &[HomieID] {
+        &self.node_order
+    }
+
+    /// Sets the declared order of the nodes, ids of unknown nodes and duplicates are dropped. An
+    /// empty order presents the nodes in id order.
+    pub fn set_node_order(&mut self, order: impl IntoIterator<Item = HomieID>) {
+        self.node_order = declared_order(&self.nodes, order);
+    }
+
+    /// Returns the nodes of the device in the order of `node_order`, followed by all nodes not
+    /// listed there in id order.
+    pub fn ordered_nodes(&self) -> impl Iterator<Item = (&HomieID, &HomieNodeDescription)> {
+        DeclaredOrder::new(&self.nodes, &self.node_order)
+    }
+
+    /// Iterates all properties of the device, nodes and properties are iterated in their declared
+    /// order (see [`Self::ordered_nodes`] and [`HomieNodeDescription::ordered_properties`]).
+    pub fn iter(&self) -> HomiePropertyIterator<'_> {
         HomiePropertyIterator::new(self)
     }
+
+    /// Iterates all properties of the device sorted by node and property id, ignoring any declared
+    /// order.
+    pub(crate) fn iter_by_id(
+        &self,
+    ) -> impl Iterator<Item = (&HomieID, &HomieNodeDescription, &HomieID, &HomiePropertyDescription)> {
+        self.nodes.iter().flat_map(|(node_id, node)| {
+            node.properties
+                .iter()
+                .map(move |(prop_id, prop)| (node_id, node, prop_id, prop))
+        })
+    }
+
+    /// Returns all properties of the description as full `PropertyRef`s for the given device.
+    pub fn property_refs<'a>(
+        &'a self,
+        device: &'a DeviceRef,
+    ) -> impl Iterator<Item = (PropertyRef, &'a HomiePropertyDescription)> + 'a {
+        self.iter().map(move |(node_id, _, prop_id, prop)| {
+            (
+                PropertyRef::new(
+                    device.homie_domain().clone(),
+                    device.device_id().clone(),
+                    node_id.clone(),
+                    prop_id.clone(),
+                ),
+                prop,
+            )
+        })
+    }
+
+    /// Returns all settable properties of the description as full `PropertyRef`s for the given device.
+    pub fn settable_properties<'a>(
+        &'a self,
+        device: &'a DeviceRef,
+    ) -> impl Iterator<Item = (PropertyRef, &'a HomiePropertyDescription)> + 'a {
+        self.property_refs(device).filter(|(_, prop)| prop.settable)
+    }
 }
 
 impl Hash for HomieDeviceDescription {
@@ -358,17 +727,20 @@ impl Hash for HomieDeviceDescription {
         self.parent.hash(state);
         self.extensions.hash(state);
 
-        // Hashing HashMap contents in a deterministic order
-        let mut keys: Vec<_> = self.nodes.keys().collect();
-        keys.sort();
-        for key in keys {
+        // BTreeMap iterates in key order, so the hash is deterministic
+        for (key, value) in self.nodes.iter() {
+            key.hash(state);
+            value.hash(state);
+        }
+        // serde_json::Map is ordered by key, so is its serialized form
+        for (key, value) in self.extra.iter() {
             key.hash(state);
-            self.nodes.get(key).unwrap().hash(state);
+            value.to_string().hash(state);
         }
     }
 }
 
-/// If the nodes HashMap is empty, skip serializing the field
+/// If the nodes map is empty, skip serializing the field
 fn serde_skip_if_nodes(nodes: &BTreeMap<HomieID, HomieNodeDescription>) -> bool {
     nodes.is_empty()
 }
@@ -382,28 +754,17 @@ pub fn serde_skip_if_empty_list<T>(children: &[T]) -> bool {
 }
 
 pub struct HomiePropertyIterator<'a> {
-    _device: &'a HomieDeviceDescription,
-    node_iter: std::collections::btree_map::Iter<'a, HomieID, HomieNodeDescription>,
+    node_iter: DeclaredOrder<'a, HomieNodeDescription>,
     current_node: Option<(&'a HomieID, &'a HomieNodeDescription)>,
-    property_iter: Option<std::collections::btree_map::Iter<'a, HomieID, HomiePropertyDescription>>,
+    property_iter: Option<DeclaredOrder<'a, HomiePropertyDescription>>,
 }
 
 impl<'a> HomiePropertyIterator<'a> {
-    pub fn new(_device: &'a HomieDeviceDescription) -> Self {
-        let mut node_iter = _device.nodes.iter();
-
-        let first_node = node_iter.next();
-
-        let (current_node_id, property_iter) = match first_node {
-            Some(node) => (Some(node), Some(node.1.properties.iter())),
-            None => (None, None),
-        };
-
+    pub fn new(device: &'a HomieDeviceDescription) -> Self {
         HomiePropertyIterator {
-            _device,
-            node_iter,
-            current_node: current_node_id,
-            property_iter,
+            node_iter: DeclaredOrder::new(&device.nodes, &device.node_order),
+            current_node: None,
+            property_iter: None,
         }
     }
 }
@@ -418,24 +779,15 @@ impl<'a> Iterator for HomiePropertyIterator<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            if let Some(iter) = self.property_iter.as_mut() {
+            if let (Some((node_id, node)), Some(iter)) = (self.current_node, self.property_iter.as_mut()) {
                 if let Some((property_id, property)) = iter.next() {
-                    return Some((
-                        self.current_node.unwrap().0,
-                        self.current_node.unwrap().1,
-                        property_id,
-                        property,
-                    ));
+                    return Some((node_id, node, property_id, property));
                 }
             }
 
-            match self.node_iter.next() {
-                Some((node_id, node)) => {
-                    self.current_node = Some((node_id, node));
-                    self.property_iter = Some(node.properties.iter())
-                }
-                None => return None,
-            }
+            let (node_id, node) = self.node_iter.next()?;
+            self.current_node = Some((node_id, node));
+            self.property_iter = Some(DeclaredOrder::new(&node.properties, &node.property_order));
         }
     }
 }
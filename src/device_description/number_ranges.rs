@@ -1,3 +1,13 @@
+//! Numeric range formats (`min:max:step`) for integer and float properties.
+//!
+//! A range consists of an optional `min`, an optional `max` and an optional `step`:
+//!
+//! - `min`/`max` are inclusive bounds, a missing bound is unbounded.
+//! - `step` defines the granularity of valid values. Values are snapped to the closest multiple
+//!   of `step` counted from a base: the `min` if set, otherwise the `max`, otherwise the value
+//!   itself (in which case no snapping happens).
+//! - Snapping happens before the bounds check, so a value slightly outside a bound may still be
+//!   accepted if it snaps onto the bound.
 use std::fmt::Display;
 use std::hash::Hash;
 
@@ -5,6 +15,7 @@ use serde::{Deserialize, Serialize};
 
 use super::property_format::HomiePropertyFormatError;
 
+/// Range format for float properties, see the [module documentation](self) for the semantics.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, PartialOrd)]
 pub struct FloatRange {
     pub min: Option<f64>,
@@ -13,21 +24,154 @@ pub struct FloatRange {
 }
 
 impl FloatRange {
+    /// Returns true if neither `min`, `max` nor `step` are set.
     pub fn is_empty(&self) -> bool {
         self.min.is_none() && self.max.is_none() && self.step.is_none()
     }
 
+    /// Returns the inclusive `(min, max)` bounds of the range, `None` meaning unbounded.
+    pub fn bounds(&self) -> (Option<f64>, Option<f64>) {
+        (self.min, self.max)
+    }
+
+    /// Returns true if the value is within the `min`/`max` bounds of the range.
+    ///
+    /// The `step` is not taken into account, use [`FloatRange::snap`] first to round the value.
+    pub fn contains(&self, value: f64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+
+    /// Rounds the value to the closest valid step.
+    ///
+    /// Steps are counted from `min` if set, otherwise from `max`. Without a `step` or without
+    /// any bound the value is returned unchanged. The result is not clamped to the bounds.
+    pub fn snap(&self, value: f64) -> f64 {
+        // Use the minimum, max, or current value as base (in that priority order)
+        let base = self.min.or(self.max).unwrap_or(value);
+        match self.step {
+            Some(step) if step > 0.0 => ((value - base) / step).round() * step + base,
+            _ => value,
+        }
+    }
+
+    /// Returns the range of values valid in both ranges, `None` if there are none.
+    ///
+    /// The result has the tighter bounds of both ranges. If both ranges have a step, the coarser
+    /// step must be a multiple of the finer step and both grids must be aligned, otherwise the
+    /// steps are incompatible and `None` is returned. The result keeps the coarser step and its
+    /// bounds are moved onto the grid of the range providing it, so the snapped values of the
+    /// result are valid in both ranges. If the bounds only leave room for a single value, the
+    /// result has no step.
+    pub fn intersect(&self, other: &FloatRange) -> Option<FloatRange> {
+        let mut min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let mut max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let grid = match (self.step, other.step) {
+            (None, None) => None,
+            (Some(_), None) => Some(self),
+            (None, Some(_)) => Some(other),
+            (Some(a), Some(b)) => {
+                let (coarse, fine) = if a >= b { (self, other) } else { (other, self) };
+                if !coarse.is_aligned_with(fine) {
+                    return None;
+                }
+                Some(coarse)
+            }
+        };
+        let step = grid.and_then(|grid| grid.step);
+
+        if let (Some(step), Some(base)) = (step, grid.and_then(|grid| grid.base())) {
+            min = min.map(|min| base + ((min - base) / step - FLOAT_STEP_EPSILON).ceil() * step);
+            max = max.map(|max| base + ((max - base) / step + FLOAT_STEP_EPSILON).floor() * step);
+        }
+
+        match (min, max, step) {
+            (Some(min), Some(max), _) if min > max => None,
+            (Some(min), Some(max), Some(step)) if step > max - min => Some(FloatRange {
+                min: Some(min),
+                max: Some(min),
+                step: None,
+            }),
+            (min, max, step) => Some(FloatRange { min, max, step }),
+        }
+    }
+
+    /// Returns the smallest range containing all values of both ranges.
+    ///
+    /// The result has the wider bounds of both ranges, so for disjoint ranges it also contains
+    /// the values in between. The finer step is kept if the steps are compatible (see
+    /// [`FloatRange::intersect`]) and the grid of the result is aligned with it, otherwise the
+    /// result has no step.
+    pub fn union(&self, other: &FloatRange) -> FloatRange {
+        let min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            _ => None,
+        };
+        let max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => None,
+        };
+        let step = match (self.step, other.step) {
+            (Some(a), Some(b)) => {
+                let (coarse, fine) = if a >= b { (self, other) } else { (other, self) };
+                let result_base = min.or(max);
+                let aligned = match (fine.base(), result_base) {
+                    (Some(fine_base), Some(result_base)) => is_float_multiple(result_base - fine_base, b.min(a)),
+                    _ => true,
+                };
+                (aligned && coarse.is_aligned_with(fine)).then_some(a.min(b))
+            }
+            _ => None,
+        };
+        FloatRange { min, max, step }
+    }
+
+    /// The value the steps are counted from.
+    fn base(&self) -> Option<f64> {
+        self.min.or(self.max)
+    }
+
+    /// Returns true if every step of `self` is also a step of `fine`.
+    fn is_aligned_with(&self, fine: &FloatRange) -> bool {
+        let (Some(coarse_step), Some(fine_step)) = (self.step, fine.step) else {
+            return false;
+        };
+        is_float_multiple(coarse_step, fine_step)
+            && match (self.base(), fine.base()) {
+                (Some(coarse_base), Some(fine_base)) => is_float_multiple(coarse_base - fine_base, fine_step),
+                _ => true,
+            }
+    }
+
+    /// Checks that the range is valid, see [`FloatRange::validate_float_range`].
+    pub fn validate(&self) -> Result<(), HomiePropertyFormatError> {
+        if Self::validate_float_range(self.min, self.max, self.step) {
+            Ok(())
+        } else {
+            Err(HomiePropertyFormatError::RangeFormatError)
+        }
+    }
+
+    /// Returns true if the range is valid: all values are finite numbers, the step is positive
+    /// and fits between the bounds and `min` is not greater than `max`.
     pub fn validate_float_range(min: Option<f64>, max: Option<f64>, step: Option<f64>) -> bool {
+        // a NaN or infinite value would silently break snapping and the bounds checks
+        if [min, max, step].into_iter().flatten().any(|value| !value.is_finite()) {
+            return false;
+        }
         if let Some(step) = step {
             if step <= 0.0 {
                 return false;
             }
         }
         match (min, max, step) {
-            (Some(min), Some(max), None) => {
-                if min > max {
-                    return false;
-                }
+            (Some(min), Some(max), None) if min > max => {
+                return false;
             }
             (Some(min), Some(max), Some(step)) => {
                 if min > max {
@@ -105,9 +249,14 @@ impl Hash for FloatRange {
 
 impl Display for FloatRange {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // an empty range has no representation and is omitted from the description, returning
+        // an error here would make `to_string()` panic
         if self.is_empty() {
-            return Err(std::fmt::Error);
+            return Ok(());
         }
+        // emit the most compact form which parses back to the same range: a min-only range needs
+        // the trailing separator to be distinguishable from a max, a step-only range needs both
+        // separators (`5:`, `:10`, `::2`, `1::2`, `:10:2`, `1:10`, `1:10:2`)
         if let Some(min) = self.min {
             if self.max.is_none() && self.step.is_none() {
                 write!(f, "{}:", min)?;
@@ -127,6 +276,7 @@ impl Display for FloatRange {
     }
 }
 
+/// Range format for integer properties, see the [module documentation](self) for the semantics.
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, PartialOrd)]
 pub struct IntegerRange {
     pub min: Option<i64>,
@@ -135,10 +285,175 @@ pub struct IntegerRange {
 }
 
 impl IntegerRange {
+    /// Returns true if neither `min`, `max` nor `step` are set.
     pub fn is_empty(&self) -> bool {
         self.min.is_none() && self.max.is_none() && self.step.is_none()
     }
 
+    /// Returns the inclusive `(min, max)` bounds of the range, `None` meaning unbounded.
+    pub fn bounds(&self) -> (Option<i64>, Option<i64>) {
+        (self.min, self.max)
+    }
+
+    /// Returns true if the value is within the `min`/`max` bounds of the range.
+    ///
+    /// The `step` is not taken into account, use [`IntegerRange::snap`] first to round the value.
+    pub fn contains(&self, value: i64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+
+    /// Rounds the value to the closest valid step.
+    ///
+    /// Steps are counted from `min` if set, otherwise from `max`. Without a `step` or without
+    /// any bound the value is returned unchanged. The result is not clamped to the bounds.
+    ///
+    /// The rounding is exact and cannot overflow for the full `i64` range. Ties are rounded away
+    /// from the base. If the closest step lies outside of the `i64` range, the closest step within
+    /// it is returned instead.
+    pub fn snap(&self, value: i64) -> i64 {
+        // Use the minimum, max, or current value as base (in that priority order)
+        let base = self.min.or(self.max).unwrap_or(value);
+        let step = match self.step {
+            Some(step) if step > 0 => i128::from(step),
+            _ => return value,
+        };
+
+        // calculate in i128 so neither the distance to the base nor the rounded result can overflow
+        let diff = i128::from(value) - i128::from(base);
+        let mut steps = diff / step;
+        let rest = diff % step;
+        if 2 * rest.abs() >= step {
+            steps += rest.signum();
+        }
+        let mut snapped = steps * step + i128::from(base);
+        if snapped > i128::from(i64::MAX) {
+            snapped -= step;
+        } else if snapped < i128::from(i64::MIN) {
+            snapped += step;
+        }
+        snapped as i64
+    }
+
+    /// Returns the range of values valid in both ranges, `None` if there are none.
+    ///
+    /// The result has the tighter bounds of both ranges. If both ranges have a step, the coarser
+    /// step must be a multiple of the finer step and both grids must be aligned, otherwise the
+    /// steps are incompatible and `None` is returned. The result keeps the coarser step and its
+    /// bounds are moved onto the grid of the range providing it, so the snapped values of the
+    /// result are valid in both ranges. If the bounds only leave room for a single value, the
+    /// result has no step.
+    pub fn intersect(&self, other: &IntegerRange) -> Option<IntegerRange> {
+        let mut min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let mut max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let grid = match (self.step, other.step) {
+            (None, None) => None,
+            (Some(_), None) => Some(self),
+            (None, Some(_)) => Some(other),
+            (Some(a), Some(b)) => {
+                let (coarse, fine) = if a >= b { (self, other) } else { (other, self) };
+                if !coarse.is_aligned_with(fine) {
+                    return None;
+                }
+                Some(coarse)
+            }
+        };
+        let step = grid.and_then(|grid| grid.step);
+
+        if let (Some(step), Some(base)) = (step, grid.and_then(|grid| grid.base())) {
+            let (step, base) = (i128::from(step), i128::from(base));
+            if let Some(value) = min {
+                let diff = i128::from(value) - base;
+                let steps = diff.div_euclid(step) + i128::from(diff.rem_euclid(step) != 0);
+                min = Some(i64::try_from(base + steps * step).ok()?);
+            }
+            if let Some(value) = max {
+                let steps = (i128::from(value) - base).div_euclid(step);
+                max = Some(i64::try_from(base + steps * step).ok()?);
+            }
+        }
+
+        match (min, max, step) {
+            (Some(min), Some(max), _) if min > max => None,
+            (Some(min), Some(max), Some(step)) if i128::from(step) > i128::from(max) - i128::from(min) => {
+                Some(IntegerRange {
+                    min: Some(min),
+                    max: Some(min),
+                    step: None,
+                })
+            }
+            (min, max, step) => Some(IntegerRange { min, max, step }),
+        }
+    }
+
+    /// Returns the smallest range containing all values of both ranges.
+    ///
+    /// The result has the wider bounds of both ranges, so for disjoint ranges it also contains
+    /// the values in between. The finer step is kept if the steps are compatible (see
+    /// [`IntegerRange::intersect`]) and the grid of the result is aligned with it, otherwise the
+    /// result has no step.
+    pub fn union(&self, other: &IntegerRange) -> IntegerRange {
+        let min = match (self.min, other.min) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            _ => None,
+        };
+        let max = match (self.max, other.max) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => None,
+        };
+        let step = match (self.step, other.step) {
+            (Some(a), Some(b)) => {
+                let (coarse, fine) = if a >= b { (self, other) } else { (other, self) };
+                let fine_step = i128::from(a.min(b));
+                let aligned = match (fine.base(), min.or(max)) {
+                    (Some(fine_base), Some(result_base)) => {
+                        (i128::from(result_base) - i128::from(fine_base)) % fine_step == 0
+                    }
+                    _ => true,
+                };
+                (aligned && coarse.is_aligned_with(fine)).then_some(a.min(b))
+            }
+            _ => None,
+        };
+        IntegerRange { min, max, step }
+    }
+
+    /// The value the steps are counted from.
+    fn base(&self) -> Option<i64> {
+        self.min.or(self.max)
+    }
+
+    /// Returns true if every step of `self` is also a step of `fine`.
+    fn is_aligned_with(&self, fine: &IntegerRange) -> bool {
+        let (Some(coarse_step), Some(fine_step)) = (self.step, fine.step) else {
+            return false;
+        };
+        let fine_step = i128::from(fine_step);
+        i128::from(coarse_step) % fine_step == 0
+            && match (self.base(), fine.base()) {
+                (Some(coarse_base), Some(fine_base)) => {
+                    (i128::from(coarse_base) - i128::from(fine_base)) % fine_step == 0
+                }
+                _ => true,
+            }
+    }
+
+    /// Checks that the range is valid, see [`IntegerRange::validate_integer_range`].
+    pub fn validate(&self) -> Result<(), HomiePropertyFormatError> {
+        if Self::validate_integer_range(self.min, self.max, self.step) {
+            Ok(())
+        } else {
+            Err(HomiePropertyFormatError::RangeFormatError)
+        }
+    }
+
+    /// Returns true if the range is valid: the step is positive and fits between the bounds and
+    /// `min` is not greater than `max`.
     pub fn validate_integer_range(min: Option<i64>, max: Option<i64>, step: Option<i64>) -> bool {
         if let Some(step) = step {
             if step <= 0 {
@@ -146,16 +461,15 @@ impl IntegerRange {
             }
         }
         match (min, max, step) {
-            (Some(min), Some(max), None) => {
-                if min > max {
-                    return false;
-                }
+            (Some(min), Some(max), None) if min > max => {
+                return false;
             }
             (Some(min), Some(max), Some(step)) => {
                 if min > max {
                     return false;
                 }
-                if step > max - min {
+                // the span of the full i64 range does not fit into an i64
+                if i128::from(step) > i128::from(max) - i128::from(min) {
                     return false;
                 }
             }
@@ -207,9 +521,14 @@ impl IntegerRange {
 
 impl Display for IntegerRange {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // an empty range has no representation and is omitted from the description, returning
+        // an error here would make `to_string()` panic
         if self.is_empty() {
-            return Err(std::fmt::Error);
+            return Ok(());
         }
+        // emit the most compact form which parses back to the same range: a min-only range needs
+        // the trailing separator to be distinguishable from a max, a step-only range needs both
+        // separators (`5:`, `:10`, `::2`, `1::2`, `:10:2`, `1:10`, `1:10:2`)
         if let Some(min) = self.min {
             if self.max.is_none() && self.step.is_none() {
                 write!(f, "{}:", min)?;
@@ -228,3 +547,12 @@ impl Display for IntegerRange {
         Ok(())
     }
 }
+
+/// Tolerance (relative to the step) for floating point rounding errors when comparing steps
+const FLOAT_STEP_EPSILON: f64 = 1e-9;
+
+/// Returns true if `value` is a whole multiple of `step`, allowing for floating point rounding errors.
+fn is_float_multiple(value: f64, step: f64) -> bool {
+    let steps = value / step;
+    (steps - steps.round()).abs() < FLOAT_STEP_EPSILON
+}
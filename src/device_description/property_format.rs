@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::fmt::Display;
 use std::hash::Hash;
 use std::iter::Iterator;
@@ -10,7 +11,13 @@ use crate::HomieDataType;
 
 use super::number_ranges::{FloatRange, IntegerRange};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, PartialOrd)]
+/// The format of a property.
+///
+/// Color formats have no semantic order, so they are always serialized, hashed and compared in the
+/// canonical order of [`ColorFormat::ALL`]. This keeps the `$description` and the version derived
+/// from it stable regardless of the order the formats were listed in. Enum values keep their order
+/// as it may be meaningful to the device (e.g. the order a controller presents them in).
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum HomiePropertyFormat {
     FloatRange(FloatRange),
     IntegerRange(IntegerRange),
@@ -40,6 +47,107 @@ impl HomiePropertyFormat {
     }
 }
 
+impl HomiePropertyFormat {
+    /// Checks that the format is valid, e.g. for a manually constructed format.
+    ///
+    /// Formats created by [`HomiePropertyFormat::parse`] are always valid. Currently only number
+    /// ranges are checked (see [`FloatRange::validate`] and [`IntegerRange::validate`]), a range
+    /// with a non-positive or non-finite step would otherwise be silently ignored.
+    pub fn validate(&self) -> Result<(), HomiePropertyFormatError> {
+        match self {
+            HomiePropertyFormat::FloatRange(range) => range.validate(),
+            HomiePropertyFormat::IntegerRange(range) => range.validate(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns true if the format can be used for a property of the given datatype.
+    ///
+    /// Each format variant belongs to exactly one datatype (e.g. `FloatRange` to `Float`),
+    /// `Empty` and `Custom` formats are compatible with any datatype.
+    pub fn is_compatible_with(&self, datatype: &HomieDataType) -> bool {
+        matches!(
+            (self, datatype),
+            (HomiePropertyFormat::Empty | HomiePropertyFormat::Custom(_), _)
+                | (HomiePropertyFormat::FloatRange(_), HomieDataType::Float)
+                | (HomiePropertyFormat::IntegerRange(_), HomieDataType::Integer)
+                | (HomiePropertyFormat::Enum(_), HomieDataType::Enum)
+                | (HomiePropertyFormat::Color(_), HomieDataType::Color)
+                | (HomiePropertyFormat::Boolean { .. }, HomieDataType::Boolean)
+                | (HomiePropertyFormat::Json(_), HomieDataType::JSON)
+        )
+    }
+}
+
+impl Hash for HomiePropertyFormat {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            HomiePropertyFormat::FloatRange(r) => r.hash(state),
+            HomiePropertyFormat::IntegerRange(r) => r.hash(state),
+            HomiePropertyFormat::Enum(values) => values.hash(state),
+            HomiePropertyFormat::Color(formats) => canonical_color_formats(formats).for_each(|c| c.hash(state)),
+            HomiePropertyFormat::Boolean { false_val, true_val } => {
+                false_val.hash(state);
+                true_val.hash(state);
+            }
+            HomiePropertyFormat::Json(data) => data.hash(state),
+            HomiePropertyFormat::Custom(data) => data.hash(state),
+            HomiePropertyFormat::Empty => {}
+        }
+    }
+}
+
+impl PartialEq for HomiePropertyFormat {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for HomiePropertyFormat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (HomiePropertyFormat::FloatRange(a), HomiePropertyFormat::FloatRange(b)) => a.partial_cmp(b),
+            (HomiePropertyFormat::IntegerRange(a), HomiePropertyFormat::IntegerRange(b)) => a.partial_cmp(b),
+            (HomiePropertyFormat::Enum(a), HomiePropertyFormat::Enum(b)) => a.partial_cmp(b),
+            (HomiePropertyFormat::Color(a), HomiePropertyFormat::Color(b)) => {
+                canonical_color_formats(a).partial_cmp(canonical_color_formats(b))
+            }
+            (
+                HomiePropertyFormat::Boolean { false_val, true_val },
+                HomiePropertyFormat::Boolean {
+                    false_val: other_false_val,
+                    true_val: other_true_val,
+                },
+            ) => (false_val, true_val).partial_cmp(&(other_false_val, other_true_val)),
+            (HomiePropertyFormat::Json(a), HomiePropertyFormat::Json(b)) => a.partial_cmp(b),
+            (HomiePropertyFormat::Custom(a), HomiePropertyFormat::Custom(b)) => a.partial_cmp(b),
+            // different variants are ordered by their declaration order
+            _ => self.variant_index().partial_cmp(&other.variant_index()),
+        }
+    }
+}
+
+impl HomiePropertyFormat {
+    fn variant_index(&self) -> u8 {
+        match self {
+            HomiePropertyFormat::FloatRange(_) => 0,
+            HomiePropertyFormat::IntegerRange(_) => 1,
+            HomiePropertyFormat::Enum(_) => 2,
+            HomiePropertyFormat::Color(_) => 3,
+            HomiePropertyFormat::Boolean { .. } => 4,
+            HomiePropertyFormat::Json(_) => 5,
+            HomiePropertyFormat::Custom(_) => 6,
+            HomiePropertyFormat::Empty => 7,
+        }
+    }
+}
+
+/// Iterates the given color formats in the canonical order of [`ColorFormat::ALL`].
+pub(super) fn canonical_color_formats(formats: &[ColorFormat]) -> impl Iterator<Item = &'static ColorFormat> + '_ {
+    ColorFormat::ALL.iter().filter(|c| formats.contains(c))
+}
+
 // Implement string representation of HomiePropertyFormat for serialization
 impl Display for HomiePropertyFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -53,7 +161,10 @@ impl Display for HomiePropertyFormat {
                 write!(
                     f,
                     "{}",
-                    formats.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(",")
+                    canonical_color_formats(formats)
+                        .map(|c| c.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(",")
                 )
             }
             HomiePropertyFormat::Boolean { false_val, true_val } => {
@@ -73,6 +184,19 @@ pub enum ColorFormat {
     Xyz,
 }
 
+impl ColorFormat {
+    /// All color formats supported by the homie convention
+    pub const ALL: [ColorFormat; 3] = [ColorFormat::Rgb, ColorFormat::Hsv, ColorFormat::Xyz];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorFormat::Rgb => "rgb",
+            ColorFormat::Hsv => "hsv",
+            ColorFormat::Xyz => "xyz",
+        }
+    }
+}
+
 impl FromStr for ColorFormat {
     type Err = HomiePropertyFormatError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -87,11 +211,7 @@ impl FromStr for ColorFormat {
 
 impl Display for ColorFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ColorFormat::Rgb => write!(f, "rgb"),
-            ColorFormat::Hsv => write!(f, "hsv"),
-            ColorFormat::Xyz => write!(f, "xyz"),
-        }
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -131,15 +251,16 @@ impl HomiePropertyFormat {
                 raw.split(',').map(|s| s.to_owned()).collect(),
             )),
             HomieDataType::Color => {
+                // unknown formats are rejected, duplicates are dropped and the formats are brought
+                // into canonical order
                 let mut formats = Vec::new();
                 for format in raw.split(',') {
-                    if let Ok(cf) = format.parse::<ColorFormat>() {
+                    let cf = format.parse::<ColorFormat>()?;
+                    if !formats.contains(&cf) {
                         formats.push(cf);
-                    } else {
-                        return Err(HomiePropertyFormatError::ColorFormatError);
                     }
                 }
-                Ok(Self::Color(formats))
+                Ok(Self::Color(canonical_color_formats(&formats).cloned().collect()))
             }
             HomieDataType::Boolean => {
                 let tokens = raw.split(',').collect::<Vec<&str>>();
@@ -0,0 +1,103 @@
+//! Validation of a snapshot of retained mqtt messages of a device against its description.
+
+use std::collections::{BTreeSet, HashMap};
+
+use super::HomieDeviceDescription;
+use crate::{
+    client::mqtt_payload_to_string,
+    extensions::{EXT_META_ATTRIBUTE, EXT_META_ID, EXT_TAGS_ATTRIBUTE},
+    parse_topic, DeviceRef, Homie5ProtocolError, HomieID, HomieValue, PropertyPointer, PROPERTY_ATTRIBUTE_TARGET,
+};
+
+/// A problem found while validating a snapshot of retained messages of a device.
+#[derive(Debug)]
+pub enum SnapshotIssue {
+    /// The retained value (or `$target`) published under `topic` is not valid for the property.
+    InvalidValue { topic: String, error: Homie5ProtocolError },
+    /// A value was published for a property which does not exist in the description.
+    UnknownProperty { topic: String },
+    /// A retained property has no value in the snapshot.
+    MissingValue { node_id: HomieID, prop_id: HomieID },
+    /// An extension attribute was published under `topic`, but the `extension` is not advertised in
+    /// the `extensions` of the description.
+    UnadvertisedExtension { topic: String, extension: &'static str },
+}
+
+impl HomieDeviceDescription {
+    /// Validates a snapshot of retained messages (topic -> payload) of the `device` described by
+    /// this description.
+    ///
+    /// - property values and `$target` values are parsed against the property's datatype and format
+    /// - values for properties not part of the description are reported
+    /// - retained properties without a value in the snapshot are reported
+    /// - extension attributes (e.g. `$meta`) of extensions not listed in the description's
+    ///   `extensions` are reported
+    ///
+    /// Topics of other devices or homie domains, topics which are not property topics (e.g. `$state`
+    /// or `$description`) and topics which cannot be parsed as homie topics are ignored.
+    pub fn validate_snapshot(&self, device: &DeviceRef, topic_values: &HashMap<String, Vec<u8>>) -> Vec<SnapshotIssue> {
+        let mut issues = Vec::new();
+        let mut seen = BTreeSet::new();
+
+        // sort the topics to report the issues in a stable order
+        let mut topic_values: Vec<_> = topic_values.iter().collect();
+        topic_values.sort_by_key(|(topic, _)| *topic);
+
+        for (topic, payload) in topic_values {
+            let Ok(parsed) = parse_topic(topic) else {
+                continue;
+            };
+            if &parsed.homie_domain != device.homie_domain() || parsed.device_id.as_ref() != Some(device.device_id()) {
+                continue;
+            }
+            if matches!(
+                parsed.attributes.first(),
+                Some(&(EXT_META_ATTRIBUTE | EXT_TAGS_ATTRIBUTE))
+            ) && !self.has_extension(EXT_META_ID)
+            {
+                issues.push(SnapshotIssue::UnadvertisedExtension {
+                    topic: topic.clone(),
+                    extension: EXT_META_ID,
+                });
+            }
+            let (Some(node_id), Some(prop_id)) = (parsed.node_id, parsed.prop_id) else {
+                continue;
+            };
+            let is_value = match parsed.attributes.as_slice() {
+                [] => true,
+                [PROPERTY_ATTRIBUTE_TARGET] => false,
+                _ => continue,
+            };
+
+            let Some(prop_desc) = self.get_property_by_id(&node_id, &prop_id) else {
+                issues.push(SnapshotIssue::UnknownProperty { topic: topic.clone() });
+                continue;
+            };
+
+            if let Err(error) = mqtt_payload_to_string(payload)
+                .map_err(Homie5ProtocolError::from)
+                .and_then(|raw| HomieValue::parse(&raw, prop_desc))
+            {
+                issues.push(SnapshotIssue::InvalidValue {
+                    topic: topic.clone(),
+                    error,
+                });
+            }
+
+            if is_value {
+                seen.insert(PropertyPointer::new(node_id, prop_id));
+            }
+        }
+
+        for (node_id, _, prop_id, prop_desc) in self.iter() {
+            if prop_desc.retained && !seen.contains(&PropertyPointer::new(node_id.clone(), prop_id.clone())) {
+                issues.push(SnapshotIssue::MissingValue {
+                    node_id: node_id.clone(),
+                    prop_id: prop_id.clone(),
+                });
+            }
+        }
+
+        issues
+    }
+}
@@ -7,14 +7,20 @@
 //! v5 protocol. Additionally, state machines for device publishing, reconfiguration,
 //! and disconnection are provided through the use of enumerated steps and transitions.
 
+use std::collections::HashSet;
+use std::iter;
+
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    client::{LastWill, Publish, QoS, Subscription, Unsubscribe},
+    client::{subscription_delta, LastWill, Publish, QoS, Subscription, Unsubscribe},
     device_description::{HomieDeviceDescription, HomiePropertyIterator},
     error::Homie5ProtocolError,
     homie_str_to_vecu8,
     statemachine::{HomieStateMachine, Transition},
-    DeviceLogLevel, DeviceRef, HomieDeviceStatus, HomieDomain, HomieID, TopicBuilder, DEVICE_ATTRIBUTES,
-    DEVICE_ATTRIBUTE_ALERT, DEVICE_ATTRIBUTE_DESCRIPTION, DEVICE_ATTRIBUTE_LOG, DEVICE_ATTRIBUTE_STATE,
+    DeviceLogLevel, DeviceRef, Homie5ValueConversionError, HomieDeviceStatus, HomieDomain, HomieID, HomieValue,
+    NodeRef, PreparedProperty, PropertyRef, TopicBuilder, DEVICE_ATTRIBUTES, DEVICE_ATTRIBUTE_ALERT,
+    DEVICE_ATTRIBUTE_DESCRIPTION, DEVICE_ATTRIBUTE_LOG, DEVICE_ATTRIBUTE_STATE, DEVICE_LOG_LEVELS, HOMIE_VERSION,
     PROPERTY_ATTRIBUTE_TARGET, PROPERTY_SET_TOPIC,
 };
 
@@ -28,7 +34,8 @@ pub enum DevicePublishStep {
     #[default]
     /// Set the state of the device to "init" and publish the state
     DeviceStateInit,
-    /// Publish the device description
+    /// Publish the device description, followed by the attributes of all used extensions (see
+    /// [`ExtensionPublisher`])
     DeviceDescription,
     /// Publish the property values for all the retained properties
     PropertyValues,
@@ -59,6 +66,46 @@ pub fn homie_device_publish_steps() -> impl Iterator<Item = DevicePublishStep> {
     HomieStateMachine::new(Default::default())
 }
 
+/// All messages of a device bring-up as data, see [`Homie5DeviceProtocol::birth_bundle`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BirthBundle {
+    /// The last will to set when connecting to the broker
+    pub last_will: LastWill,
+    /// The publishes of all [`DevicePublishStep`]s in order, the `$state = ready` publish is last
+    pub publishes: Vec<Publish>,
+    /// The subscriptions of the [`DevicePublishStep::SubscribeProperties`] step, these need to be
+    /// made before the final `$state = ready` publish
+    pub subscriptions: Vec<Subscription>,
+}
+
+/// A problem found by [`Homie5DeviceProtocol::validate_plan`].
+#[derive(Debug)]
+pub enum PlanIssue {
+    /// The description cannot be published (root/child rules or serialization).
+    InvalidDescription(Homie5ProtocolError),
+    /// No value is available for a retained property.
+    MissingValue { node_id: HomieID, prop_id: HomieID },
+    /// The value for a property does not match its datatype.
+    InvalidValue {
+        node_id: HomieID,
+        prop_id: HomieID,
+        error: Homie5ValueConversionError,
+    },
+}
+
+/// Implemented by protocol extensions which publish additional retained attributes for a device
+/// (e.g. `$meta` and `$tags` of the meta extension).
+///
+/// The publishes are meant to be sent right after the device description in the
+/// [`DevicePublishStep::DeviceDescription`] step, so that controllers receive them together
+/// with the description.
+pub trait ExtensionPublisher {
+    type Error;
+
+    /// Returns the publishes for the extension attributes of the device `device_id`.
+    fn extension_publishes(&self, device_id: &HomieID) -> Result<Vec<Publish>, Self::Error>;
+}
+
 /// Represents the steps required to reconfigure a Homie device.
 ///
 /// This enum defines the steps needed to update a device's configuration, such as
@@ -70,7 +117,8 @@ pub enum DeviceReconfigureStep {
     DeviceStateInit,
     /// Unsubscribe from all device properties
     UnsubscribeProperties,
-    /// Perform the device reconfiguration (change of nodes/properties/name...)
+    /// Perform the device reconfiguration (change of nodes/properties/name...). Retained topics of
+    /// removed properties can be cleared via [`Homie5DeviceProtocol::clear_removed_properties`].
     Reconfigure,
     /// Publish the device description
     DeviceDescription,
@@ -145,6 +193,7 @@ pub fn homie_device_disconnect_steps() -> impl Iterator<Item = DeviceDisconnectS
 pub struct Homie5DeviceProtocol {
     device_ref: DeviceRef,
     is_child: bool,
+    version: Option<String>,
 }
 
 impl Homie5DeviceProtocol {
@@ -157,26 +206,47 @@ impl Homie5DeviceProtocol {
     /// # Returns
     /// A tuple of the created [`Homie5DeviceProtocol`] and its [`LastWill`] message.
     pub fn new(device_id: HomieID, homie_domain: HomieDomain) -> (Self, LastWill) {
-        let last_will = LastWill {
-            topic: TopicBuilder::new_for_device(&homie_domain, &device_id)
-                .add_attr(DEVICE_ATTRIBUTE_STATE)
-                .build(),
-            message: HomieDeviceStatus::Lost.as_str().bytes().collect(),
-            qos: crate::client::QoS::AtLeastOnce,
-            retain: true,
-        };
-
         let homie5_proto = Self {
             device_ref: DeviceRef {
                 homie_domain,
                 id: device_id,
             },
             is_child: false,
+            version: None,
         };
+        let last_will = homie5_proto.last_will();
 
         (homie5_proto, last_will)
     }
 
+    /// Overrides the homie version segment used in all generated topics (`HOMIE_VERSION` by default).
+    ///
+    /// This is intended for testing and simulating other protocol versions only. Note that the
+    /// last will returned by [`Homie5DeviceProtocol::new`] uses the default version, use
+    /// [`Homie5DeviceProtocol::last_will`] to get the last will for the overridden version.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Returns the homie version segment used in the generated topics.
+    pub fn version(&self) -> &str {
+        self.version.as_deref().unwrap_or(HOMIE_VERSION)
+    }
+
+    /// Returns the last will message for the device.
+    pub fn last_will(&self) -> LastWill {
+        LastWill {
+            topic: TopicBuilder::new_for_device(self.homie_domain(), self.id())
+                .with_version(self.version())
+                .add_attr(DEVICE_ATTRIBUTE_STATE)
+                .build(),
+            message: HomieDeviceStatus::Lost.as_bytes().to_vec(),
+            qos: crate::client::QoS::AtLeastOnce,
+            retain: true,
+        }
+    }
+
     /// Returns the device ref the protocol is instantiated for.
     pub fn device_ref(&self) -> &DeviceRef {
         &self.device_ref
@@ -191,6 +261,16 @@ impl Homie5DeviceProtocol {
         &self.device_ref.homie_domain
     }
 
+    /// Returns a `NodeRef` for the node `node_id` of the device.
+    pub fn node_ref(&self, node_id: HomieID) -> NodeRef {
+        NodeRef::from_device(self.device_ref.clone(), node_id)
+    }
+
+    /// Returns a `PropertyRef` for the property `prop_id` of the node `node_id` of the device.
+    pub fn property_ref(&self, node_id: HomieID, prop_id: HomieID) -> PropertyRef {
+        PropertyRef::from_node(self.node_ref(node_id), prop_id)
+    }
+
     /// Checks if the device is a child device.
     pub fn is_child(&self) -> bool {
         self.is_child
@@ -204,6 +284,7 @@ impl Homie5DeviceProtocol {
                 id: device_id,
             },
             is_child: true,
+            version: self.version.clone(),
         }
     }
 
@@ -215,6 +296,7 @@ impl Homie5DeviceProtocol {
                 id: device_id,
             },
             is_child: true,
+            version: root.version,
         }
     }
 
@@ -227,14 +309,55 @@ impl Homie5DeviceProtocol {
     pub fn publish_state_for_id(&self, device_id: &HomieID, state: HomieDeviceStatus) -> Publish {
         Publish {
             topic: TopicBuilder::new_for_device(self.homie_domain(), device_id)
+                .with_version(self.version())
                 .add_attr(DEVICE_ATTRIBUTE_STATE)
                 .build(),
             retain: true,
-            payload: state.as_str().into(),
+            // a state is never empty, so no need for the empty payload convention here
+            payload: state.as_bytes().to_vec(),
             qos: QoS::ExactlyOnce,
         }
     }
 
+    /// Publishes the `init` state to signal the start of a device reconfiguration.
+    ///
+    /// See [`DeviceReconfigureStep::DeviceStateInit`].
+    pub fn begin_reconfigure(&self) -> Publish {
+        self.publish_state(HomieDeviceStatus::Init)
+    }
+
+    /// Publishes the `ready` state to signal the end of a device reconfiguration.
+    ///
+    /// See [`DeviceReconfigureStep::DeviceStateReady`].
+    pub fn end_reconfigure(&self) -> Publish {
+        self.publish_state(HomieDeviceStatus::Ready)
+    }
+
+    /// Publishes the `sleeping` state to signal that the device is going to sleep.
+    ///
+    /// Retained property values stay on the broker while the device sleeps, so controllers keep
+    /// showing the last known values. Non-retained properties need no special handling, there is
+    /// nothing to clear.
+    pub fn enter_sleep(&self) -> Publish {
+        self.publish_state(HomieDeviceStatus::Sleeping)
+    }
+
+    /// Generates the publishes for a device waking up from sleep.
+    ///
+    /// The device re-announces itself with `$state = init`, its description and `$state = ready`.
+    /// Retained property values published before [`Self::enter_sleep`] are still valid and are not
+    /// republished, publish changed values before sending the final `ready` state.
+    ///
+    /// # Errors
+    /// Returns an error if the description cannot be published (see [`Self::publish_description`]).
+    pub fn exit_sleep(&self, description: &HomieDeviceDescription) -> Result<Vec<Publish>, Homie5ProtocolError> {
+        Ok(vec![
+            self.publish_state(HomieDeviceStatus::Init),
+            self.publish_description(description)?,
+            self.publish_state(HomieDeviceStatus::Ready),
+        ])
+    }
+
     /// Publishes a log message for the device.
     pub fn publish_log(&self, level: DeviceLogLevel, log_msg: &str) -> Publish {
         self.publish_log_for_id(self.id(), level, log_msg)
@@ -244,12 +367,13 @@ impl Homie5DeviceProtocol {
     pub fn publish_log_for_id(&self, device_id: &HomieID, level: DeviceLogLevel, log_msg: &str) -> Publish {
         Publish {
             topic: TopicBuilder::new_for_device(self.homie_domain(), device_id)
+                .with_version(self.version())
                 .add_attr(DEVICE_ATTRIBUTE_LOG)
                 .add_attr(level.as_str())
                 .build(),
             qos: QoS::AtLeastOnce,
             retain: true,
-            payload: log_msg.into(),
+            payload: homie_str_to_vecu8(log_msg),
         }
     }
 
@@ -262,15 +386,76 @@ impl Homie5DeviceProtocol {
     pub fn publish_alert_for_id(&self, device_id: &HomieID, alert_id: &HomieID, alert_msg: &str) -> Publish {
         Publish {
             topic: TopicBuilder::new_for_device(self.homie_domain(), device_id)
+                .with_version(self.version())
                 .add_attr(DEVICE_ATTRIBUTE_ALERT)
                 .add_attr(alert_id.as_str())
                 .build(),
             qos: QoS::AtLeastOnce,
             retain: true,
-            payload: alert_msg.into(),
+            payload: homie_str_to_vecu8(alert_msg),
         }
     }
 
+    /// Clears the retained alerts with the given `alert_ids` for the device.
+    ///
+    /// The broker does not tell a device which alerts it published before a restart, so the ids
+    /// to clear need to be provided explicitly.
+    ///
+    /// # Errors
+    /// Returns `InvalidHomieID` if one of the `alert_ids` is not a valid homie id, nothing is
+    /// cleared in that case.
+    pub fn clear_alerts(&self, alert_ids: &[&str]) -> Result<Vec<Publish>, Homie5ProtocolError> {
+        self.clear_alerts_for_id(self.id(), alert_ids)
+    }
+
+    /// Clears the retained alerts with the given `alert_ids` for the provided `device_id`.
+    ///
+    /// # Errors
+    /// Returns `InvalidHomieID` if one of the `alert_ids` is not a valid homie id, nothing is
+    /// cleared in that case.
+    pub fn clear_alerts_for_id(
+        &self,
+        device_id: &HomieID,
+        alert_ids: &[&str],
+    ) -> Result<Vec<Publish>, Homie5ProtocolError> {
+        let alert_ids = alert_ids
+            .iter()
+            .map(|alert_id| HomieID::try_from(alert_id.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(alert_ids
+            .iter()
+            .map(|alert_id| Publish {
+                topic: TopicBuilder::new_for_device(self.homie_domain(), device_id)
+                    .with_version(self.version())
+                    .add_attr(DEVICE_ATTRIBUTE_ALERT)
+                    .add_attr(alert_id.as_str())
+                    .build(),
+                qos: QoS::AtLeastOnce,
+                retain: true,
+                payload: Vec::default(),
+            })
+            .collect())
+    }
+
+    /// Clears the retained log messages of all log levels for the device.
+    pub fn clear_logs(&self) -> impl Iterator<Item = Publish> + '_ {
+        self.clear_logs_for_id(self.id())
+    }
+
+    /// Clears the retained log messages of all log levels for the provided `device_id`.
+    pub fn clear_logs_for_id<'a>(&'a self, device_id: &'a HomieID) -> impl Iterator<Item = Publish> + 'a {
+        DEVICE_LOG_LEVELS.iter().map(move |level| Publish {
+            topic: TopicBuilder::new_for_device(self.homie_domain(), device_id)
+                .with_version(self.version())
+                .add_attr(DEVICE_ATTRIBUTE_LOG)
+                .add_attr(level.as_str())
+                .build(),
+            qos: QoS::AtLeastOnce,
+            retain: true,
+            payload: Vec::default(),
+        })
+    }
+
     /// Publishes a Homie value for a given property and node.
     pub fn publish_value(
         &self,
@@ -292,13 +477,49 @@ impl Homie5DeviceProtocol {
         retain: bool,
     ) -> Publish {
         Publish {
-            topic: TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id).build(),
+            topic: TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id)
+                .with_version(self.version())
+                .build(),
             qos: QoS::ExactlyOnce,
             retain,
             payload: homie_str_to_vecu8(value.into()),
         }
     }
 
+    /// Publishes a value for a given property and node using the `retained` setting of the
+    /// property from the description.
+    ///
+    /// # Errors
+    /// Returns `PropertyNotFound` if the property does not exist in the description.
+    pub fn publish_value_from_desc(
+        &self,
+        node_id: &HomieID,
+        prop_id: &HomieID,
+        value: impl Into<String>,
+        description: &HomieDeviceDescription,
+    ) -> Result<Publish, Homie5ProtocolError> {
+        self.publish_value_from_desc_for_id(self.id(), node_id, prop_id, value, description)
+    }
+
+    /// Publishes a value for a specific `device_id` using the `retained` setting of the property
+    /// from the description.
+    ///
+    /// # Errors
+    /// Returns `PropertyNotFound` if the property does not exist in the description.
+    pub fn publish_value_from_desc_for_id(
+        &self,
+        device_id: &HomieID,
+        node_id: &HomieID,
+        prop_id: &HomieID,
+        value: impl Into<String>,
+        description: &HomieDeviceDescription,
+    ) -> Result<Publish, Homie5ProtocolError> {
+        let Some(prop_desc) = description.get_property_by_id(node_id, prop_id) else {
+            return Err(Homie5ProtocolError::PropertyNotFound);
+        };
+        Ok(self.publish_value_for_id(device_id, node_id, prop_id, value, prop_desc.retained))
+    }
+
     /// Publishes the target value for a given property and node.
     pub fn publish_target(
         &self,
@@ -321,6 +542,7 @@ impl Homie5DeviceProtocol {
     ) -> Publish {
         Publish {
             topic: TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id)
+                .with_version(self.version())
                 .add_attr(PROPERTY_ATTRIBUTE_TARGET)
                 .build(),
             qos: QoS::ExactlyOnce,
@@ -329,6 +551,137 @@ impl Homie5DeviceProtocol {
         }
     }
 
+    /// Publishes a value for the property referenced by `prop`.
+    ///
+    /// # Errors
+    /// Returns `PropertyDeviceMismatch` if the property does not belong to this device (see
+    /// [`Self::device_ref`]).
+    pub fn publish_value_prop(
+        &self,
+        prop: &PropertyRef,
+        value: impl Into<String>,
+        retain: bool,
+    ) -> Result<Publish, Homie5ProtocolError> {
+        self.check_prop_device(prop)?;
+        Ok(self.publish_value(prop.node_id(), prop.prop_id(), value, retain))
+    }
+
+    /// Publishes the target value for the property referenced by `prop`.
+    ///
+    /// # Errors
+    /// Returns `PropertyDeviceMismatch` if the property does not belong to this device (see
+    /// [`Self::device_ref`]).
+    pub fn publish_target_prop(
+        &self,
+        prop: &PropertyRef,
+        value: impl Into<String>,
+        retain: bool,
+    ) -> Result<Publish, Homie5ProtocolError> {
+        self.check_prop_device(prop)?;
+        Ok(self.publish_target(prop.node_id(), prop.prop_id(), value, retain))
+    }
+
+    /// Subscribes to the `set` topic of the property referenced by `prop`.
+    ///
+    /// # Errors
+    /// Returns `PropertyDeviceMismatch` if the property does not belong to this device (see
+    /// [`Self::device_ref`]).
+    pub fn subscribe_prop(&self, prop: &PropertyRef) -> Result<Subscription, Homie5ProtocolError> {
+        self.check_prop_device(prop)?;
+        Ok(Subscription {
+            topic: TopicBuilder::new_for_property(self.homie_domain(), self.id(), prop.node_id(), prop.prop_id())
+                .with_version(self.version())
+                .add_attr(PROPERTY_SET_TOPIC)
+                .build(),
+            qos: QoS::ExactlyOnce,
+        })
+    }
+
+    /// Checks that the property belongs to this device.
+    fn check_prop_device(&self, prop: &PropertyRef) -> Result<(), Homie5ProtocolError> {
+        if prop.device_ref() != self.device_ref() {
+            return Err(Homie5ProtocolError::PropertyDeviceMismatch);
+        }
+        Ok(())
+    }
+
+    /// Publishes the target value for a given property and node using the `retained` setting of
+    /// the property from the description.
+    ///
+    /// The `$target` follows the retention of the property value: it is retained if the property
+    /// is retained and published non-retained otherwise. This way a controller (re)connecting to
+    /// the broker never sees a retained target for a property without a retained value.
+    ///
+    /// # Errors
+    /// Returns `PropertyNotFound` if the property does not exist in the description.
+    pub fn publish_target_from_desc(
+        &self,
+        node_id: &HomieID,
+        prop_id: &HomieID,
+        value: impl Into<String>,
+        description: &HomieDeviceDescription,
+    ) -> Result<Publish, Homie5ProtocolError> {
+        self.publish_target_from_desc_for_id(self.id(), node_id, prop_id, value, description)
+    }
+
+    /// Publishes the target value for a specific `device_id` using the `retained` setting of the
+    /// property from the description (see [`Self::publish_target_from_desc`]).
+    ///
+    /// # Errors
+    /// Returns `PropertyNotFound` if the property does not exist in the description.
+    pub fn publish_target_from_desc_for_id(
+        &self,
+        device_id: &HomieID,
+        node_id: &HomieID,
+        prop_id: &HomieID,
+        value: impl Into<String>,
+        description: &HomieDeviceDescription,
+    ) -> Result<Publish, Homie5ProtocolError> {
+        let Some(prop_desc) = description.get_property_by_id(node_id, prop_id) else {
+            return Err(Homie5ProtocolError::PropertyNotFound);
+        };
+        Ok(self.publish_target_for_id(device_id, node_id, prop_id, value, prop_desc.retained))
+    }
+
+    /// Builds the topics of the property referenced by `prop` once, for use with
+    /// [`Self::publish_value_prepared`] and [`Self::publish_target_prepared`].
+    ///
+    /// The version override of the protocol (see [`Self::with_version`]) is applied to the topics.
+    ///
+    /// # Errors
+    /// Returns `PropertyDeviceMismatch` if the property does not belong to this device (see
+    /// [`Self::device_ref`]).
+    pub fn prepare_property(&self, prop: PropertyRef) -> Result<PreparedProperty, Homie5ProtocolError> {
+        self.check_prop_device(&prop)?;
+        Ok(PreparedProperty::new_for_version(prop, self.version()))
+    }
+
+    /// Publishes a value for a property with prebuilt topics.
+    ///
+    /// Intended for properties updated at a high rate, see [`PreparedProperty`] and
+    /// [`Self::prepare_property`].
+    pub fn publish_value_prepared(&self, prop: &PreparedProperty, value: impl Into<String>, retain: bool) -> Publish {
+        Publish {
+            topic: prop.value_topic().to_owned(),
+            qos: QoS::ExactlyOnce,
+            retain,
+            payload: homie_str_to_vecu8(value.into()),
+        }
+    }
+
+    /// Publishes a target value for a property with prebuilt topics.
+    ///
+    /// Intended for properties updated at a high rate, see [`PreparedProperty`] and
+    /// [`Self::prepare_property`].
+    pub fn publish_target_prepared(&self, prop: &PreparedProperty, value: impl Into<String>, retain: bool) -> Publish {
+        Publish {
+            topic: prop.target_topic().to_owned(),
+            qos: QoS::ExactlyOnce,
+            retain,
+            payload: homie_str_to_vecu8(value),
+        }
+    }
+
     /// Publishes the device description.
     ///
     /// # Errors
@@ -340,28 +693,42 @@ impl Homie5DeviceProtocol {
     /// Publishes the device description for the provided `device_id`.
     ///
     /// # Errors
-    /// Returns an error if the description is invalid for the device type.
+    /// Returns an error if the description is invalid for the device type or contains an invalid
+    /// property format (see [`HomiePropertyFormat::validate`](crate::device_description::HomiePropertyFormat::validate)
+    /// and [`HomiePropertyFormat::is_compatible_with`](crate::device_description::HomiePropertyFormat::is_compatible_with)).
     pub fn publish_description_for_id(
         &self,
         device_id: &HomieID,
         description: &HomieDeviceDescription,
     ) -> Result<Publish, Homie5ProtocolError> {
-        if !self.is_child && self.id() == device_id && description.root.is_some() {
-            return Err(Homie5ProtocolError::NonEmptyRootForRootDevice);
-        } else if !self.is_child && self.id() != device_id && Some(self.id()) != description.root.as_ref() {
-            return Err(Homie5ProtocolError::RootMismatch);
+        self.check_description_root(device_id, description)?;
+        if description
+            .iter()
+            .any(|(_, _, _, prop)| prop.format.validate().is_err() || !prop.format.is_compatible_with(&prop.datatype))
+        {
+            return Err(Homie5ProtocolError::InvalidDeviceDescription);
         }
         match serde_json::to_string(description) {
-            Ok(json) => Ok(Publish {
-                topic: TopicBuilder::new_for_device(self.homie_domain(), device_id)
-                    .add_attr(DEVICE_ATTRIBUTE_DESCRIPTION)
-                    .build(),
-                qos: QoS::ExactlyOnce,
-                retain: true,
-                payload: json.into(),
-            }),
-            Err(_) => {
-                // TODO: log actual error for debug purposes
+            Ok(json) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    device_id = device_id.as_str(),
+                    bytes = json.len(),
+                    "publish description"
+                );
+                Ok(Publish {
+                    topic: TopicBuilder::new_for_device(self.homie_domain(), device_id)
+                        .with_version(self.version())
+                        .add_attr(DEVICE_ATTRIBUTE_DESCRIPTION)
+                        .build(),
+                    qos: QoS::ExactlyOnce,
+                    retain: true,
+                    payload: json.into(),
+                })
+            }
+            Err(_err) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(device_id = device_id.as_str(), error = %_err, "cannot serialize description");
                 Err(Homie5ProtocolError::InvalidDeviceDescription)
             }
         }
@@ -387,14 +754,17 @@ impl Homie5DeviceProtocol {
         device_id: &'a HomieID,
         description: &'a HomieDeviceDescription,
     ) -> Result<impl Iterator<Item = Subscription> + 'a, Homie5ProtocolError> {
-        if !self.is_child && self.id() == device_id && description.root.is_some() {
-            return Err(Homie5ProtocolError::NonEmptyRootForRootDevice);
-        } else if !self.is_child && self.id() != device_id && Some(self.id()) != description.root.as_ref() {
-            return Err(Homie5ProtocolError::RootMismatch);
-        }
+        self.check_description_root(device_id, description)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            device_id = device_id.as_str(),
+            count = description.iter().count(),
+            "subscribe properties"
+        );
 
         Ok(description.iter().map(move |(node_id, _, prop_id, _)| Subscription {
             topic: TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id)
+                .with_version(self.version())
                 .add_attr(PROPERTY_SET_TOPIC)
                 .build(),
             qos: QoS::ExactlyOnce,
@@ -421,17 +791,152 @@ impl Homie5DeviceProtocol {
         device_id: &'a HomieID,
         description: &'a HomieDeviceDescription,
     ) -> Result<impl Iterator<Item = Unsubscribe> + 'a, Homie5ProtocolError> {
-        if !self.is_child && self.id() == device_id && description.root.is_some() {
-            return Err(Homie5ProtocolError::NonEmptyRootForRootDevice);
-        } else if !self.is_child && self.id() != device_id && Some(self.id()) != description.root.as_ref() {
-            return Err(Homie5ProtocolError::RootMismatch);
-        }
+        self.check_description_root(device_id, description)?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            device_id = device_id.as_str(),
+            count = description.iter().count(),
+            "unsubscribe properties"
+        );
         let prop_iter = HomiePropertyIterator::new(description);
         Ok(prop_iter.map(move |(node_id, _, prop_id, _)| Unsubscribe {
-            topic: TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id).build(),
+            topic: TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id)
+                .with_version(self.version())
+                .build(),
         }))
     }
 
+    /// Validates a received set command against the device description.
+    ///
+    /// Checks that the property exists in the description, that it is settable and that the raw
+    /// value can be parsed according to the property's datatype and format.
+    ///
+    /// # Errors
+    /// - `PropertyNotFound` if the property does not exist in the description.
+    /// - `PropertyNotSettable` if the property is not settable.
+    /// - `InvalidHomieValue` if the value cannot be parsed for the property.
+    pub fn validate_set(
+        &self,
+        prop: &PropertyRef,
+        raw: &str,
+        description: &HomieDeviceDescription,
+    ) -> Result<HomieValue, Homie5ProtocolError> {
+        let Some(prop_desc) = description.get_property(prop.prop_pointer()) else {
+            return Err(Homie5ProtocolError::PropertyNotFound);
+        };
+        if !prop_desc.settable {
+            return Err(Homie5ProtocolError::PropertyNotSettable);
+        }
+        HomieValue::parse(raw, prop_desc)
+    }
+
+    /// Computes the subscription changes needed when the device is reconfigured from the `old` to
+    /// the `new` description (see [`HomieDeviceDescription::diff`]).
+    ///
+    /// Only the `set` topics of settable properties are subscribed, so added settable properties
+    /// and properties which became settable are subscribed, while removed settable properties and
+    /// properties which are no longer settable are unsubscribed. Both lists are sorted by topic.
+    ///
+    /// # Errors
+    /// Returns an error if one of the descriptions is invalid for the device type.
+    pub fn reconfigure_subscriptions(
+        &self,
+        old: &HomieDeviceDescription,
+        new: &HomieDeviceDescription,
+    ) -> Result<(Vec<Subscription>, Vec<Unsubscribe>), Homie5ProtocolError> {
+        self.reconfigure_subscriptions_for_id(self.id(), old, new)
+    }
+
+    /// Computes the subscription changes for a reconfiguration of the given `device_id`.
+    ///
+    /// # Errors
+    /// Returns an error if one of the descriptions is invalid for the device type.
+    pub fn reconfigure_subscriptions_for_id(
+        &self,
+        device_id: &HomieID,
+        old: &HomieDeviceDescription,
+        new: &HomieDeviceDescription,
+    ) -> Result<(Vec<Subscription>, Vec<Unsubscribe>), Homie5ProtocolError> {
+        self.check_description_root(device_id, old)?;
+        self.check_description_root(device_id, new)?;
+        let set_topics = |description: &HomieDeviceDescription| -> HashSet<String> {
+            description
+                .iter()
+                .filter(|(_, _, _, prop)| prop.settable)
+                .map(|(node_id, _, prop_id, _)| {
+                    TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id)
+                        .with_version(self.version())
+                        .add_attr(PROPERTY_SET_TOPIC)
+                        .build()
+                })
+                .collect()
+        };
+        Ok(subscription_delta(&set_topics(old), &set_topics(new)))
+    }
+
+    /// Clears the retained topics of all properties which exist in the `old` description but not
+    /// in the `new` description.
+    ///
+    /// For each removed property the `$target` is cleared and, if the property was retained, its
+    /// value as well. Use this during a reconfiguration (see [`DeviceReconfigureStep::Reconfigure`])
+    /// so that removed properties do not linger on the broker.
+    pub fn clear_removed_properties<'a>(
+        &'a self,
+        old: &'a HomieDeviceDescription,
+        new: &'a HomieDeviceDescription,
+    ) -> impl Iterator<Item = Publish> + 'a {
+        self.clear_removed_properties_for_id(self.id(), old, new)
+    }
+
+    /// Clears the retained topics of all removed properties for the given `device_id`.
+    pub fn clear_removed_properties_for_id<'a>(
+        &'a self,
+        device_id: &'a HomieID,
+        old: &'a HomieDeviceDescription,
+        new: &'a HomieDeviceDescription,
+    ) -> impl Iterator<Item = Publish> + 'a {
+        old.iter_by_id()
+            .filter(|(node_id, _, prop_id, _)| new.get_property_by_id(node_id, prop_id).is_none())
+            .flat_map(move |(node_id, _, prop_id, prop_desc)| {
+                let topic = TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id)
+                    .with_version(self.version());
+                let value = prop_desc.retained.then(|| Publish {
+                    topic: topic.clone().build(),
+                    qos: QoS::ExactlyOnce,
+                    retain: true,
+                    payload: Vec::default(),
+                });
+                let target = Publish {
+                    topic: topic.add_attr(PROPERTY_ATTRIBUTE_TARGET).build(),
+                    qos: QoS::ExactlyOnce,
+                    retain: true,
+                    payload: Vec::default(),
+                };
+                value.into_iter().chain(iter::once(target))
+            })
+    }
+
+    /// Generates the publishes for a graceful disconnect of the device.
+    ///
+    /// This is the [`DeviceDisconnectStep::DeviceStateDisconnect`] step: the retained
+    /// `$state = disconnected` overrides the `lost` state of the last will (see
+    /// [`Self::last_will`]). After a clean mqtt disconnect the broker discards the last will, so
+    /// these publishes must be sent before disconnecting, otherwise the device keeps its previous
+    /// state (e.g. `ready`).
+    ///
+    /// Retained property values are kept as the last known state of the device, so no property
+    /// topics are cleared. Use [`Self::remove_device`] to remove the device altogether.
+    ///
+    /// # Errors
+    /// Returns an error if the description is invalid for the device type.
+    pub fn graceful_disconnect(
+        &self,
+        description: &HomieDeviceDescription,
+    ) -> Result<Vec<Publish>, Homie5ProtocolError> {
+        self.check_description_root(self.id(), description)?;
+        Ok(vec![self.publish_state(HomieDeviceStatus::Disconnected)])
+    }
+
     /// Removes the device by clearing all retained property values.
     ///
     /// # Errors
@@ -452,15 +957,12 @@ impl Homie5DeviceProtocol {
         device_id: &'a HomieID,
         description: &'a HomieDeviceDescription,
     ) -> Result<impl Iterator<Item = Publish> + 'a, Homie5ProtocolError> {
-        if !self.is_child && self.id() == device_id && description.root.is_some() {
-            return Err(Homie5ProtocolError::NonEmptyRootForRootDevice);
-        } else if !self.is_child && self.id() != device_id && Some(self.id()) != description.root.as_ref() {
-            return Err(Homie5ProtocolError::RootMismatch);
-        }
+        self.check_description_root(device_id, description)?;
 
         // clear device attributes (startes with `$state` as per convention)
         let attrs = DEVICE_ATTRIBUTES.iter().map(move |attribute| Publish {
             topic: TopicBuilder::new_for_device(self.homie_domain(), device_id)
+                .with_version(self.version())
                 .add_attr(attribute)
                 .build(),
             qos: QoS::ExactlyOnce,
@@ -468,7 +970,7 @@ impl Homie5DeviceProtocol {
             payload: Vec::default(),
         });
 
-        let prop_iter = HomiePropertyIterator::new(description);
+        let prop_iter = description.iter_by_id();
         // clear all retained property values
         let props = prop_iter
             .filter(|(_, _, _, prop)| prop.retained)
@@ -476,6 +978,7 @@ impl Homie5DeviceProtocol {
                 [
                     Publish {
                         topic: TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id)
+                            .with_version(self.version())
                             .add_attr(PROPERTY_SET_TOPIC)
                             .build(),
                         qos: QoS::ExactlyOnce,
@@ -484,6 +987,7 @@ impl Homie5DeviceProtocol {
                     },
                     Publish {
                         topic: TopicBuilder::new_for_property(self.homie_domain(), device_id, node_id, prop_id)
+                            .with_version(self.version())
                             .add_attr(PROPERTY_ATTRIBUTE_TARGET)
                             .build(),
                         qos: QoS::ExactlyOnce,
@@ -494,4 +998,101 @@ impl Homie5DeviceProtocol {
             });
         Ok(attrs.chain(props))
     }
+
+    /// Checks the description against the root/child rules for the given `device_id`.
+    fn check_description_root(
+        &self,
+        device_id: &HomieID,
+        description: &HomieDeviceDescription,
+    ) -> Result<(), Homie5ProtocolError> {
+        if !self.is_child && self.id() == device_id && description.root.is_some() {
+            return Err(Homie5ProtocolError::NonEmptyRootForRootDevice);
+        } else if !self.is_child && self.id() != device_id && Some(self.id()) != description.root.as_ref() {
+            return Err(Homie5ProtocolError::RootMismatch);
+        }
+        Ok(())
+    }
+
+    /// Validates the device bring-up (see [`DevicePublishStep`]) without publishing anything.
+    ///
+    /// This runs the checks of [`Self::publish_description`] and [`Self::subscribe_props`] and
+    /// verifies that `value_for` provides a value for every retained property, as required for the
+    /// [`DevicePublishStep::PropertyValues`] step. The values must be of the datatype of their
+    /// property and valid for its format (e.g. within the range of a number).
+    ///
+    /// All issues found are returned.
+    pub fn validate_plan(
+        &self,
+        description: &HomieDeviceDescription,
+        mut value_for: impl FnMut(&HomieID, &HomieID) -> Option<HomieValue>,
+    ) -> Result<(), Vec<PlanIssue>> {
+        let mut issues = Vec::new();
+
+        if let Err(err) = self.publish_description(description) {
+            issues.push(PlanIssue::InvalidDescription(err));
+        }
+
+        for (node_id, _, prop_id, prop_desc) in description.iter().filter(|(_, _, _, prop)| prop.retained) {
+            match value_for(node_id, prop_id) {
+                Some(value) => {
+                    let checked = value
+                        .matches_description(prop_desc)
+                        .map_err(|(expected, actual)| {
+                            Homie5ValueConversionError::DatatypeMismatch(expected, Some(actual))
+                        })
+                        .and_then(|_| value.validate_format(prop_desc));
+                    if let Err(error) = checked {
+                        issues.push(PlanIssue::InvalidValue {
+                            node_id: node_id.clone(),
+                            prop_id: prop_id.clone(),
+                            error,
+                        });
+                    }
+                }
+                None => issues.push(PlanIssue::MissingValue {
+                    node_id: node_id.clone(),
+                    prop_id: prop_id.clone(),
+                }),
+            }
+        }
+
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Generates all messages to bring the device online (see [`DevicePublishStep`]) as a single
+    /// serializable [`BirthBundle`].
+    ///
+    /// `value_for` provides the current values of the retained properties, properties without a
+    /// value are not published. Attributes of extensions (see [`ExtensionPublisher`]) are not part
+    /// of the bundle.
+    ///
+    /// # Errors
+    /// Returns an error if the description cannot be published (see [`Self::publish_description`]).
+    pub fn birth_bundle(
+        &self,
+        description: &HomieDeviceDescription,
+        mut value_for: impl FnMut(&HomieID, &HomieID) -> Option<HomieValue>,
+    ) -> Result<BirthBundle, Homie5ProtocolError> {
+        let mut publishes = vec![
+            self.publish_state(HomieDeviceStatus::Init),
+            self.publish_description(description)?,
+        ];
+        for (node_id, _, prop_id, _) in description.iter().filter(|(_, _, _, prop)| prop.retained) {
+            if let Some(value) = value_for(node_id, prop_id) {
+                publishes.push(self.publish_value(node_id, prop_id, value, true));
+            }
+        }
+        let subscriptions = self.subscribe_props(description)?.collect();
+        publishes.push(self.publish_state(HomieDeviceStatus::Ready));
+
+        Ok(BirthBundle {
+            last_will: self.last_will(),
+            publishes,
+            subscriptions,
+        })
+    }
 }
@@ -3,7 +3,7 @@
 
 use thiserror::Error;
 
-use crate::{Homie5ValueConversionError, InvalidHomieDomainError, InvalidHomieIDError};
+use crate::{Homie5ValueConversionError, HomieDataType, InvalidHomieDomainError, InvalidHomieIDError};
 
 /// Represents various errors that can occur while handling the Homie v5 protocol.
 ///
@@ -60,6 +60,15 @@ pub enum Homie5ProtocolError {
     #[error("The requested property does not exist in the device description.")]
     PropertyNotFound,
 
+    /// A set command was received for a property that is not settable.
+    #[error("The requested property is not settable.")]
+    PropertyNotSettable,
+
+    /// The referenced property belongs to another device or homie domain than the one the
+    /// protocol was created for.
+    #[error("The referenced property does not belong to this device.")]
+    PropertyDeviceMismatch,
+
     /// The datatype of a property is invalid according to the Homie specification.
     #[error("Invalid homie datatype.")]
     InvalidHomieDataType,
@@ -85,4 +94,12 @@ pub enum Homie5ProtocolError {
     /// Invalid Device log level
     #[error("Invalid device log level: {0}")]
     InvalidDeviceLogLevel(String),
+
+    /// A topic segment is empty or contains a topic level separator or wildcard (`/`, `+`, `#`).
+    #[error("Invalid topic segment: [{0}]")]
+    InvalidTopicSegment(String),
+
+    /// A property of the given datatype (`enum` or `color`) requires a format, but none is set.
+    #[error("A format is required for properties of datatype {0}.")]
+    MissingRequiredFormat(HomieDataType),
 }
@@ -5,10 +5,12 @@ use thiserror::Error;
 
 use crate::{
     client::{mqtt_payload_to_string, Publish, QoS, Subscription},
-    DeviceRef, Homie5DeviceProtocol, HomieDomain, HomieID, InvalidHomieDomainError, InvalidHomieIDError, NodeRef,
-    PropertyRef, TopicBuilder, HOMIE_VERSION,
+    parse_topic, DeviceRef, ExtensionPublisher, Homie5DeviceProtocol, Homie5ProtocolError, HomieDomain, HomieID,
+    InvalidHomieDomainError, InvalidHomieIDError, NodeRef, ParsedTopic, PropertyRef, TopicBuilder, HOMIE_VERSION,
 };
 
+/// Identifier of the meta extension as advertised in the `extensions` of a device description
+pub const EXT_META_ID: &str = "org.homie.meta";
 pub const EXT_META_ATTRIBUTE: &str = "$meta";
 pub const EXT_TAGS_ATTRIBUTE: &str = "$tags";
 
@@ -40,6 +42,17 @@ pub enum MetaExtError {
     InvalidHomieID(#[from] InvalidHomieIDError),
 }
 
+impl From<Homie5ProtocolError> for MetaExtError {
+    fn from(value: Homie5ProtocolError) -> Self {
+        match value {
+            Homie5ProtocolError::InvalidHomieDomain(err) => Self::InvalidHomieDomain(err),
+            Homie5ProtocolError::InvalidHomieID(err) => Self::InvalidHomieID(err),
+            Homie5ProtocolError::PayloadConversionError(err) => Self::PayloadConversionError(err),
+            _ => Self::InvalidTopic,
+        }
+    }
+}
+
 /// Represents the protocol implementation for the meta extension for a device, providing methods for
 /// publishing and handling meta information
 ///
@@ -47,6 +60,8 @@ pub enum MetaExtError {
 pub struct MetaDeviceProtocol {
     id: HomieID,
     homie_domain: HomieDomain,
+    device_meta: HashMap<String, String>,
+    device_tags: Vec<String>,
 }
 
 impl MetaDeviceProtocol {
@@ -54,9 +69,21 @@ impl MetaDeviceProtocol {
         Self {
             id: device_id,
             homie_domain,
+            device_meta: HashMap::new(),
+            device_tags: Vec::new(),
         }
     }
 
+    /// Sets the device level meta data and tags published as part of the device bring-up via
+    /// [`ExtensionPublisher`]. Empty meta data or tags are not published.
+    ///
+    /// The tuple returned by [`MetaBuilder::build`] can be used directly.
+    pub fn with_device_meta(mut self, (meta, tags): (HashMap<String, String>, Vec<String>)) -> Self {
+        self.device_meta = meta;
+        self.device_tags = tags;
+        self
+    }
+
     /// Returns the device's ID.
     pub fn id(&self) -> &HomieID {
         &self.id
@@ -162,14 +189,142 @@ impl MetaDeviceProtocol {
             qos: QoS::ExactlyOnce,
         })
     }
+
+    /// Clears the retained meta data of the given `device_id` by publishing an empty payload.
+    pub fn clear_meta_device(&self, device_id: &HomieID) -> Publish {
+        clear_retained(
+            TopicBuilder::new_for_device(&self.homie_domain, device_id)
+                .add_attr(EXT_META_ATTRIBUTE)
+                .build(),
+        )
+    }
+
+    /// Clears the retained meta data of the given `device_id` and `node_id` by publishing an
+    /// empty payload.
+    pub fn clear_meta_node(&self, device_id: &HomieID, node_id: &HomieID) -> Publish {
+        clear_retained(
+            TopicBuilder::new_for_node(&self.homie_domain, device_id, node_id)
+                .add_attr(EXT_META_ATTRIBUTE)
+                .build(),
+        )
+    }
+
+    /// Clears the retained meta data of the given property by publishing an empty payload.
+    pub fn clear_meta_property(&self, device_id: &HomieID, node_id: &HomieID, property_id: &HomieID) -> Publish {
+        clear_retained(
+            TopicBuilder::new_for_property(&self.homie_domain, device_id, node_id, property_id)
+                .add_attr(EXT_META_ATTRIBUTE)
+                .build(),
+        )
+    }
+
+    /// Clears the retained tags of the given `device_id` by publishing an empty payload.
+    pub fn clear_tags_device(&self, device_id: &HomieID) -> Publish {
+        clear_retained(
+            TopicBuilder::new_for_device(&self.homie_domain, device_id)
+                .add_attr(EXT_TAGS_ATTRIBUTE)
+                .build(),
+        )
+    }
+
+    /// Clears the retained tags of the given `device_id` and `node_id` by publishing an empty
+    /// payload.
+    pub fn clear_tags_node(&self, device_id: &HomieID, node_id: &HomieID) -> Publish {
+        clear_retained(
+            TopicBuilder::new_for_node(&self.homie_domain, device_id, node_id)
+                .add_attr(EXT_TAGS_ATTRIBUTE)
+                .build(),
+        )
+    }
+
+    /// Clears the retained tags of the given property by publishing an empty payload.
+    pub fn clear_tags_property(&self, device_id: &HomieID, node_id: &HomieID, property_id: &HomieID) -> Publish {
+        clear_retained(
+            TopicBuilder::new_for_property(&self.homie_domain, device_id, node_id, property_id)
+                .add_attr(EXT_TAGS_ATTRIBUTE)
+                .build(),
+        )
+    }
+}
+
+fn clear_retained(topic: String) -> Publish {
+    Publish {
+        topic,
+        retain: true,
+        payload: Vec::default(),
+        qos: QoS::ExactlyOnce,
+    }
+}
+
+impl ExtensionPublisher for MetaDeviceProtocol {
+    type Error = MetaExtError;
+
+    fn extension_publishes(&self, device_id: &HomieID) -> Result<Vec<Publish>, Self::Error> {
+        let mut publishes = Vec::new();
+        if !self.device_meta.is_empty() {
+            publishes.push(self.publish_meta_device(device_id, &self.device_meta)?);
+        }
+        if !self.device_tags.is_empty() {
+            publishes.push(self.publish_tags_device(device_id, &self.device_tags)?);
+        }
+        Ok(publishes)
+    }
 }
 
 impl From<&Homie5DeviceProtocol> for MetaDeviceProtocol {
     fn from(value: &Homie5DeviceProtocol) -> Self {
-        Self {
-            id: value.id().clone(),
-            homie_domain: value.homie_domain().clone(),
+        Self::new(value.id().clone(), value.homie_domain().clone())
+    }
+}
+
+/// Builder to accumulate meta key/value pairs and tags for publishing via [`MetaDeviceProtocol`]
+#[derive(Debug, Default, Clone)]
+pub struct MetaBuilder {
+    meta: HashMap<String, String>,
+    tags: Vec<String>,
+}
+
+impl MetaBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds (or replaces) a meta key/value pair
+    pub fn add_meta(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.meta.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn remove_meta(mut self, key: &str) -> Self {
+        self.meta.remove(key);
+        self
+    }
+
+    /// Adds a tag, tags which are already present are ignored
+    pub fn add_tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
         }
+        self
+    }
+
+    pub fn remove_tag(mut self, tag: &str) -> Self {
+        self.tags.retain(|t| t != tag);
+        self
+    }
+
+    pub fn do_if(self, condition: bool, cb: impl FnOnce(Self) -> Self) -> Self {
+        if condition {
+            cb(self)
+        } else {
+            self
+        }
+    }
+
+    /// Returns the meta map and the tags
+    pub fn build(self) -> (HashMap<String, String>, Vec<String>) {
+        (self.meta, self.tags)
     }
 }
 
@@ -217,104 +372,83 @@ pub enum MetaExtMessage {
 }
 
 impl MetaExtMessage {
+    /// Parses a meta extension message.
+    ///
+    /// An empty payload signals cleared meta data or tags and is parsed as empty `meta`/`tags`.
     pub fn from_mqtt_message(topic: &str, payload: &[u8]) -> Result<Self, MetaExtError> {
-        // Split the topic into components based on '/' delimiter
-        let tokens: Vec<&str> = topic.split('/').collect();
+        let ParsedTopic {
+            homie_domain,
+            device_id,
+            node_id,
+            prop_id,
+            attributes,
+        } = parse_topic(topic)?;
 
-        // Ensure the topic contains at least 4 tokens and the last one is named $meta (e.g. "homie/5/device-id/$meta")
-        if tokens.last() != Some(&EXT_META_ATTRIBUTE) || tokens.last() != Some(&EXT_TAGS_ATTRIBUTE) {
+        // meta data is never published on broadcast topics
+        let Some(device_id) = device_id else {
             return Err(MetaExtError::InvalidTopic);
-        }
+        };
 
-        let homie_domain: HomieDomain = tokens[0].to_owned().try_into()?;
-
-        // check the homie id provided
-        let device_id = tokens[2].to_string().try_into()?;
-
-        match (tokens.len(), tokens.last()) {
+        match (node_id, prop_id, attributes.as_slice()) {
             // Device meta
             // ===================
-            (4, Some(&EXT_META_ATTRIBUTE)) => Ok(serde_json::from_str::<HashMap<String, String>>(
-                &mqtt_payload_to_string(payload)?,
-            )
-            .map(|meta| Self::DeviceMeta {
+            (None, None, [EXT_META_ATTRIBUTE]) => Ok(Self::DeviceMeta {
                 device: DeviceRef {
                     homie_domain,
                     id: device_id,
                 },
-                meta,
-            })?),
+                meta: parse_meta(payload)?,
+            }),
             // Device tags
             // ===================
-            (4, Some(&EXT_TAGS_ATTRIBUTE)) => Ok(serde_json::from_str::<Vec<String>>(&mqtt_payload_to_string(
-                payload,
-            )?)
-            .map(|tags| Self::DeviceTags {
+            (None, None, [EXT_TAGS_ATTRIBUTE]) => Ok(Self::DeviceTags {
                 device: DeviceRef {
                     homie_domain,
                     id: device_id,
                 },
-                tags,
-            })?),
+                tags: parse_tags(payload)?,
+            }),
             // Node meta
             // ===================
-            (5, Some(&EXT_META_ATTRIBUTE)) => {
-                let node_id = tokens[3].to_string().try_into()?;
-
-                Ok(
-                    serde_json::from_str::<HashMap<String, String>>(&mqtt_payload_to_string(payload)?).map(|meta| {
-                        Self::NodeMeta {
-                            node: NodeRef::new(homie_domain, device_id, node_id),
-                            meta,
-                        }
-                    })?,
-                )
-            }
+            (Some(node_id), None, [EXT_META_ATTRIBUTE]) => Ok(Self::NodeMeta {
+                node: NodeRef::new(homie_domain, device_id, node_id),
+                meta: parse_meta(payload)?,
+            }),
             // Node tags
             // ===================
-            (5, Some(&EXT_TAGS_ATTRIBUTE)) => {
-                let node_id = tokens[3].to_string().try_into()?;
-
-                Ok(
-                    serde_json::from_str::<Vec<String>>(&mqtt_payload_to_string(payload)?).map(|tags| {
-                        Self::NodeTags {
-                            node: NodeRef::new(homie_domain, device_id, node_id),
-                            tags,
-                        }
-                    })?,
-                )
-            }
+            (Some(node_id), None, [EXT_TAGS_ATTRIBUTE]) => Ok(Self::NodeTags {
+                node: NodeRef::new(homie_domain, device_id, node_id),
+                tags: parse_tags(payload)?,
+            }),
             // Property meta
             // ===================
-            (6, Some(&EXT_META_ATTRIBUTE)) => {
-                let node_id = tokens[3].to_string().try_into()?;
-                let property_id = tokens[4].to_string().try_into()?;
-
-                Ok(
-                    serde_json::from_str::<HashMap<String, String>>(&mqtt_payload_to_string(payload)?).map(|meta| {
-                        Self::PropertyMeta {
-                            property: PropertyRef::new(homie_domain, device_id, node_id, property_id),
-                            meta,
-                        }
-                    })?,
-                )
-            }
+            (Some(node_id), Some(property_id), [EXT_META_ATTRIBUTE]) => Ok(Self::PropertyMeta {
+                property: PropertyRef::new(homie_domain, device_id, node_id, property_id),
+                meta: parse_meta(payload)?,
+            }),
             // Property tags
             // ===================
-            (6, Some(&EXT_TAGS_ATTRIBUTE)) => {
-                let node_id = tokens[3].to_string().try_into()?;
-                let property_id = tokens[4].to_string().try_into()?;
-
-                Ok(
-                    serde_json::from_str::<Vec<String>>(&mqtt_payload_to_string(payload)?).map(|tags| {
-                        Self::PropertyTags {
-                            property: PropertyRef::new(homie_domain, device_id, node_id, property_id),
-                            tags,
-                        }
-                    })?,
-                )
-            }
+            (Some(node_id), Some(property_id), [EXT_TAGS_ATTRIBUTE]) => Ok(Self::PropertyTags {
+                property: PropertyRef::new(homie_domain, device_id, node_id, property_id),
+                tags: parse_tags(payload)?,
+            }),
             _ => Err(MetaExtError::InvalidTopic),
         }
     }
 }
+
+/// Parses a `$meta` payload, an empty payload (cleared meta data) is parsed as empty map.
+fn parse_meta(payload: &[u8]) -> Result<HashMap<String, String>, MetaExtError> {
+    if payload.is_empty() {
+        return Ok(HashMap::new());
+    }
+    Ok(serde_json::from_str(&mqtt_payload_to_string(payload)?)?)
+}
+
+/// Parses a `$tags` payload, an empty payload (cleared tags) is parsed as empty list.
+fn parse_tags(payload: &[u8]) -> Result<Vec<String>, MetaExtError> {
+    if payload.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&mqtt_payload_to_string(payload)?)?)
+}
@@ -26,11 +26,13 @@
 //! - `DeviceDescription`: Provides metadata about the device.
 //! - `DeviceLog`: Contains log messages for debugging purposes.
 //! - `DeviceAlert`: Represents critical alerts from the device.
+//! - `DeviceAlertCleared`: An alert of the device has been cleared.
 //! - `PropertyValue`: Contains the current value of a property.
 //! - `PropertyTarget`: Represents the desired state of a property.
 //! - `PropertySet`: A command to set a property to a specific value.
 //! - `Broadcast`: Represents general-purpose communication broadcast messages.
 //! - `DeviceRemoval`: Represents the removal of a device from the network.
+//! - `DeviceAttributeCleared`: A retained device attribute has been cleared (e.g. during device removal).
 //!
 //! # Parsing MQTT Messages
 //!
@@ -69,9 +71,16 @@
 //! The `parse_mqtt_message` function successfully parses the message, and the resulting `Homie5Message` enum variant
 //! is used to handle the message.
 
+use std::collections::HashMap;
+
 use crate::{
-    client::mqtt_payload_to_string, device_description::HomieDeviceDescription, error::Homie5ProtocolError,
-    DeviceLogLevel, DeviceRef, HomieDeviceStatus, HomieDomain, HomieID, PropertyRef, HOMIE_VERSION,
+    client::{mqtt_payload_to_string, Publish, QoS},
+    device_description::{HomieDeviceDescription, HomiePropertyDescription},
+    error::Homie5ProtocolError,
+    homie_str_to_vecu8, parse_topic, DeviceAttribute, DeviceLogLevel, DeviceRef, HomieDeviceStatus, HomieDomain,
+    HomieID, HomieValue, ParsedTopic, PropertyRef, ToTopic, TopicBuilder, DEVICE_ATTRIBUTE_ALERT,
+    DEVICE_ATTRIBUTE_DESCRIPTION, DEVICE_ATTRIBUTE_LOG, DEVICE_ATTRIBUTE_STATE, HOMIE_TOPIC_BROADCAST,
+    PROPERTY_ATTRIBUTE_TARGET, PROPERTY_SET_TOPIC,
 };
 /// Represents all possible MQTT message types according to the Homie 5 protocol.
 /// These messages define the interactions between devices, their attributes, and the broker.
@@ -125,6 +134,17 @@ pub enum Homie5Message {
         alert_msg: String,
     },
 
+    /// An alert of the device has been cleared.
+    ///
+    /// Devices clear an alert by publishing a zero-length payload to `homie/5/<device-id>/$alert/<alert-id>`.
+    /// Note that an alert with an empty message (a single 0 byte payload) is a `DeviceAlert`.
+    DeviceAlertCleared {
+        /// The device identifier from which the alert was cleared.
+        device: DeviceRef,
+        /// The identifier of the cleared alert.
+        alert_id: HomieID,
+    },
+
     /// A property value message has been received.
     ///
     /// Property values are typically sensor readings or other dynamic values
@@ -180,6 +200,302 @@ pub enum Homie5Message {
         /// The device identifier for the device that was removed.
         device: DeviceRef,
     },
+
+    /// A retained device attribute has been cleared.
+    ///
+    /// When a device is removed, all of its retained attributes (`$description`, `$log`, `$alert`) are
+    /// cleared by publishing a zero-length payload after the `$state` topic. These clears carry no
+    /// information beyond the removal itself and can usually be ignored.
+    DeviceAttributeCleared {
+        /// The device identifier of the device whose attribute was cleared.
+        device: DeviceRef,
+        /// The cleared attribute (`Description`, `Log` or `Alert`).
+        attribute: DeviceAttribute,
+    },
+}
+
+impl Homie5Message {
+    /// Creates the mqtt message which reproduces this message when parsed with
+    /// [`parse_mqtt_message`], e.g. for replaying messages or generating test fixtures.
+    ///
+    /// QoS and retain flags are the ones used by [`crate::Homie5DeviceProtocol`] and
+    /// [`crate::Homie5ControllerProtocol`]. As the retained flag of a property is not part of the
+    /// message, property values and targets are always published retained.
+    ///
+    /// Returns `None` if the device description cannot be serialized.
+    pub fn to_publish(&self) -> Option<Publish> {
+        let publish = match self {
+            Homie5Message::DeviceState { device, state } => Publish {
+                topic: device.to_topic().add_attr(DEVICE_ATTRIBUTE_STATE).build(),
+                qos: QoS::ExactlyOnce,
+                retain: true,
+                payload: state.as_bytes().to_vec(),
+            },
+            Homie5Message::DeviceDescription { device, description } => Publish {
+                topic: device.to_topic().add_attr(DEVICE_ATTRIBUTE_DESCRIPTION).build(),
+                qos: QoS::ExactlyOnce,
+                retain: true,
+                payload: serde_json::to_string(description).ok()?.into(),
+            },
+            Homie5Message::DeviceLog { device, level, log_msg } => Publish {
+                topic: device
+                    .to_topic()
+                    .add_attr(DEVICE_ATTRIBUTE_LOG)
+                    .add_attr(level.as_str())
+                    .build(),
+                qos: QoS::AtLeastOnce,
+                retain: true,
+                payload: homie_str_to_vecu8(log_msg),
+            },
+            Homie5Message::DeviceAlert {
+                device,
+                alert_id,
+                alert_msg,
+            } => Publish {
+                topic: device
+                    .to_topic()
+                    .add_attr(DEVICE_ATTRIBUTE_ALERT)
+                    .add_attr(alert_id.as_str())
+                    .build(),
+                qos: QoS::AtLeastOnce,
+                retain: true,
+                payload: homie_str_to_vecu8(alert_msg),
+            },
+            Homie5Message::DeviceAlertCleared { device, alert_id } => Publish {
+                topic: device
+                    .to_topic()
+                    .add_attr(DEVICE_ATTRIBUTE_ALERT)
+                    .add_attr(alert_id.as_str())
+                    .build(),
+                qos: QoS::AtLeastOnce,
+                retain: true,
+                payload: Vec::new(),
+            },
+            Homie5Message::PropertyValue { property, value } => Publish {
+                topic: property.to_topic().build(),
+                qos: QoS::ExactlyOnce,
+                retain: true,
+                payload: homie_str_to_vecu8(value),
+            },
+            Homie5Message::PropertyTarget { property, target } => Publish {
+                topic: property.to_topic().add_attr(PROPERTY_ATTRIBUTE_TARGET).build(),
+                qos: QoS::ExactlyOnce,
+                retain: true,
+                payload: homie_str_to_vecu8(target),
+            },
+            Homie5Message::PropertySet { property, set_value } => Publish {
+                topic: property.to_topic().add_attr(PROPERTY_SET_TOPIC).build(),
+                qos: QoS::ExactlyOnce,
+                retain: false,
+                payload: homie_str_to_vecu8(set_value),
+            },
+            Homie5Message::Broadcast {
+                homie_domain,
+                subtopic,
+                data,
+            } => Publish {
+                topic: TopicBuilder::new(homie_domain)
+                    .add_attr(HOMIE_TOPIC_BROADCAST)
+                    .add_attr(subtopic)
+                    .build(),
+                qos: QoS::ExactlyOnce,
+                retain: false,
+                payload: homie_str_to_vecu8(data),
+            },
+            // an empty retained $state removes the device
+            Homie5Message::DeviceRemoval { device } => Publish {
+                topic: device.to_topic().add_attr(DEVICE_ATTRIBUTE_STATE).build(),
+                qos: QoS::ExactlyOnce,
+                retain: true,
+                payload: Vec::new(),
+            },
+            Homie5Message::DeviceAttributeCleared { device, attribute } => Publish {
+                topic: device.to_topic().add_attr(attribute.as_str()).build(),
+                qos: QoS::ExactlyOnce,
+                retain: true,
+                payload: Vec::new(),
+            },
+        };
+        Some(publish)
+    }
+
+    /// Parses the raw value of a property value, `$target` or set message against the given
+    /// property description.
+    ///
+    /// Returns `None` for messages which are not property messages. The message itself keeps the
+    /// raw value, see also [`PropertyEvent::from_message`] to look up the property in a device
+    /// description.
+    ///
+    /// # Example
+    /// ```rust
+    /// use homie5::*;
+    /// use homie5::device_description::*;
+    ///
+    /// let desc = PropertyDescriptionBuilder::new(HomieDataType::Integer).build();
+    /// let msg = parse_mqtt_message("homie/5/device-1/node/prop", b"42").unwrap();
+    /// assert_eq!(msg.parse_value_with(&desc).unwrap().unwrap(), HomieValue::Integer(42));
+    /// ```
+    pub fn parse_value_with(&self, desc: &HomiePropertyDescription) -> Option<Result<HomieValue, Homie5ProtocolError>> {
+        let (_, _, raw) = property_message(self)?;
+        Some(HomieValue::parse(raw, desc))
+    }
+}
+
+/// The kind of property message a [`PropertyEvent`] was created from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropertyEventKind {
+    /// The current value of the property (`Homie5Message::PropertyValue`)
+    Value,
+    /// The `$target` of the property (`Homie5Message::PropertyTarget`)
+    Target,
+    /// A set command for the property (`Homie5Message::PropertySet`)
+    Set,
+}
+
+/// A property message with its raw value parsed against the property description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyEvent {
+    /// The property the message was received for
+    pub prop_ref: PropertyRef,
+    /// Whether the message was a value, `$target` or set message
+    pub kind: PropertyEventKind,
+    /// The parsed value
+    pub value: HomieValue,
+}
+
+impl PropertyEvent {
+    /// Creates a `PropertyEvent` from a property value, target or set message by parsing the raw
+    /// value against the property in the device description.
+    ///
+    /// Returns `Ok(None)` for messages which are not property messages.
+    ///
+    /// # Errors
+    /// - Returns `Homie5ProtocolError::PropertyNotFound` if the property does not exist in the
+    ///   description.
+    /// - Returns `Homie5ProtocolError::InvalidHomieValue` if the raw value is not valid for the
+    ///   property.
+    pub fn from_message(
+        msg: &Homie5Message,
+        description: &HomieDeviceDescription,
+    ) -> Result<Option<Self>, Homie5ProtocolError> {
+        let Some((prop_ref, kind, raw)) = property_message(msg) else {
+            return Ok(None);
+        };
+        let prop_desc = description
+            .get_property(prop_ref.prop_pointer())
+            .ok_or(Homie5ProtocolError::PropertyNotFound)?;
+
+        Ok(Some(Self {
+            prop_ref: prop_ref.clone(),
+            kind,
+            value: HomieValue::parse(raw, prop_desc)?,
+        }))
+    }
+}
+
+/// A property message enriched with the description of the property and its parsed value, as
+/// returned by [`enrich`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnrichedMessage<'a> {
+    /// The property the message was received for
+    pub prop_ref: PropertyRef,
+    /// Whether the message was a value, `$target` or set message
+    pub kind: PropertyEventKind,
+    /// The description of the property
+    pub description: &'a HomiePropertyDescription,
+    /// The parsed value
+    pub value: HomieValue,
+}
+
+/// Resolves the description of the property a value, `$target` or set message is for and parses
+/// the raw value against it.
+///
+/// `devices` maps device ids to their descriptions. Returns `None` for messages which are not
+/// property messages, for properties of unknown devices or properties not in the description and
+/// for values which are not valid for the property. Use [`PropertyEvent::from_message`] to
+/// distinguish these cases.
+///
+/// # Example
+/// ```rust
+/// use std::collections::HashMap;
+/// use homie5::*;
+/// use homie5::device_description::*;
+///
+/// let description = DeviceDescriptionBuilder::new()
+///     .add_node(
+///         HomieID::new_const("light"),
+///         NodeDescriptionBuilder::new()
+///             .add_property(HomieID::new_const("on"), PropertyDescriptionBuilder::new(HomieDataType::Boolean).build())
+///             .build(),
+///     )
+///     .build();
+/// let devices = HashMap::from([(HomieID::new_const("device-1"), description)]);
+///
+/// let messages = [
+///     parse_mqtt_message("homie/5/device-1/$state", b"ready").unwrap(),
+///     parse_mqtt_message("homie/5/device-1/light/on", b"true").unwrap(),
+/// ];
+/// let values: Vec<_> = messages.iter().filter_map(|msg| enrich(msg, &devices)).map(|e| e.value).collect();
+/// assert_eq!(values, vec![HomieValue::Bool(true)]);
+/// ```
+pub fn enrich<'a>(
+    msg: &Homie5Message,
+    devices: &'a HashMap<HomieID, HomieDeviceDescription>,
+) -> Option<EnrichedMessage<'a>> {
+    let (prop_ref, kind, raw) = property_message(msg)?;
+    let description = devices
+        .get(prop_ref.device_id())?
+        .get_property(prop_ref.prop_pointer())?;
+
+    Some(EnrichedMessage {
+        prop_ref: prop_ref.clone(),
+        kind,
+        description,
+        value: HomieValue::parse(raw, description).ok()?,
+    })
+}
+
+/// A protocol-suspicious message detected by [`classify_with_description`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MessageDiagnostic {
+    /// A value, `$target` or set message for a property which is not in the device description
+    UnknownProperty(PropertyRef),
+    /// A `$target` message for a property which is not settable
+    TargetOnNonSettable(PropertyRef),
+    /// A set message for a property which is not settable
+    SetOnNonSettable(PropertyRef),
+}
+
+/// Checks a parsed message against the description of its device and returns all diagnostics
+/// for suspicious messages, e.g. to detect misbehaving devices or controllers.
+///
+/// [`parse_mqtt_message`] has no knowledge of the device description, so it cannot detect these
+/// cases itself. Messages other than property value, `$target` and set messages are never
+/// flagged. An empty result means nothing suspicious was found.
+pub fn classify_with_description(msg: &Homie5Message, desc: &HomieDeviceDescription) -> Vec<MessageDiagnostic> {
+    let Some((prop_ref, kind, _)) = property_message(msg) else {
+        return Vec::new();
+    };
+    let Some(prop_desc) = desc.get_property(prop_ref.prop_pointer()) else {
+        return vec![MessageDiagnostic::UnknownProperty(prop_ref.clone())];
+    };
+
+    match kind {
+        PropertyEventKind::Target if !prop_desc.settable => {
+            vec![MessageDiagnostic::TargetOnNonSettable(prop_ref.clone())]
+        }
+        PropertyEventKind::Set if !prop_desc.settable => vec![MessageDiagnostic::SetOnNonSettable(prop_ref.clone())],
+        _ => Vec::new(),
+    }
+}
+
+fn property_message(msg: &Homie5Message) -> Option<(&PropertyRef, PropertyEventKind, &str)> {
+    match msg {
+        Homie5Message::PropertyValue { property, value } => Some((property, PropertyEventKind::Value, value)),
+        Homie5Message::PropertyTarget { property, target } => Some((property, PropertyEventKind::Target, target)),
+        Homie5Message::PropertySet { property, set_value } => Some((property, PropertyEventKind::Set, set_value)),
+        _ => None,
+    }
 }
 
 /// Parses an incoming MQTT message into a `Homie5Message`.
@@ -211,142 +527,138 @@ pub enum Homie5Message {
 /// let message = parse_mqtt_message(topic, payload).unwrap();
 /// ```
 pub fn parse_mqtt_message(topic: &str, payload: &[u8]) -> Result<Homie5Message, Homie5ProtocolError> {
-    // Split the topic into components based on '/' delimiter
-    let tokens: Vec<&str> = topic.split('/').collect();
-
-    // Ensure the topic contains at least 4 tokens (e.g. "homie/5/device-id/$state")
-    if tokens.len() <= 3 {
-        return Err(Homie5ProtocolError::InvalidTopic);
+    let result = parse_message(topic, payload);
+    #[cfg(feature = "tracing")]
+    if let Err(err) = &result {
+        tracing::trace!(topic, error = %err, "failed to parse homie message");
     }
+    result
+}
 
-    let homie_domain: HomieDomain = tokens[0].to_owned().try_into()?;
-
-    // Ensure homie version matches to supported version
-    if tokens[1] != HOMIE_VERSION {
-        return Err(Homie5ProtocolError::InvalidTopic);
+/// Parses a homie mqtt message like [`parse_mqtt_message`], but rejects non-conforming device
+/// descriptions which would otherwise be accepted.
+///
+/// A `$description` with an `enum` or `color` property without a format (see
+/// [`crate::device_description::HomiePropertyDescription::is_missing_required_format`]) is
+/// rejected. All other messages are parsed exactly like with [`parse_mqtt_message`].
+///
+/// # Errors
+///
+/// - Returns `Homie5ProtocolError::InvalidDeviceDescription` if the description is missing a
+///   required format.
+/// - Returns all errors of [`parse_mqtt_message`].
+///
+/// # Example
+/// ```rust
+/// use homie5::*;
+/// let payload = br#"{"homie":"5.0","version":1,"nodes":{"n":{"properties":{"p":{"datatype":"enum"}}}}}"#;
+/// assert!(parse_mqtt_message("homie/5/device1/$description", payload).is_ok());
+/// assert!(parse_mqtt_message_strict("homie/5/device1/$description", payload).is_err());
+/// ```
+pub fn parse_mqtt_message_strict(topic: &str, payload: &[u8]) -> Result<Homie5Message, Homie5ProtocolError> {
+    match parse_mqtt_message(topic, payload)? {
+        Homie5Message::DeviceDescription { description, .. } if !description.has_required_formats() => {
+            Err(Homie5ProtocolError::InvalidDeviceDescription)
+        }
+        message => Ok(message),
     }
+}
+
+fn parse_message(topic: &str, payload: &[u8]) -> Result<Homie5Message, Homie5ProtocolError> {
+    let ParsedTopic {
+        homie_domain,
+        device_id,
+        node_id,
+        prop_id,
+        attributes,
+    } = parse_topic(topic)?;
 
     // Handle broadcast messages (e.g. "homie/5/$broadcast")
-    if tokens[2] == "$broadcast" {
+    let Some(device_id) = device_id else {
         return Ok(Homie5Message::Broadcast {
             homie_domain,
-            subtopic: tokens[3..].join("/"),
+            subtopic: attributes.join("/"),
             data: mqtt_payload_to_string(payload)?,
         });
-    }
+    };
 
-    // check the homie id provided
-    let device_id = tokens[2].to_string().try_into()?;
+    let device = DeviceRef {
+        homie_domain,
+        id: device_id,
+    };
 
-    // Match the topic length to identify the message type
-    // len: 0    1  2     3        4       5       6
-    // topic: homie/5/device_id/node_id/prop_id/$target
-    // topic: homie/5/device_id/$state
-    // index:    0  1     2        3       4       5
-    match tokens.len() {
-        4 => {
-            // Device attribute (e.g. "homie/5/device-id/$state")
-            let attr = tokens[3];
-            match attr {
-                // Handle the "$state" attribute
-                "$state" => {
-                    if !payload.is_empty() {
-                        if let Ok(state) = mqtt_payload_to_string(payload)?.try_into() {
-                            Ok(Homie5Message::DeviceState {
-                                device: DeviceRef {
-                                    homie_domain,
-                                    id: device_id,
-                                },
-                                state,
-                            })
-                        } else {
-                            Err(Homie5ProtocolError::InvalidPayload)
-                        }
-                    } else {
-                        // Empty payload signifies device removal
-                        Ok(Homie5Message::DeviceRemoval {
-                            device: DeviceRef {
-                                homie_domain,
-                                id: device_id,
-                            },
-                        })
-                    }
-                }
-                // Handle the "$description" attribute, parsing as JSON
-                "$description" => {
-                    match serde_json::from_str::<HomieDeviceDescription>(&mqtt_payload_to_string(payload)?) {
-                        Ok(description) => Ok(Homie5Message::DeviceDescription {
-                            device: DeviceRef {
-                                homie_domain,
-                                id: device_id,
-                            },
-                            description,
-                        }),
-                        Err(err) => {
-                            log::error!("{:#?}", err);
-                            Err(Homie5ProtocolError::InvalidPayload)
-                        }
-                    }
+    match (node_id, prop_id, attributes.as_slice()) {
+        // Handle the "$state" attribute (e.g. "homie/5/device-id/$state")
+        (None, None, [DEVICE_ATTRIBUTE_STATE]) => {
+            if !payload.is_empty() {
+                if let Ok(state) = mqtt_payload_to_string(payload)?.try_into() {
+                    Ok(Homie5Message::DeviceState { device, state })
+                } else {
+                    Err(Homie5ProtocolError::InvalidPayload)
                 }
-                _ => Err(Homie5ProtocolError::InvalidTopic),
+            } else {
+                // Empty payload signifies device removal
+                Ok(Homie5Message::DeviceRemoval { device })
             }
         }
-        5 => {
-            match tokens[3] {
-                // Handle alert messages (e.g. "device-id/$alert/alert-id")
-                "$alert" => {
-                    let alert_id = HomieID::try_from(tokens[4].to_owned())?;
-                    Ok(Homie5Message::DeviceAlert {
-                        device: DeviceRef {
-                            homie_domain,
-                            id: device_id,
-                        },
-                        alert_id,
-                        alert_msg: mqtt_payload_to_string(payload)?,
-                    })
-                }
-                // Handle the "$log" attribute
-                "$log" => {
-                    let level = DeviceLogLevel::try_from(tokens[4])?;
-                    Ok(Homie5Message::DeviceLog {
-                        device: DeviceRef {
-                            homie_domain,
-                            id: device_id,
-                        },
-                        level,
-                        log_msg: mqtt_payload_to_string(payload)?,
-                    })
-                }
-                // Handle property values (e.g. "device-id/node-id/prop-id")
-                _ => {
-                    let node_id = HomieID::try_from(tokens[3].to_string())?;
-                    let prop_id = HomieID::try_from(tokens[4].to_string())?;
-                    Ok(Homie5Message::PropertyValue {
-                        property: PropertyRef::new(homie_domain, device_id, node_id, prop_id),
-                        value: mqtt_payload_to_string(payload)?,
-                    })
+        // Handle cleared device attributes as published during device removal
+        (None, None, [attribute @ (DEVICE_ATTRIBUTE_DESCRIPTION | DEVICE_ATTRIBUTE_LOG | DEVICE_ATTRIBUTE_ALERT)])
+            if payload.is_empty() =>
+        {
+            let attribute = match *attribute {
+                DEVICE_ATTRIBUTE_DESCRIPTION => DeviceAttribute::Description,
+                DEVICE_ATTRIBUTE_LOG => DeviceAttribute::Log,
+                _ => DeviceAttribute::Alert,
+            };
+            Ok(Homie5Message::DeviceAttributeCleared { device, attribute })
+        }
+        // Handle the "$description" attribute, parsing as JSON
+        (None, None, [DEVICE_ATTRIBUTE_DESCRIPTION]) => {
+            match serde_json::from_str::<HomieDeviceDescription>(&mqtt_payload_to_string(payload)?) {
+                Ok(description) => Ok(Homie5Message::DeviceDescription { device, description }),
+                Err(err) => {
+                    log::error!("{:#?}", err);
+                    Err(Homie5ProtocolError::InvalidPayload)
                 }
             }
         }
-        6 => {
-            // Handle property attributes (e.g. "device-id/node-id/prop-id/$target")
-            let node_id = HomieID::try_from(tokens[3].to_string())?;
-            let prop_id = HomieID::try_from(tokens[4].to_string())?;
-            let attr = tokens[5];
-            match attr {
-                // Handle the "set" action
-                "set" => Ok(Homie5Message::PropertySet {
-                    property: PropertyRef::new(homie_domain, device_id, node_id.to_owned(), prop_id.to_owned()),
-                    set_value: mqtt_payload_to_string(payload)?,
-                }),
-                // Handle the "$target" attribute
-                "$target" => Ok(Homie5Message::PropertyTarget {
-                    property: PropertyRef::new(homie_domain, device_id, node_id, prop_id),
-                    target: mqtt_payload_to_string(payload)?,
-                }),
-                _ => Err(Homie5ProtocolError::InvalidTopic),
+        // Handle alert messages (e.g. "device-id/$alert/alert-id")
+        (None, None, [DEVICE_ATTRIBUTE_ALERT, alert_id]) => {
+            let alert_id = HomieID::try_from(alert_id.to_string())?;
+            // Empty payload signifies a cleared alert
+            if payload.is_empty() {
+                return Ok(Homie5Message::DeviceAlertCleared { device, alert_id });
             }
+            Ok(Homie5Message::DeviceAlert {
+                device,
+                alert_id,
+                alert_msg: mqtt_payload_to_string(payload)?,
+            })
+        }
+        // Handle the "$log" attribute (e.g. "device-id/$log/debug")
+        (None, None, [DEVICE_ATTRIBUTE_LOG, level]) => {
+            let level = DeviceLogLevel::try_from(*level)?;
+            Ok(Homie5Message::DeviceLog {
+                device,
+                level,
+                log_msg: mqtt_payload_to_string(payload)?,
+            })
         }
+        // Handle property values (e.g. "device-id/node-id/prop-id")
+        (Some(node_id), Some(prop_id), []) => Ok(Homie5Message::PropertyValue {
+            property: PropertyRef::new(device.homie_domain, device.id, node_id, prop_id),
+            value: mqtt_payload_to_string(payload)?,
+        }),
+        // Handle the "set" action (e.g. "device-id/node-id/prop-id/set")
+        (Some(node_id), Some(prop_id), [PROPERTY_SET_TOPIC]) => Ok(Homie5Message::PropertySet {
+            property: PropertyRef::new(device.homie_domain, device.id, node_id, prop_id),
+            set_value: mqtt_payload_to_string(payload)?,
+        }),
+        // Handle the "$target" attribute (e.g. "device-id/node-id/prop-id/$target")
+        (Some(node_id), Some(prop_id), [PROPERTY_ATTRIBUTE_TARGET]) => Ok(Homie5Message::PropertyTarget {
+            property: PropertyRef::new(device.homie_domain, device.id, node_id, prop_id),
+            target: mqtt_payload_to_string(payload)?,
+        }),
         _ => Err(Homie5ProtocolError::InvalidTopic),
     }
 }
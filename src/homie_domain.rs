@@ -32,6 +32,7 @@
 
 use core::fmt;
 use std::borrow::Cow;
+use std::str::FromStr;
 
 use crate::DEFAULT_HOMIE_DOMAIN;
 
@@ -279,6 +280,33 @@ impl TryFrom<String> for HomieDomain {
     }
 }
 
+impl FromStr for HomieDomain {
+    type Err = InvalidHomieDomainError;
+
+    /// Parses a `HomieDomain` from a string slice of any lifetime, the custom domain is stored
+    /// as an owned string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidHomieDomainError` if the input string does not conform to the homie5 specifications.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use homie5::HomieDomain;
+    ///
+    /// let domain: HomieDomain = "my-brand".parse().unwrap();
+    /// assert_eq!(domain.as_str(), "my-brand");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            DEFAULT_HOMIE_DOMAIN => Ok(HomieDomain::Default),
+            "+" => Ok(HomieDomain::All),
+            _ => Ok(HomieDomain::Custom(s.to_owned().try_into()?)),
+        }
+    }
+}
+
 #[test]
 fn test_homie_domain() {
     assert_eq!(HomieDomain::try_from("hello").unwrap().to_string(), "hello");
@@ -286,3 +314,13 @@ fn test_homie_domain() {
     assert_eq!(HomieDomain::try_from("homie").unwrap(), HomieDomain::Default);
     assert_eq!(HomieDomain::try_from("+").unwrap(), HomieDomain::All);
 }
+
+#[test]
+fn test_homie_domain_from_str() {
+    let hello = String::from("hello");
+    assert_eq!(hello.parse::<HomieDomain>().unwrap().to_string(), "hello");
+    assert_eq!("homie".parse::<HomieDomain>().unwrap(), HomieDomain::Default);
+    assert_eq!("+".parse::<HomieDomain>().unwrap(), HomieDomain::All);
+    assert!("my/brand".parse::<HomieDomain>().is_err());
+    assert!("".parse::<HomieDomain>().is_err());
+}
@@ -6,6 +6,10 @@
 //! - IDs must only include lowercase letters (`a-z`), digits (`0-9`), and hyphens (`-`).
 //! - IDs must not be empty or contain any other characters.
 //!
+//! As a consequence an ID can never start with `$`, so IDs cannot collide with attribute names
+//! like `$state` or `$target`. IDs equal to the version segment (e.g. `5`) are valid: topic
+//! segments are interpreted by their position, so `homie/5/5/$state` is unambiguous.
+//!
 //! A `HomieID` can be created via `TryFrom<&'static str>` or `TryFrom<String>`. The `'static` lifetime is used for string slices to ensure the ID can be safely sent across threads or through channels, where the ownership or lifetime of the data must be guaranteed for the duration of the program if needed.
 //!
 //! # Why Only `&'static str`?
@@ -74,7 +78,8 @@ impl std::error::Error for InvalidHomieIDError {}
 ///
 /// A `HomieID` ensures that the ID string conforms to the Homie specification:
 /// - Contains only lowercase letters `a` to `z`, numbers `0` to `9`, and hyphens `-`.
-/// - Does not contain any other characters.
+/// - Does not contain any other characters (in particular it never starts with `$`, which is
+///   reserved for attributes).
 /// - Is not empty.
 ///
 /// # Examples
@@ -102,11 +107,45 @@ impl HomieID {
         Self(Cow::Borrowed(id))
     }
 
+    /// Wrap a statically known string into a `HomieID` without validating it.
+    ///
+    /// The caller must guarantee that `id` is a valid `HomieID`, release builds do not check
+    /// this at all. In debug builds invalid ids (e.g. containing `/`) still panic, like with
+    /// [`HomieID::new_const`]. Prefer [`HomieID::new_const`] in `const` contexts, where the
+    /// validation happens at compile time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use homie5::HomieID;
+    ///
+    /// let id = HomieID::new_unchecked("sensor-01");
+    /// assert_eq!(id.as_str(), "sensor-01");
+    /// ```
+    pub const fn new_unchecked(id: &'static str) -> Self {
+        if cfg!(debug_assertions) {
+            if let Err(e) = Self::validate(id) {
+                panic!("{}", e.details);
+            }
+        }
+        Self(Cow::Borrowed(id))
+    }
+
     /// Allows borrowing the inner string slice of the `HomieID`.
     pub fn as_str(&self) -> &str {
         &self.0
     }
 
+    /// Checks if `id` is a valid `HomieID`.
+    ///
+    /// This is a `const fn`, so ids can also be validated at compile time:
+    ///
+    /// ```
+    /// use homie5::HomieID;
+    ///
+    /// const VALID: bool = HomieID::validate("sensor-01").is_ok();
+    /// assert!(VALID);
+    /// ```
     pub const fn validate(id: &str) -> Result<(), InvalidHomieIDError> {
         if id.is_empty() {
             return Err(InvalidHomieIDError::new("Homie ID cannot be empty"));
@@ -19,10 +19,13 @@
 //!
 //! These methods enable referencing Homie devices within the MQTT topic structure.
 
+use serde::{Deserialize, Serialize};
+
+use crate::device_description::HomieDeviceDescription;
 use crate::{HomieDomain, HomieID, NodeRef, PropertyRef, ToTopic, TopicBuilder};
 
 /// Identifies a device via homie-domain and the device id
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct DeviceRef {
     /// the homie_domain (e.g. homie) under which the device is published
     pub(crate) homie_domain: HomieDomain,
@@ -53,6 +56,43 @@ impl DeviceRef {
     pub fn into_parts(self) -> (HomieDomain, HomieID) {
         (self.homie_domain, self.id)
     }
+
+    /// Create a fingerprint of this device with the given description.
+    pub fn fingerprint(&self, description: &HomieDeviceDescription) -> DeviceFingerprint {
+        DeviceFingerprint {
+            device: self.clone(),
+            content_hash: description.content_hash(),
+        }
+    }
+}
+
+/// Identifies a device together with the content of its description.
+///
+/// A controller can persist the fingerprints of its devices and compare them after a restart to
+/// find devices which changed while it was offline. The content hash is based on
+/// [`HomieDeviceDescription::content_hash`], so the announced `version` of the description is not
+/// part of the fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DeviceFingerprint {
+    device: DeviceRef,
+    content_hash: u64,
+}
+
+impl DeviceFingerprint {
+    /// Return the device the fingerprint was created for
+    pub fn device(&self) -> &DeviceRef {
+        &self.device
+    }
+
+    /// Return the content hash of the device description
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash
+    }
+
+    /// Returns true if both fingerprints are for the same device with an unchanged description.
+    pub fn unchanged_since(&self, other: &DeviceFingerprint) -> bool {
+        self == other
+    }
 }
 
 impl PartialEq<PropertyRef> for DeviceRef {
@@ -1,10 +1,12 @@
 mod device_ref;
 mod node_ref;
+mod prepared_property;
 mod property_pointer;
 mod property_ref;
 
 pub use device_ref::*;
 pub use node_ref::*;
+pub use prepared_property::*;
 pub use property_pointer::*;
 pub use property_ref::*;
 
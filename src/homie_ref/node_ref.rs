@@ -19,6 +19,7 @@
 //! - `from_device`: Creates a `NodeRef` from an existing `DeviceRef` and a node ID.
 //! - `node_id`: Returns a reference to the node ID.
 //! - `device_id`: Returns a reference to the device ID that the node belongs to.
+//! - `device_ref`: Returns a reference to the device the node belongs to.
 //!
 //! These methods allow precise identification and referencing of Homie nodes in MQTT topics.
 
@@ -63,9 +64,11 @@ impl NodeRef {
         &self.device.homie_domain
     }
 
+    /// Return a reference to the device the node belongs to
     pub fn device_ref(&self) -> &DeviceRef {
         &self.device
     }
+
     pub fn into_parts(self) -> (HomieDomain, HomieID, HomieID) {
         let (homie_domain, device_id) = self.device.into_parts();
         (homie_domain, device_id, self.id)
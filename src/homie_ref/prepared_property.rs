@@ -0,0 +1,93 @@
+//! A `PropertyRef` with its mqtt topics built ahead of time.
+//!
+//! Devices and controllers updating a single property in a tight loop would otherwise format the
+//! same topics for every publish. A `PreparedProperty` builds the value, `$target` and `set`
+//! topics once, so each publish only needs to copy the already built topic. The copy is still an
+//! allocation, as the topic of a `Publish` is an owned `String`.
+//!
+//! Use `prepare_property` of the device or controller protocol to build the topics for the
+//! version of the protocol. [`PreparedProperty::new`] always uses the default homie version.
+//!
+//! # Example
+//!
+//! ```rust
+//! use homie5::{HomieDomain, HomieID, PreparedProperty, PropertyRef};
+//!
+//! let prop_ref = PropertyRef::new(
+//!     HomieDomain::Default,
+//!     HomieID::new_const("device-01"),
+//!     HomieID::new_const("node-01"),
+//!     HomieID::new_const("temperature"),
+//! );
+//! let prepared = PreparedProperty::new(prop_ref);
+//!
+//! assert_eq!(prepared.value_topic(), "homie/5/device-01/node-01/temperature");
+//! assert_eq!(prepared.target_topic(), "homie/5/device-01/node-01/temperature/$target");
+//! ```
+
+use crate::{AsPropPointer, PropertyRef, ToTopic, HOMIE_VERSION, PROPERTY_ATTRIBUTE_TARGET, PROPERTY_SET_TOPIC};
+
+use super::PropertyPointer;
+
+/// A `PropertyRef` together with its prebuilt value, `$target` and `set` topics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreparedProperty {
+    property: PropertyRef,
+    value_topic: String,
+    target_topic: String,
+    set_topic: String,
+}
+
+impl PreparedProperty {
+    /// Builds all topics for the property using the default homie version.
+    pub fn new(property: PropertyRef) -> Self {
+        Self::new_for_version(property, HOMIE_VERSION)
+    }
+
+    /// Builds all topics for the property using the given homie version segment.
+    pub(crate) fn new_for_version(property: PropertyRef, version: &str) -> Self {
+        let topic = property.to_topic().with_version(version);
+        Self {
+            value_topic: topic.clone().build(),
+            target_topic: topic.clone().add_attr(PROPERTY_ATTRIBUTE_TARGET).build(),
+            set_topic: topic.add_attr(PROPERTY_SET_TOPIC).build(),
+            property,
+        }
+    }
+
+    /// Returns the property the topics were built for.
+    pub fn property(&self) -> &PropertyRef {
+        &self.property
+    }
+
+    /// Returns the mqtt topic the property value is published to
+    pub fn value_topic(&self) -> &str {
+        &self.value_topic
+    }
+
+    /// Returns the mqtt topic the `$target` attribute of the property is published to
+    pub fn target_topic(&self) -> &str {
+        &self.target_topic
+    }
+
+    /// Returns the mqtt topic set commands for the property are published to
+    pub fn set_topic(&self) -> &str {
+        &self.set_topic
+    }
+
+    pub fn into_property(self) -> PropertyRef {
+        self.property
+    }
+}
+
+impl From<PropertyRef> for PreparedProperty {
+    fn from(property: PropertyRef) -> Self {
+        Self::new(property)
+    }
+}
+
+impl AsPropPointer for PreparedProperty {
+    fn as_prop_pointer(&self) -> &PropertyPointer {
+        self.property.as_prop_pointer()
+    }
+}
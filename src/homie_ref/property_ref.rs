@@ -24,11 +24,13 @@
 //! - `prop_id`: Returns a reference to the property ID.
 //! - `node_id`: Returns a reference to the node ID the property belongs to.
 //! - `device_id`: Returns a reference to the device ID that the property belongs to.
+//! - `device_ref`/`node_ref`: Return the device and the node the property belongs to.
+//! - `value_topic`/`target_topic`: Return the mqtt topics of the property value and its `$target` attribute.
 //!
 //! These methods allow precise identification and referencing of Homie properties in MQTT topics.
 
 use crate::AsPropPointer;
-use crate::{AsNodeId, DeviceRef, HomieDomain, HomieID, NodeRef, ToTopic, TopicBuilder};
+use crate::{AsNodeId, DeviceRef, HomieDomain, HomieID, NodeRef, ToTopic, TopicBuilder, PROPERTY_ATTRIBUTE_TARGET};
 
 use super::PropertyPointer;
 
@@ -80,10 +82,18 @@ impl PropertyRef {
         &self.device.homie_domain
     }
 
+    /// Return a reference to the device the property belongs to
     pub fn device_ref(&self) -> &DeviceRef {
         &self.device
     }
 
+    /// Return the node the property belongs to
+    ///
+    /// The node is not stored as part of the `PropertyRef`, so a new `NodeRef` is created.
+    pub fn node_ref(&self) -> NodeRef {
+        NodeRef::from_device(self.device.clone(), self.prop_pointer.node_id.clone())
+    }
+
     pub fn prop_pointer(&self) -> &PropertyPointer {
         &self.prop_pointer
     }
@@ -95,6 +105,16 @@ impl PropertyRef {
         &self.device == device && &self.prop_pointer.node_id == node_id && &self.prop_pointer.prop_id == prop_id
     }
 
+    /// Return the mqtt topic the property value is published to
+    pub fn value_topic(&self) -> String {
+        self.to_topic().build()
+    }
+
+    /// Return the mqtt topic the `$target` attribute of the property is published to
+    pub fn target_topic(&self) -> String {
+        self.to_topic().add_attr(PROPERTY_ATTRIBUTE_TARGET).build()
+    }
+
     pub fn into_parts(self) -> (HomieDomain, HomieID, HomieID, HomieID) {
         let (homie_domain, device_id) = self.device.into_parts();
         let (node_id, prop_id) = self.prop_pointer.into_parts();
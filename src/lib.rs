@@ -20,6 +20,8 @@
 
 pub mod client;
 mod controller_proto;
+mod controller_snapshot;
+mod device_activity;
 pub mod device_description;
 mod device_proto;
 mod error;
@@ -29,15 +31,21 @@ mod homie_domain;
 mod homie_id;
 mod homie_ref;
 mod statemachine;
+mod topic;
+mod unit;
 mod value;
 
 pub use controller_proto::*;
+pub use controller_snapshot::*;
+pub use device_activity::*;
 pub use device_proto::*;
 pub use error::Homie5ProtocolError;
 pub use homie5_message::*;
 pub use homie_domain::*;
 pub use homie_id::*;
 pub use homie_ref::*;
+pub use topic::*;
+pub use unit::*;
 pub use value::*;
 
 use serde::{Deserialize, Serialize};
@@ -198,7 +206,7 @@ pub enum HomieDeviceStatus {
 }
 impl HomieDeviceStatus {
     /// Returns the &str representation of the device status
-    pub fn as_str(&self) -> &str {
+    pub fn as_str(&self) -> &'static str {
         match self {
             HomieDeviceStatus::Init => "init",
             HomieDeviceStatus::Ready => "ready",
@@ -207,6 +215,11 @@ impl HomieDeviceStatus {
             HomieDeviceStatus::Lost => "lost",
         }
     }
+
+    /// Returns the device status as the payload bytes of the `$state` attribute
+    pub fn as_bytes(&self) -> &'static [u8] {
+        self.as_str().as_bytes()
+    }
 }
 impl Debug for HomieDeviceStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -283,6 +296,11 @@ impl DeviceLogLevel {
             DeviceLogLevel::Fatal => "fatal",
         }
     }
+
+    /// Returns the log level as bytes, matching [`DeviceLogLevel::as_str`]
+    pub fn as_bytes(&self) -> &'static [u8] {
+        self.as_str().as_bytes()
+    }
 }
 
 impl fmt::Display for DeviceLogLevel {
@@ -320,6 +338,42 @@ impl TryFrom<&str> for DeviceLogLevel {
         }
     }
 }
+/// The attributes published by a device (`$state`, `$log`, `$alert`, `$description`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeviceAttribute {
+    State,
+    Log,
+    Alert,
+    Description,
+}
+
+impl DeviceAttribute {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceAttribute::State => DEVICE_ATTRIBUTE_STATE,
+            DeviceAttribute::Log => DEVICE_ATTRIBUTE_LOG,
+            DeviceAttribute::Alert => DEVICE_ATTRIBUTE_ALERT,
+            DeviceAttribute::Description => DEVICE_ATTRIBUTE_DESCRIPTION,
+        }
+    }
+
+    /// Returns the attribute as a static single element slice as used by the subscription iterators
+    pub(crate) fn as_slice(&self) -> &'static [&'static str] {
+        match self {
+            DeviceAttribute::State => &[DEVICE_ATTRIBUTE_STATE],
+            DeviceAttribute::Log => &[DEVICE_ATTRIBUTE_LOG],
+            DeviceAttribute::Alert => &[DEVICE_ATTRIBUTE_ALERT],
+            DeviceAttribute::Description => &[DEVICE_ATTRIBUTE_DESCRIPTION],
+        }
+    }
+}
+
+impl fmt::Display for DeviceAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// This trait provides the capability to provide a mqtt topic for an object defining where it is
 /// published on the broker
 pub trait ToTopic {
@@ -330,6 +384,8 @@ pub trait ToTopic {
 #[derive(Default, Debug, Clone, Hash, PartialEq, Eq)]
 pub struct TopicBuilder {
     topic: String,
+    // byte range of the version segment within the topic
+    version_range: (usize, usize),
 }
 
 impl Display for TopicBuilder {
@@ -344,8 +400,22 @@ impl TopicBuilder {
         let mut topic = String::with_capacity(96);
         topic.push_str(homie_domain.as_str());
         topic.push('/');
+        let version_start = topic.len();
         topic.push_str(HOMIE_VERSION);
-        Self { topic }
+        Self {
+            version_range: (version_start, topic.len()),
+            topic,
+        }
+    }
+
+    /// Replaces the homie version segment (`HOMIE_VERSION` by default) of the topic.
+    ///
+    /// This is intended for testing and simulating other protocol versions only.
+    pub fn with_version(mut self, version: &str) -> Self {
+        let (start, end) = self.version_range;
+        self.topic.replace_range(start..end, version);
+        self.version_range = (start, start + version.len());
+        self
     }
 
     pub fn new_for_device(homie_domain: &HomieDomain, device_id: &HomieID) -> Self {
@@ -363,12 +433,38 @@ impl TopicBuilder {
     ) -> Self {
         Self::new_for_node(homie_domain, device_id, node_id).add_id(property_id)
     }
+    /// Appends an attribute segment (e.g. `$state`) to the topic.
+    ///
+    /// The segment is appended as is, use [`Self::try_add_attr`] for segments which are not
+    /// known to be valid.
     pub fn add_attr(mut self, attr: &str) -> Self {
         self.topic.push('/');
         self.topic.push_str(attr);
         self
     }
 
+    /// Appends an attribute segment to the topic after checking that it is a single valid topic
+    /// level.
+    ///
+    /// # Errors
+    /// Returns `Homie5ProtocolError::InvalidTopicSegment` if the segment is empty or contains a
+    /// topic level separator (`/`) or a wildcard (`+`, `#`).
+    ///
+    /// # Example
+    /// ```rust
+    /// use homie5::*;
+    ///
+    /// let builder = TopicBuilder::new_for_device(&HomieDomain::Default, &HomieID::new_const("device-1"));
+    /// assert!(builder.clone().try_add_attr("$meta").is_ok());
+    /// assert!(builder.try_add_attr("$meta/extra").is_err());
+    /// ```
+    pub fn try_add_attr(self, attr: &str) -> Result<Self, Homie5ProtocolError> {
+        if attr.is_empty() || attr.contains(['/', '+', '#']) {
+            return Err(Homie5ProtocolError::InvalidTopicSegment(attr.to_owned()));
+        }
+        Ok(self.add_attr(attr))
+    }
+
     pub fn add_id(mut self, id: &HomieID) -> Self {
         self.topic.push('/');
         self.topic.push_str(id.as_str());
@@ -431,3 +527,33 @@ pub const HOMIE_UNIT_KELVIN: &str = "K";
 pub const HOMIE_UNIT_MIRED: &str = "MK⁻¹";
 /// unit for countable amounts
 pub const HOMIE_UNIT_COUNT_AMOUNT: &str = "#";
+
+/// All units recommended by the homie convention
+pub const HOMIE_UNITS: [&str; 26] = [
+    HOMIE_UNIT_DEGREE_CELSIUS,
+    HOMIE_UNIT_DEGREE_FAHRENHEIT,
+    HOMIE_UNIT_DEGREE,
+    HOMIE_UNIT_LITER,
+    HOMIE_UNIT_GALLON,
+    HOMIE_UNIT_VOLT,
+    HOMIE_UNIT_WATT,
+    HOMIE_UNIT_KILOWATT,
+    HOMIE_UNIT_KILOWATTHOUR,
+    HOMIE_UNIT_AMPERE,
+    HOMIE_UNIT_HERTZ,
+    HOMIE_UNIT_MILI_AMPERE,
+    HOMIE_UNIT_PERCENT,
+    HOMIE_UNIT_METER,
+    HOMIE_UNIT_CUBIC_METER,
+    HOMIE_UNIT_FEET,
+    HOMIE_UNIT_PASCAL,
+    HOMIE_UNIT_KILOPASCAL,
+    HOMIE_UNIT_PSI,
+    HOMIE_UNIT_SECONDS,
+    HOMIE_UNIT_MINUTES,
+    HOMIE_UNIT_HOURS,
+    HOMIE_UNIT_LUX,
+    HOMIE_UNIT_KELVIN,
+    HOMIE_UNIT_MIRED,
+    HOMIE_UNIT_COUNT_AMOUNT,
+];
@@ -0,0 +1,257 @@
+//! Provides a single place to split a homie mqtt topic into its typed segments.
+//!
+//! Both [`crate::parse_mqtt_message`] and the extension message parsers build on [`parse_topic`]
+//! so that the rules on how a topic is interpreted only exist once.
+//!
+//! # Example
+//!
+//! ```rust
+//! use homie5::*;
+//!
+//! let topic = parse_topic("homie/5/device-1/node-1/prop-1/$target").unwrap();
+//! assert_eq!(topic.device_id.unwrap().as_str(), "device-1");
+//! assert_eq!(topic.node_id.unwrap().as_str(), "node-1");
+//! assert_eq!(topic.prop_id.unwrap().as_str(), "prop-1");
+//! assert_eq!(topic.attributes, vec!["$target"]);
+//! ```
+
+use crate::{
+    error::Homie5ProtocolError, HomieDomain, HomieID, HOMIE_TOPIC_BROADCAST, HOMIE_VERSION, PROPERTY_ATTRIBUTE_TARGET,
+    PROPERTY_SET_TOPIC,
+};
+
+/// The typed segments of a homie mqtt topic.
+///
+/// - For broadcast topics (`homie/5/$broadcast/...`) `device_id` is `None` and `attributes`
+///   contains the segments of the broadcast subtopic.
+/// - For device attributes (`homie/5/<device-id>/$state`) only `device_id` is set and
+///   `attributes` contains all segments starting at the attribute (e.g. `["$log", "debug"]`).
+/// - For node attributes (`homie/5/<device-id>/<node-id>/$meta`) `node_id` is set as well.
+/// - For properties (`homie/5/<device-id>/<node-id>/<prop-id>/...`) `prop_id` is set and
+///   `attributes` contains the trailing segments (e.g. `["set"]`, `["$target"]` or none for the value).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedTopic<'a> {
+    /// The homie domain the topic belongs to
+    pub homie_domain: HomieDomain,
+    /// The device id, `None` for broadcast topics
+    pub device_id: Option<HomieID>,
+    /// The node id, if the topic addresses a node or property
+    pub node_id: Option<HomieID>,
+    /// The property id, if the topic addresses a property
+    pub prop_id: Option<HomieID>,
+    /// The remaining (not yet interpreted) topic segments
+    pub attributes: Vec<&'a str>,
+}
+
+impl ParsedTopic<'_> {
+    /// Returns true if the topic is a broadcast topic.
+    pub fn is_broadcast(&self) -> bool {
+        self.device_id.is_none()
+    }
+}
+
+/// Splits a homie mqtt topic into its typed segments.
+///
+/// The homie domain, the version segment and all node and property ids are validated. The
+/// trailing attribute segments are returned as is and are up to the caller to interpret.
+///
+/// # Errors
+///
+/// - Returns `Homie5ProtocolError::InvalidTopic` if the topic has less than 4 segments or is for
+///   a different homie version.
+/// - Returns `Homie5ProtocolError::InvalidHomieDomain`/`InvalidHomieID` if one of the segments is
+///   not valid.
+pub fn parse_topic(topic: &str) -> Result<ParsedTopic<'_>, Homie5ProtocolError> {
+    // Split the topic into components based on '/' delimiter
+    let tokens: Vec<&str> = topic.split('/').collect();
+
+    // Ensure the topic contains at least 4 tokens (e.g. "homie/5/device-id/$state")
+    if tokens.len() <= 3 {
+        return Err(Homie5ProtocolError::InvalidTopic);
+    }
+
+    let homie_domain: HomieDomain = tokens[0].to_owned().try_into()?;
+
+    // Ensure homie version matches to supported version
+    if tokens[1] != HOMIE_VERSION {
+        return Err(Homie5ProtocolError::InvalidTopic);
+    }
+
+    // Handle broadcast messages (e.g. "homie/5/$broadcast/alarm")
+    if tokens[2] == HOMIE_TOPIC_BROADCAST {
+        return Ok(ParsedTopic {
+            homie_domain,
+            device_id: None,
+            node_id: None,
+            prop_id: None,
+            attributes: tokens[3..].to_vec(),
+        });
+    }
+
+    let device_id = HomieID::try_from(tokens[2].to_owned())?;
+
+    // the first segment starting with a `$` (or the `set` segment after a property) starts the
+    // attributes
+    // index:    0  1     2        3       4       5
+    // topic: homie/5/device_id/node_id/prop_id/$target
+    let mut ids = [None, None];
+    let mut index = 3;
+    while index < 5 && index < tokens.len() && !tokens[index].starts_with('$') {
+        ids[index - 3] = Some(HomieID::try_from(tokens[index].to_owned())?);
+        index += 1;
+    }
+    let [node_id, prop_id] = ids;
+
+    Ok(ParsedTopic {
+        homie_domain,
+        device_id: Some(device_id),
+        node_id,
+        prop_id,
+        attributes: tokens[index..].to_vec(),
+    })
+}
+
+/// The kind of message a homie mqtt topic is for, as determined by [`classify_topic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TopicKind {
+    /// A broadcast message (`homie/5/$broadcast/...`)
+    Broadcast,
+    /// A device attribute (e.g. `homie/5/<device-id>/$state` or `homie/5/<device-id>/$log/info`)
+    DeviceAttribute,
+    /// A node attribute (e.g. `homie/5/<device-id>/<node-id>/$meta`)
+    NodeAttribute,
+    /// A property value (`homie/5/<device-id>/<node-id>/<prop-id>`)
+    PropertyValue,
+    /// A set command for a property (`homie/5/<device-id>/<node-id>/<prop-id>/set`)
+    PropertySet,
+    /// A property target (`homie/5/<device-id>/<node-id>/<prop-id>/$target`)
+    PropertyTarget,
+    /// Any other property attribute (e.g. `homie/5/<device-id>/<node-id>/<prop-id>/$meta`)
+    PropertyAttribute,
+}
+
+/// Cheaply determines what kind of message a homie mqtt topic is for.
+///
+/// This is meant for dispatching raw mqtt messages before parsing them with
+/// [`crate::parse_mqtt_message`]. Only the structure of the topic and the version segment are
+/// checked, the homie domain and the ids are not validated.
+///
+/// Returns `None` if the topic is not a homie topic of the supported version.
+///
+/// # Example
+///
+/// ```rust
+/// use homie5::*;
+///
+/// assert_eq!(classify_topic("homie/5/device-1/$state"), Some(TopicKind::DeviceAttribute));
+/// assert_eq!(classify_topic("homie/5/device-1/node-1/prop-1/set"), Some(TopicKind::PropertySet));
+/// assert_eq!(classify_topic("homie/4/device-1/$state"), None);
+/// ```
+pub fn classify_topic(topic: &str) -> Option<TopicKind> {
+    let mut tokens = topic.split('/');
+
+    // index:    0  1     2        3       4       5
+    // topic: homie/5/device_id/node_id/prop_id/$target
+    let (_domain, version, device) = (tokens.next()?, tokens.next()?, tokens.next()?);
+    if version != HOMIE_VERSION {
+        return None;
+    }
+    if device == HOMIE_TOPIC_BROADCAST {
+        return tokens.next().map(|_| TopicKind::Broadcast);
+    }
+
+    let node = tokens.next()?;
+    if node.starts_with('$') {
+        return Some(TopicKind::DeviceAttribute);
+    }
+    let prop = tokens.next()?;
+    if prop.starts_with('$') {
+        return Some(TopicKind::NodeAttribute);
+    }
+
+    match (tokens.next(), tokens.next()) {
+        (None, _) => Some(TopicKind::PropertyValue),
+        (Some(PROPERTY_SET_TOPIC), None) => Some(TopicKind::PropertySet),
+        (Some(PROPERTY_ATTRIBUTE_TARGET), None) => Some(TopicKind::PropertyTarget),
+        (Some(attr), _) if attr.starts_with('$') => Some(TopicKind::PropertyAttribute),
+        _ => None,
+    }
+}
+
+/// Returns true if the mqtt `topic` matches the subscription `filter`.
+///
+/// Implements the mqtt wildcard rules without allocating:
+/// - `+` matches exactly one topic level (which may be empty)
+/// - `#` matches any number of levels including the parent level (`homie/#` matches `homie`), it
+///   must be the last level of the filter
+/// - filters starting with a wildcard do not match topics starting with `$`
+///
+/// Invalid filters (e.g. `#` not being the last level or wildcards mixed with other characters
+/// in a level) never match.
+///
+/// # Example
+///
+/// ```rust
+/// use homie5::topic_matches_filter;
+///
+/// assert!(topic_matches_filter("homie/5/device-1/$state", "homie/5/+/$state"));
+/// assert!(!topic_matches_filter("homie/5/device-1/node/prop", "homie/5/+/$state"));
+/// ```
+pub fn topic_matches_filter(topic: &str, filter: &str) -> bool {
+    if topic.starts_with('$') && (filter.starts_with('+') || filter.starts_with('#')) {
+        return false;
+    }
+
+    let mut topic_levels = topic.split('/');
+    let mut filter_levels = filter.split('/').peekable();
+
+    while let Some(filter_level) = filter_levels.next() {
+        match filter_level {
+            "#" => return filter_levels.peek().is_none(),
+            "+" => {
+                if topic_levels.next().is_none() {
+                    return false;
+                }
+            }
+            _ if filter_level.contains(['+', '#']) => return false,
+            _ => {
+                if topic_levels.next() != Some(filter_level) {
+                    return false;
+                }
+            }
+        }
+    }
+
+    topic_levels.next().is_none()
+}
+
+/// Cheaply extracts the homie domain and the device id of a homie mqtt topic.
+///
+/// Only the first three segments are parsed and validated, the rest of the topic is ignored.
+/// This is meant for routing or partitioning messages by device before fully parsing them.
+/// For broadcast topics the device id is `None`.
+///
+/// Returns `None` if the topic is not a homie topic of the supported version.
+///
+/// # Example
+///
+/// ```rust
+/// use homie5::*;
+///
+/// let (domain, device_id) = topic_device("homie/5/device-1/node-1/prop-1").unwrap();
+/// assert_eq!(domain, HomieDomain::Default);
+/// assert_eq!(device_id.unwrap().as_str(), "device-1");
+/// ```
+pub fn topic_device(topic: &str) -> Option<(HomieDomain, Option<HomieID>)> {
+    let mut tokens = topic.splitn(4, '/');
+    let (domain, version, device, _rest) = (tokens.next()?, tokens.next()?, tokens.next()?, tokens.next()?);
+    if version != HOMIE_VERSION {
+        return None;
+    }
+    let homie_domain = HomieDomain::try_from(domain.to_owned()).ok()?;
+    if device == HOMIE_TOPIC_BROADCAST {
+        return Some((homie_domain, None));
+    }
+    let device_id = HomieID::try_from(device.to_owned()).ok()?;
+    Some((homie_domain, Some(device_id)))
+}
@@ -0,0 +1,197 @@
+//! Typed representation of the units recommended by the homie convention.
+//!
+//! [`HomieUnit`] maps the `HOMIE_UNIT_*` constants to an enum which can be introspected (e.g.
+//! grouped by [`UnitDimension`]) while still accepting any custom unit string.
+//!
+//! # Example
+//!
+//! ```rust
+//! use homie5::*;
+//!
+//! let unit: HomieUnit = "kWh".parse().unwrap();
+//! assert_eq!(unit, HomieUnit::KilowattHour);
+//! assert_eq!(unit.dimension(), Some(UnitDimension::Energy));
+//!
+//! let unit: HomieUnit = "ppm".parse().unwrap();
+//! assert_eq!(unit, HomieUnit::Custom("ppm".to_owned()));
+//! assert_eq!(unit.dimension(), None);
+//! ```
+
+use std::convert::Infallible;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{
+    HOMIE_UNIT_AMPERE, HOMIE_UNIT_COUNT_AMOUNT, HOMIE_UNIT_CUBIC_METER, HOMIE_UNIT_DEGREE, HOMIE_UNIT_DEGREE_CELSIUS,
+    HOMIE_UNIT_DEGREE_FAHRENHEIT, HOMIE_UNIT_FEET, HOMIE_UNIT_GALLON, HOMIE_UNIT_HERTZ, HOMIE_UNIT_HOURS,
+    HOMIE_UNIT_KELVIN, HOMIE_UNIT_KILOPASCAL, HOMIE_UNIT_KILOWATT, HOMIE_UNIT_KILOWATTHOUR, HOMIE_UNIT_LITER,
+    HOMIE_UNIT_LUX, HOMIE_UNIT_METER, HOMIE_UNIT_MILI_AMPERE, HOMIE_UNIT_MINUTES, HOMIE_UNIT_MIRED, HOMIE_UNIT_PASCAL,
+    HOMIE_UNIT_PERCENT, HOMIE_UNIT_PSI, HOMIE_UNIT_SECONDS, HOMIE_UNIT_VOLT, HOMIE_UNIT_WATT,
+};
+
+/// The physical dimension a [`HomieUnit`] measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitDimension {
+    Temperature,
+    Angle,
+    Volume,
+    Voltage,
+    Power,
+    Energy,
+    Current,
+    Frequency,
+    Ratio,
+    Length,
+    Pressure,
+    Time,
+    Illuminance,
+    ColorTemperature,
+    Count,
+}
+
+/// A unit of a property value.
+///
+/// All units recommended by the homie convention have their own variant, any other unit is
+/// represented as `HomieUnit::Custom`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HomieUnit {
+    DegreeCelsius,
+    DegreeFahrenheit,
+    Degree,
+    Liter,
+    Gallon,
+    Volt,
+    Watt,
+    Kilowatt,
+    KilowattHour,
+    Ampere,
+    Hertz,
+    MilliAmpere,
+    Percent,
+    Meter,
+    CubicMeter,
+    Feet,
+    Pascal,
+    Kilopascal,
+    Psi,
+    Seconds,
+    Minutes,
+    Hours,
+    Lux,
+    Kelvin,
+    Mired,
+    CountAmount,
+    /// A unit not recommended by the homie convention
+    Custom(String),
+}
+
+impl HomieUnit {
+    /// All units recommended by the homie convention, in the order of [`crate::HOMIE_UNITS`]
+    pub const KNOWN: [HomieUnit; 26] = [
+        HomieUnit::DegreeCelsius,
+        HomieUnit::DegreeFahrenheit,
+        HomieUnit::Degree,
+        HomieUnit::Liter,
+        HomieUnit::Gallon,
+        HomieUnit::Volt,
+        HomieUnit::Watt,
+        HomieUnit::Kilowatt,
+        HomieUnit::KilowattHour,
+        HomieUnit::Ampere,
+        HomieUnit::Hertz,
+        HomieUnit::MilliAmpere,
+        HomieUnit::Percent,
+        HomieUnit::Meter,
+        HomieUnit::CubicMeter,
+        HomieUnit::Feet,
+        HomieUnit::Pascal,
+        HomieUnit::Kilopascal,
+        HomieUnit::Psi,
+        HomieUnit::Seconds,
+        HomieUnit::Minutes,
+        HomieUnit::Hours,
+        HomieUnit::Lux,
+        HomieUnit::Kelvin,
+        HomieUnit::Mired,
+        HomieUnit::CountAmount,
+    ];
+
+    /// Returns the unit string as used in the property description.
+    pub fn as_str(&self) -> &str {
+        match self {
+            HomieUnit::DegreeCelsius => HOMIE_UNIT_DEGREE_CELSIUS,
+            HomieUnit::DegreeFahrenheit => HOMIE_UNIT_DEGREE_FAHRENHEIT,
+            HomieUnit::Degree => HOMIE_UNIT_DEGREE,
+            HomieUnit::Liter => HOMIE_UNIT_LITER,
+            HomieUnit::Gallon => HOMIE_UNIT_GALLON,
+            HomieUnit::Volt => HOMIE_UNIT_VOLT,
+            HomieUnit::Watt => HOMIE_UNIT_WATT,
+            HomieUnit::Kilowatt => HOMIE_UNIT_KILOWATT,
+            HomieUnit::KilowattHour => HOMIE_UNIT_KILOWATTHOUR,
+            HomieUnit::Ampere => HOMIE_UNIT_AMPERE,
+            HomieUnit::Hertz => HOMIE_UNIT_HERTZ,
+            HomieUnit::MilliAmpere => HOMIE_UNIT_MILI_AMPERE,
+            HomieUnit::Percent => HOMIE_UNIT_PERCENT,
+            HomieUnit::Meter => HOMIE_UNIT_METER,
+            HomieUnit::CubicMeter => HOMIE_UNIT_CUBIC_METER,
+            HomieUnit::Feet => HOMIE_UNIT_FEET,
+            HomieUnit::Pascal => HOMIE_UNIT_PASCAL,
+            HomieUnit::Kilopascal => HOMIE_UNIT_KILOPASCAL,
+            HomieUnit::Psi => HOMIE_UNIT_PSI,
+            HomieUnit::Seconds => HOMIE_UNIT_SECONDS,
+            HomieUnit::Minutes => HOMIE_UNIT_MINUTES,
+            HomieUnit::Hours => HOMIE_UNIT_HOURS,
+            HomieUnit::Lux => HOMIE_UNIT_LUX,
+            HomieUnit::Kelvin => HOMIE_UNIT_KELVIN,
+            HomieUnit::Mired => HOMIE_UNIT_MIRED,
+            HomieUnit::CountAmount => HOMIE_UNIT_COUNT_AMOUNT,
+            HomieUnit::Custom(unit) => unit,
+        }
+    }
+
+    /// Returns the dimension the unit measures, `None` for custom units.
+    pub fn dimension(&self) -> Option<UnitDimension> {
+        let dimension = match self {
+            HomieUnit::DegreeCelsius | HomieUnit::DegreeFahrenheit | HomieUnit::Kelvin => UnitDimension::Temperature,
+            HomieUnit::Degree => UnitDimension::Angle,
+            HomieUnit::Liter | HomieUnit::Gallon | HomieUnit::CubicMeter => UnitDimension::Volume,
+            HomieUnit::Volt => UnitDimension::Voltage,
+            HomieUnit::Watt | HomieUnit::Kilowatt => UnitDimension::Power,
+            HomieUnit::KilowattHour => UnitDimension::Energy,
+            HomieUnit::Ampere | HomieUnit::MilliAmpere => UnitDimension::Current,
+            HomieUnit::Hertz => UnitDimension::Frequency,
+            HomieUnit::Percent => UnitDimension::Ratio,
+            HomieUnit::Meter | HomieUnit::Feet => UnitDimension::Length,
+            HomieUnit::Pascal | HomieUnit::Kilopascal | HomieUnit::Psi => UnitDimension::Pressure,
+            HomieUnit::Seconds | HomieUnit::Minutes | HomieUnit::Hours => UnitDimension::Time,
+            HomieUnit::Lux => UnitDimension::Illuminance,
+            HomieUnit::Mired => UnitDimension::ColorTemperature,
+            HomieUnit::CountAmount => UnitDimension::Count,
+            HomieUnit::Custom(_) => return None,
+        };
+        Some(dimension)
+    }
+
+    /// Returns true if the unit is not one of the units recommended by the homie convention.
+    pub fn is_custom(&self) -> bool {
+        matches!(self, HomieUnit::Custom(_))
+    }
+}
+
+impl FromStr for HomieUnit {
+    type Err = Infallible;
+
+    /// Parses a unit string, unknown units are returned as `HomieUnit::Custom`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(HomieUnit::KNOWN
+            .into_iter()
+            .find(|unit| unit.as_str() == s)
+            .unwrap_or_else(|| HomieUnit::Custom(s.to_owned())))
+    }
+}
+
+impl Display for HomieUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -27,6 +27,10 @@ pub enum Homie5ValueConversionError {
     UnsupportedColorFormat(ColorFormat, Vec<ColorFormat>),
     InvalidBooleanFormat(String),
     JsonParseError(String),
+    /// The JSON schema in the format of a json property is invalid (only with the `jsonschema` feature).
+    InvalidJsonSchema(String),
+    /// The value does not hold the expected datatype (expected, actual). `None` for an empty value.
+    DatatypeMismatch(HomieDataType, Option<HomieDataType>),
 }
 impl fmt::Display for Homie5ValueConversionError {
     /// Formats the error message for display purposes.
@@ -79,6 +83,15 @@ impl fmt::Display for Homie5ValueConversionError {
             Homie5ValueConversionError::JsonParseError(error) => {
                 write!(f, "Error parsing json value: {}", error)
             }
+            Homie5ValueConversionError::InvalidJsonSchema(error) => {
+                write!(f, "Invalid json schema: {}", error)
+            }
+            Homie5ValueConversionError::DatatypeMismatch(expected, Some(actual)) => {
+                write!(f, "Expected a value of type '{}' but got '{}'", expected, actual)
+            }
+            Homie5ValueConversionError::DatatypeMismatch(expected, None) => {
+                write!(f, "Expected a value of type '{}' but got an empty value", expected)
+            }
         }
     }
 }
@@ -125,6 +138,9 @@ pub enum HomieColorValue {
     /// Represents a color in the XYZ color space, using two floating-point values for X and Y.
     /// The Z value is calculated as `1 - X - Y`, and all values range from 0.0 to 1.0.
     ///   - Example: `"xyz,0.25,0.34"`.
+    ///
+    /// Z is fully determined by X and Y: it is neither published nor compared. Use
+    /// [`HomieColorValue::new_xyz`] to construct a value with a consistent Z.
     XYZ(f64, f64, f64),
 }
 
@@ -145,8 +161,10 @@ impl PartialEq for HomieColorValue {
         match (self, other) {
             (HomieColorValue::RGB(r1, g1, b1), HomieColorValue::RGB(r2, g2, b2)) => r1 == r2 && g1 == g2 && b1 == b2,
             (HomieColorValue::HSV(h1, s1, v1), HomieColorValue::HSV(h2, s2, v2)) => h1 == h2 && s1 == s2 && v1 == v2,
-            (HomieColorValue::XYZ(x1, y1, z1), HomieColorValue::XYZ(x2, y2, z2)) => {
-                (x1 - x2).abs() < EPSILON && (y1 - y2).abs() < EPSILON && (z1 - z2).abs() < EPSILON
+            // z is derived from x and y and therefore not compared, so that values constructed
+            // with an inconsistent z still equal their parsed equivalent
+            (HomieColorValue::XYZ(x1, y1, _), HomieColorValue::XYZ(x2, y2, _)) => {
+                (x1 - x2).abs() < EPSILON && (y1 - y2).abs() < EPSILON
             }
             _ => false,
         }
@@ -164,9 +182,154 @@ impl PartialOrd<HomieColorValue> for HomieColorValue {
 }
 
 impl HomieColorValue {
+    /// Creates a XYZ color value, calculating Z as `1 - X - Y`.
     pub fn new_xyz(x: f64, y: f64) -> Self {
         HomieColorValue::XYZ(x, y, 1.0 - x - y)
     }
+
+    /// Converts the color to sRGB (red, green, blue).
+    ///
+    /// XYZ values only describe the chromaticity of a color, they are converted with full
+    /// brightness (the brightest channel is 255). Z is always recalculated as `1 - X - Y`.
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            HomieColorValue::RGB(r, g, b) => (clamp_channel(r), clamp_channel(g), clamp_channel(b)),
+            HomieColorValue::HSV(h, s, v) => hsv_to_rgb(h as f64, s as f64, v as f64),
+            HomieColorValue::XYZ(x, y, _) => xyz_to_rgb(x, y),
+        }
+    }
+
+    /// Converts the color to HSV (hue 0-360, saturation 0-100, value 0-100).
+    ///
+    /// The values are not rounded, so that converting them back to RGB is lossless.
+    pub fn to_hsv(&self) -> (f64, f64, f64) {
+        match *self {
+            HomieColorValue::HSV(h, s, v) => (h as f64, s as f64, v as f64),
+            _ => rgb_to_hsv(self.to_rgb()),
+        }
+    }
+
+    /// Converts the color to the XYZ chromaticity coordinates (x, y, z) of the sRGB (D65) color
+    /// space, with `z = 1 - x - y`.
+    ///
+    /// Black has no chromaticity and is converted to the D65 white point.
+    pub fn to_xyz(&self) -> (f64, f64, f64) {
+        match *self {
+            HomieColorValue::XYZ(x, y, _) => (x, y, 1.0 - x - y),
+            _ => rgb_to_xyz(self.to_rgb()),
+        }
+    }
+
+    /// Converts the color to normalized sRGB channels in the range `0.0..=1.0` (see
+    /// [`Self::to_rgb`]).
+    pub fn to_normalized_rgb(&self) -> [f64; 3] {
+        let (r, g, b) = self.to_rgb();
+        [r, g, b].map(|c| c as f64 / 255.0)
+    }
+
+    /// Converts the color to normalized sRGB channels with the given `alpha` channel (see
+    /// [`Self::to_normalized_rgb`]). `alpha` is clamped to `0.0..=1.0`.
+    pub fn to_normalized_rgba(&self, alpha: f64) -> [f64; 4] {
+        let [r, g, b] = self.to_normalized_rgb();
+        [r, g, b, alpha.clamp(0.0, 1.0)]
+    }
+}
+
+/// D65 white point chromaticity (x, y)
+const D65_WHITE: (f64, f64) = (0.3127, 0.329);
+
+fn clamp_channel(value: i64) -> u8 {
+    value.clamp(0, 255) as u8
+}
+
+fn to_channel(value: f64) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn rgb_to_hsv((r, g, b): (u8, u8, u8)) -> (f64, f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    (hue, saturation * 100.0, max * 100.0)
+}
+
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let (s, v) = ((s / 100.0).clamp(0.0, 1.0), (v / 100.0).clamp(0.0, 1.0));
+    let chroma = v * s;
+    let sextant = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sextant.rem_euclid(2.0) - 1.0).abs());
+
+    let (r, g, b) = match sextant as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = v - chroma;
+    (to_channel(r + m), to_channel(g + m), to_channel(b + m))
+}
+
+fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn rgb_to_xyz((r, g, b): (u8, u8, u8)) -> (f64, f64, f64) {
+    let [r, g, b] = [r, g, b].map(|c| srgb_to_linear(c as f64 / 255.0));
+    let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+
+    let sum = x + y + z;
+    let (x, y) = if sum == 0.0 { D65_WHITE } else { (x / sum, y / sum) };
+    (x, y, 1.0 - x - y)
+}
+
+fn xyz_to_rgb(x: f64, y: f64) -> (u8, u8, u8) {
+    if y <= 0.0 {
+        return (0, 0, 0);
+    }
+    // scale the chromaticity to a luminance of 1, z is reconstructed from x and y
+    let z = 1.0 - x - y;
+    let (big_x, big_y, big_z) = (x / y, 1.0, z / y);
+
+    let r = 3.2404542 * big_x - 1.5371385 * big_y - 0.4985314 * big_z;
+    let g = -0.9692660 * big_x + 1.8760108 * big_y + 0.0415560 * big_z;
+    let b = 0.0556434 * big_x - 0.2040259 * big_y + 1.0572252 * big_z;
+
+    // colors outside of the sRGB gamut are clipped, the brightest channel is scaled to full
+    let [r, g, b] = [r, g, b].map(|c| c.max(0.0));
+    let max = r.max(g).max(b);
+    if max == 0.0 {
+        return (0, 0, 0);
+    }
+    let [r, g, b] = [r, g, b].map(|c| to_channel(linear_to_srgb(c / max)));
+    (r, g, b)
 }
 
 impl From<HomieColorValue> for String {
@@ -196,7 +359,9 @@ impl FromStr for HomieColorValue {
                     tokens.next().map(|g| g.parse::<i64>()),
                     tokens.next().map(|b| b.parse::<i64>()),
                 ) {
-                    return Ok(Self::RGB(r, g, b));
+                    if [r, g, b].iter().all(|c| (0..=255).contains(c)) {
+                        return Ok(Self::RGB(r, g, b));
+                    }
                 }
             }
             Some("hsv") => {
@@ -205,7 +370,9 @@ impl FromStr for HomieColorValue {
                     tokens.next().map(|s| s.parse::<i64>()),
                     tokens.next().map(|v| v.parse::<i64>()),
                 ) {
-                    return Ok(Self::HSV(h, s, v));
+                    if (0..=360).contains(&h) && (0..=100).contains(&s) && (0..=100).contains(&v) {
+                        return Ok(Self::HSV(h, s, v));
+                    }
                 }
             }
             Some("xyz") => {
@@ -213,7 +380,9 @@ impl FromStr for HomieColorValue {
                     tokens.next().map(|x| x.parse::<f64>()),
                     tokens.next().map(|y| y.parse::<f64>()),
                 ) {
-                    return Ok(Self::XYZ(x, y, 1.0 - x - y));
+                    if (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) {
+                        return Ok(Self::XYZ(x, y, 1.0 - x - y));
+                    }
                 }
             }
             _ => {}
@@ -307,6 +476,18 @@ pub enum HomieValue {
     JSON(serde_json::Value),
 }
 
+/// How a value was adjusted to fit the property's format, as reported by
+/// [`HomieValue::parse_reporting`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Adjustment {
+    /// The value was accepted as is
+    Exact,
+    /// The value was rounded to the closest step within the bounds of the range
+    Rounded { from: HomieValue },
+    /// The value was outside the bounds of the range and was snapped onto a bound
+    Clamped { from: HomieValue },
+}
+
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<chrono::Duration, D::Error>
 where
     D: Deserializer<'de>,
@@ -386,6 +567,32 @@ impl From<serde_json::Value> for HomieValue {
     }
 }
 
+// Note: there is no `TryFrom<HomieValue> for String` as `From<HomieValue> for String` already
+// converts any value to its payload representation.
+macro_rules! impl_try_from_homie_value {
+    ($target:ty, $variant:ident, $datatype:expr) => {
+        impl TryFrom<HomieValue> for $target {
+            type Error = Homie5ValueConversionError;
+
+            fn try_from(value: HomieValue) -> Result<Self, Self::Error> {
+                match value {
+                    HomieValue::$variant(inner) => Ok(inner),
+                    other => Err(Homie5ValueConversionError::DatatypeMismatch(
+                        $datatype,
+                        other.datatype(),
+                    )),
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_homie_value!(i64, Integer, HomieDataType::Integer);
+impl_try_from_homie_value!(f64, Float, HomieDataType::Float);
+impl_try_from_homie_value!(bool, Bool, HomieDataType::Boolean);
+impl_try_from_homie_value!(HomieColorValue, Color, HomieDataType::Color);
+impl_try_from_homie_value!(serde_json::Value, JSON, HomieDataType::JSON);
+
 impl From<HomieValue> for String {
     fn from(value: HomieValue) -> Self {
         value.to_string()
@@ -397,12 +604,18 @@ impl From<&HomieValue> for String {
     }
 }
 
+/// Converts the value to its mqtt payload without a property description.
+///
+/// Prefer [`HomieValue::to_payload_bytes`] when the property description is available.
 impl From<HomieValue> for Vec<u8> {
     fn from(value: HomieValue) -> Self {
         homie_str_to_vecu8(value.to_string())
     }
 }
 
+/// Converts the value to its mqtt payload without a property description.
+///
+/// Prefer [`HomieValue::to_payload_bytes`] when the property description is available.
 impl From<&HomieValue> for Vec<u8> {
     fn from(value: &HomieValue) -> Self {
         homie_str_to_vecu8(value.to_string())
@@ -420,6 +633,55 @@ pub fn homie_str_to_vecu8(value: impl Into<String>) -> Vec<u8> {
     }
 }
 
+/// Deserializes a [`HomieValue`] of a datatype only known at runtime, e.g. from a config file
+/// declaring the datatype of a property alongside its value.
+///
+/// The value can either be given in its payload form as a string (e.g. `"rgb,255,0,0"` or
+/// `"42"`) or, for numbers and booleans, natively (e.g. `42`). Values of the `json` datatype are
+/// taken as they are. The value is parsed like [`HomieValue::parse`] does for a property without
+/// a format.
+///
+/// # Example
+/// ```rust
+/// use serde::de::DeserializeSeed;
+/// use homie5::{HomieDataType, HomieValue, HomieValueSeed};
+///
+/// let mut deserializer = serde_json::Deserializer::from_str("42");
+/// let value = HomieValueSeed(&HomieDataType::Integer).deserialize(&mut deserializer).unwrap();
+/// assert_eq!(value, HomieValue::Integer(42));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HomieValueSeed<'a>(pub &'a HomieDataType);
+
+impl<'de> de::DeserializeSeed<'de> for HomieValueSeed<'_> {
+    type Value = HomieValue;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        if *self.0 == HomieDataType::JSON {
+            return Ok(HomieValue::JSON(value));
+        }
+        let raw = match value {
+            serde_json::Value::String(raw) => raw,
+            serde_json::Value::Number(number) => number.to_string(),
+            serde_json::Value::Bool(b) => b.to_string(),
+            other => return Err(de::Error::custom(format!("expected a {} value, got {}", self.0, other))),
+        };
+        let desc = HomiePropertyDescription {
+            name: None,
+            datatype: *self.0,
+            format: HomiePropertyFormat::Empty,
+            settable: false,
+            retained: true,
+            unit: None,
+        };
+        HomieValue::parse(&raw, &desc).map_err(de::Error::custom)
+    }
+}
+
 impl PartialOrd<HomieValue> for HomieValue {
     fn partial_cmp(&self, other: &HomieValue) -> Option<Ordering> {
         match (self, other) {
@@ -451,6 +713,177 @@ impl PartialOrd<HomieValue> for HomieValue {
 }
 
 impl HomieValue {
+    /// Returns the homie datatype of the value.
+    ///
+    /// `HomieValue::Empty` has no datatype and returns `None`.
+    pub fn datatype(&self) -> Option<HomieDataType> {
+        match self {
+            HomieValue::Empty => None,
+            HomieValue::String(_) => Some(HomieDataType::String),
+            HomieValue::Integer(_) => Some(HomieDataType::Integer),
+            HomieValue::Float(_) => Some(HomieDataType::Float),
+            HomieValue::Bool(_) => Some(HomieDataType::Boolean),
+            HomieValue::Enum(_) => Some(HomieDataType::Enum),
+            HomieValue::Color(_) => Some(HomieDataType::Color),
+            HomieValue::DateTime(_) => Some(HomieDataType::Datetime),
+            HomieValue::Duration(_) => Some(HomieDataType::Duration),
+            HomieValue::JSON(_) => Some(HomieDataType::JSON),
+        }
+    }
+
+    /// Checks if the datatype of the value matches the datatype of the property description.
+    ///
+    /// On a mismatch the `(expected, actual)` datatype pair is returned which can be used to
+    /// create a meaningful error message. `HomieValue::Empty` is treated as matching any datatype.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use homie5::device_description::*;
+    /// use homie5::{HomieValue, HomieDataType};
+    ///
+    /// let property_desc = PropertyDescriptionBuilder::new(HomieDataType::Integer).build();
+    ///
+    /// assert_eq!(HomieValue::Integer(5).matches_description(&property_desc), Ok(()));
+    /// assert_eq!(
+    ///     HomieValue::Float(5.0).matches_description(&property_desc),
+    ///     Err((HomieDataType::Integer, HomieDataType::Float))
+    /// );
+    /// ```
+    pub fn matches_description(
+        &self,
+        property_desc: &HomiePropertyDescription,
+    ) -> Result<(), (HomieDataType, HomieDataType)> {
+        match self.datatype() {
+            Some(actual) if actual != property_desc.datatype => Err((property_desc.datatype, actual)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Converts the value to the datatype of the target property, e.g. when migrating values
+    /// between properties.
+    ///
+    /// Only conversions which cannot lose information are allowed:
+    /// - values of the target datatype are kept
+    /// - integers are converted to floats
+    /// - all values are converted to strings using their payload representation, booleans become
+    ///   `true`/`false` regardless of a boolean format
+    /// - empty values stay empty
+    ///
+    /// All other conversions, including float to integer, return
+    /// `Homie5ValueConversionError::DatatypeMismatch`. The converted value is checked against the
+    /// format of the target property: numbers are rounded to the closest step and must be within
+    /// the range, enum values must be listed and colors must be in a supported format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use homie5::device_description::*;
+    /// use homie5::{HomieValue, HomieDataType};
+    ///
+    /// let target = PropertyDescriptionBuilder::new(HomieDataType::Float).build();
+    /// assert_eq!(HomieValue::Integer(5).coerce(&target), Ok(HomieValue::Float(5.0)));
+    /// ```
+    pub fn coerce(&self, target: &HomiePropertyDescription) -> Result<HomieValue, Homie5ValueConversionError> {
+        let value = match (self, &target.datatype) {
+            (HomieValue::Empty, _) => return Ok(HomieValue::Empty),
+            (value, datatype) if value.datatype().as_ref() == Some(datatype) => value.clone(),
+            (HomieValue::Integer(value), HomieDataType::Float) => HomieValue::Float(*value as f64),
+            (value, HomieDataType::String) => HomieValue::String(value.to_string()),
+            (value, datatype) => {
+                return Err(Homie5ValueConversionError::DatatypeMismatch(
+                    *datatype,
+                    value.datatype(),
+                ))
+            }
+        };
+
+        match value {
+            HomieValue::Integer(value) => Self::validate_int(value, target).map(HomieValue::Integer),
+            HomieValue::Float(value) => Self::validate_float(value, target).map(HomieValue::Float),
+            HomieValue::Enum(value) => match &target.format {
+                HomiePropertyFormat::Enum(values) if !values.contains(&value) => {
+                    Err(Homie5ValueConversionError::InvalidEnumFormat(value, values.clone()))
+                }
+                _ => Ok(HomieValue::Enum(value)),
+            },
+            HomieValue::Color(color) => match &target.format {
+                HomiePropertyFormat::Color(formats) if !formats.contains(&color.color_format()) => Err(
+                    Homie5ValueConversionError::UnsupportedColorFormat(color.color_format(), formats.clone()),
+                ),
+                _ => Ok(HomieValue::Color(color)),
+            },
+            value => Ok(value),
+        }
+    }
+
+    /// Converts the value to the mqtt payload to publish for the given property.
+    ///
+    /// Empty values and empty strings are published as a single 0 byte according to the homie
+    /// convention. Booleans are always published as `true`/`false`: the labels of a boolean
+    /// format are for display purposes only (see [`HomieValue::to_display_string`]) and are not
+    /// accepted by [`HomieValue::parse`].
+    pub fn to_payload_bytes(&self, property_desc: &HomiePropertyDescription) -> Vec<u8> {
+        match (self, &property_desc.datatype) {
+            // a value without datatype for a string property is an empty string
+            (HomieValue::Empty, HomieDataType::String) => homie_str_to_vecu8(""),
+            _ => homie_str_to_vecu8(self.to_string()),
+        }
+    }
+
+    /// Converts the value to the mqtt payload to publish without a property description.
+    ///
+    /// `HomieValue::Empty` and empty strings are published as a single 0 byte according to the
+    /// homie convention, all other values are published as the UTF-8 bytes of their string
+    /// representation. Use [`HomieValue::to_payload_bytes`] when the property description is
+    /// available.
+    pub fn to_mqtt_payload(&self) -> Vec<u8> {
+        match self {
+            HomieValue::Empty => vec![0_u8],
+            HomieValue::String(value) if value.is_empty() => vec![0_u8],
+            _ => self.to_string().into_bytes(),
+        }
+    }
+
+    /// Returns a human readable representation of the value for the given property.
+    ///
+    /// Unlike `Display` this uses the labels of a boolean format (e.g. `off`/`on` for the
+    /// format `off,on`). All other values are formatted as their payload.
+    pub fn to_display_string(&self, property_desc: &HomiePropertyDescription) -> String {
+        match (self, &property_desc.format) {
+            (HomieValue::Bool(value), HomiePropertyFormat::Boolean { false_val, true_val }) => {
+                if *value { true_val } else { false_val }.clone()
+            }
+            _ => self.to_string(),
+        }
+    }
+
+    /// Creates a `HomieValue::Duration` from a number of seconds.
+    ///
+    /// Values exceeding the supported duration range are clamped to the minimum/maximum duration.
+    pub fn duration_from_secs(secs: i64) -> HomieValue {
+        HomieValue::Duration(chrono::Duration::try_seconds(secs).unwrap_or(if secs < 0 {
+            chrono::Duration::min_value()
+        } else {
+            chrono::Duration::max_value()
+        }))
+    }
+
+    /// Creates a `HomieValue::Duration` from a `std::time::Duration`.
+    ///
+    /// Values exceeding the supported duration range are clamped to the maximum duration.
+    pub fn duration_from_std(duration: std::time::Duration) -> HomieValue {
+        HomieValue::Duration(chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::max_value()))
+    }
+
+    /// Returns the number of whole seconds if the value is a `HomieValue::Duration`.
+    pub fn duration_as_secs(&self) -> Option<i64> {
+        match self {
+            HomieValue::Duration(duration) => Some(duration.num_seconds()),
+            _ => None,
+        }
+    }
+
     /// Parses a raw string value into a `HomieValue` based on the provided property description.
     ///
     /// This function attempts to convert a string representation of a property value into
@@ -462,7 +895,7 @@ impl HomieValue {
     ///
     /// - `raw`: The raw string value to be parsed.
     /// - `property_desc`: A reference to the property description that defines the expected data type
-    ///    and format of the property.
+    ///   and format of the property.
     ///
     /// # Returns
     ///
@@ -518,55 +951,184 @@ impl HomieValue {
             HomieDataType::String => Ok(HomieValue::String(raw.to_owned())),
             HomieDataType::Enum => {
                 if let HomiePropertyFormat::Enum(values) = &property_desc.format {
-                    let string_val = raw.to_owned();
-                    values
-                        .contains(&string_val)
-                        .then_some(HomieValue::Enum(string_val))
-                        .ok_or(Homie5ValueConversionError::InvalidEnumFormat(
-                            raw.to_string(),
-                            values.clone(),
-                        ))
+                    Self::new_enum(raw, values)
                 } else {
                     // not sure if this can happen per spec
                     Ok(HomieValue::Enum(raw.to_string()))
                 }
             }
-            HomieDataType::Color => raw
-                .parse::<HomieColorValue>()
-                .and_then(|color_value| {
-                    if !property_desc.format.is_empty() {
-                        // if supported formats are specified, check if the provided value is
-                        // compatible
-                        if let HomiePropertyFormat::Color(formats) = &property_desc.format {
-                            match color_value {
-                                HomieColorValue::RGB(_, _, _) if formats.contains(&ColorFormat::Rgb) => Ok(color_value),
-                                HomieColorValue::HSV(_, _, _) if formats.contains(&ColorFormat::Hsv) => Ok(color_value),
-                                HomieColorValue::XYZ(_, _, _) if formats.contains(&ColorFormat::Xyz) => Ok(color_value),
-                                color => Err(Homie5ValueConversionError::UnsupportedColorFormat(
-                                    color.color_format(),
-                                    formats.clone(),
-                                )),
-                            }
-                        } else {
-                            // if no color format is supplied no check is needed (this should
-                            // never happen actually)
-                            Ok(color_value)
-                        }
-                    } else {
-                        // if no format at all is provided, no further checks are needed
-                        Ok(color_value)
-                    }
-                })
-                .map(HomieValue::Color),
+            HomieDataType::Color => raw.parse::<HomieColorValue>().and_then(|color_value| {
+                // if supported formats are specified, check if the provided value is
+                // compatible
+                if let HomiePropertyFormat::Color(formats) = &property_desc.format {
+                    Self::new_color(color_value, formats)
+                } else {
+                    // if no format at all is provided, no further checks are needed
+                    Ok(HomieValue::Color(color_value))
+                }
+            }),
             HomieDataType::Datetime => Self::flexible_datetime_parser(raw).map(HomieValue::DateTime),
             HomieDataType::Duration => Self::parse_duration(raw).map(HomieValue::Duration),
+            HomieDataType::JSON => serde_json::from_str::<serde_json::Value>(raw)
+                .map_err(|e| Homie5ValueConversionError::JsonParseError(e.to_string()))
+                .and_then(|value| Self::validate_json(value, property_desc))
+                .map(HomieValue::JSON),
+        }
+        .map_err(Homie5ProtocolError::InvalidHomieValue)
+    }
+
+    /// Creates an enum value, checking that it is one of the given `variants`.
+    ///
+    /// This is the validated alternative to constructing `HomieValue::Enum` directly and checks
+    /// the same as [`HomieValue::parse`] does for a property with an enum format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use homie5::HomieValue;
+    ///
+    /// let variants = vec!["on".to_owned(), "off".to_owned()];
+    /// assert_eq!(HomieValue::new_enum("on", &variants), Ok(HomieValue::Enum("on".to_owned())));
+    /// assert!(HomieValue::new_enum("dim", &variants).is_err());
+    /// ```
+    pub fn new_enum(value: impl Into<String>, variants: &[String]) -> Result<HomieValue, Homie5ValueConversionError> {
+        let value = value.into();
+        if variants.contains(&value) {
+            Ok(HomieValue::Enum(value))
+        } else {
+            Err(Homie5ValueConversionError::InvalidEnumFormat(value, variants.to_vec()))
+        }
+    }
+
+    /// Creates a color value, checking that its color format is one of the `allowed_formats`.
+    ///
+    /// An empty list of allowed formats accepts all color formats. This is the validated
+    /// alternative to constructing `HomieValue::Color` directly and checks the same as
+    /// [`HomieValue::parse`] does for a property with a color format.
+    pub fn new_color(
+        value: HomieColorValue,
+        allowed_formats: &[ColorFormat],
+    ) -> Result<HomieValue, Homie5ValueConversionError> {
+        if allowed_formats.is_empty() || allowed_formats.contains(&value.color_format()) {
+            Ok(HomieValue::Color(value))
+        } else {
+            Err(Homie5ValueConversionError::UnsupportedColorFormat(
+                value.color_format(),
+                allowed_formats.to_vec(),
+            ))
+        }
+    }
+
+    /// Deserializes a value of the given `datatype`, see [`HomieValueSeed`].
+    ///
+    /// # Example
+    /// ```rust
+    /// use homie5::{HomieColorValue, HomieDataType, HomieValue};
+    ///
+    /// let mut deserializer = serde_json::Deserializer::from_str(r#""rgb,255,0,0""#);
+    /// let value = HomieValue::deserialize_as(&HomieDataType::Color, &mut deserializer).unwrap();
+    /// assert_eq!(value, HomieValue::Color(HomieColorValue::RGB(255, 0, 0)));
+    /// ```
+    pub fn deserialize_as<'de, D>(datatype: &HomieDataType, deserializer: D) -> Result<HomieValue, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        de::DeserializeSeed::deserialize(HomieValueSeed(datatype), deserializer)
+    }
+
+    /// Parses a raw string value into the datatype of the property without validating it against
+    /// the property format.
+    ///
+    /// In contrast to the strict [`HomieValue::parse`], integer and float values are neither
+    /// checked against the bounds of their range nor rounded to its step, colors are accepted
+    /// in any color format and json values are not checked against a schema. This is intended for passive monitoring, where the value a device
+    /// actually published is of interest even if it violates its own description. All other
+    /// datatypes are parsed exactly like [`HomieValue::parse`] does.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use homie5::device_description::*;
+    /// use homie5::{HomieValue, HomieDataType};
+    ///
+    /// let property_desc = PropertyDescriptionBuilder::new(HomieDataType::Integer)
+    ///     .format(HomiePropertyFormat::IntegerRange(IntegerRange { min: Some(0), max: Some(100), step: None }))
+    ///     .build();
+    ///
+    /// assert!(HomieValue::parse("150", &property_desc).is_err());
+    /// assert_eq!(HomieValue::parse_unvalidated("150", &property_desc).ok(), Some(HomieValue::Integer(150)));
+    /// ```
+    pub fn parse_unvalidated(
+        raw: &str,
+        property_desc: &HomiePropertyDescription,
+    ) -> Result<HomieValue, Homie5ProtocolError> {
+        match &property_desc.datatype {
+            HomieDataType::Integer => raw
+                .parse::<i64>()
+                .map(HomieValue::Integer)
+                .map_err(|_| Homie5ValueConversionError::InvalidIntegerFormat(raw.to_string())),
+            HomieDataType::Float => raw
+                .parse::<f64>()
+                .map(HomieValue::Float)
+                .map_err(|_| Homie5ValueConversionError::InvalidFloatFormat(raw.to_string())),
+            HomieDataType::Color => raw.parse::<HomieColorValue>().map(HomieValue::Color),
             HomieDataType::JSON => serde_json::from_str::<serde_json::Value>(raw)
                 .map(HomieValue::JSON)
                 .map_err(|e| Homie5ValueConversionError::JsonParseError(e.to_string())),
+            _ => return Self::parse(raw, property_desc),
         }
         .map_err(Homie5ProtocolError::InvalidHomieValue)
     }
 
+    /// Parses a raw string value like [`HomieValue::parse`] and additionally reports if the value
+    /// was adjusted to fit the format of the property.
+    ///
+    /// Integer and float values are rounded to the closest step of their range, which may also
+    /// move a value slightly outside of the bounds onto a bound. All other values are never
+    /// adjusted and are reported as `Adjustment::Exact`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use homie5::device_description::*;
+    /// use homie5::{Adjustment, HomieValue, HomieDataType};
+    ///
+    /// let property_desc = PropertyDescriptionBuilder::new(HomieDataType::Float)
+    ///     .format(HomiePropertyFormat::FloatRange(FloatRange { min: Some(2.0), max: Some(6.0), step: Some(2.0) }))
+    ///     .build();
+    ///
+    /// let (value, adjustment) = HomieValue::parse_reporting("3.5", &property_desc).unwrap();
+    /// assert_eq!(value, HomieValue::Float(4.0));
+    /// assert_eq!(adjustment, Adjustment::Rounded { from: HomieValue::Float(3.5) });
+    /// ```
+    pub fn parse_reporting(
+        raw: &str,
+        property_desc: &HomiePropertyDescription,
+    ) -> Result<(HomieValue, Adjustment), Homie5ProtocolError> {
+        let value = Self::parse(raw, property_desc)?;
+        // parse succeeded, so the raw value is known to be a valid number for these types
+        let (from, in_bounds) = match (&value, &property_desc.format) {
+            (HomieValue::Integer(_), HomiePropertyFormat::IntegerRange(range)) => {
+                let from = raw.parse::<i64>().unwrap_or_default();
+                (HomieValue::Integer(from), range.contains(from))
+            }
+            (HomieValue::Float(_), HomiePropertyFormat::FloatRange(range)) => {
+                let from = raw.parse::<f64>().unwrap_or_default();
+                (HomieValue::Float(from), range.contains(from))
+            }
+            _ => return Ok((value, Adjustment::Exact)),
+        };
+
+        let adjustment = if from == value {
+            Adjustment::Exact
+        } else if in_bounds {
+            Adjustment::Rounded { from }
+        } else {
+            Adjustment::Clamped { from }
+        };
+        Ok((value, adjustment))
+    }
+
     fn parse_duration(s: &str) -> Result<chrono::Duration, Homie5ValueConversionError> {
         let re = regex::Regex::new(r"^PT(?:(\d+)H)?(?:(\d+)M)?(?:(\d+)S)?$").unwrap();
         if let Some(captures) = re.captures(s) {
@@ -616,21 +1178,66 @@ impl HomieValue {
         )
     }
 
-    fn validate_float(value: f64, property_desc: &HomiePropertyDescription) -> Result<f64, Homie5ValueConversionError> {
-        let HomiePropertyFormat::FloatRange(range) = &property_desc.format else {
+    /// Checks a json value against the JSON schema in the format of the property.
+    ///
+    /// The schema is compiled for every value, as descriptions only hold the raw schema string.
+    #[cfg(feature = "jsonschema")]
+    fn validate_json(
+        value: serde_json::Value,
+        property_desc: &HomiePropertyDescription,
+    ) -> Result<serde_json::Value, Homie5ValueConversionError> {
+        let HomiePropertyFormat::Json(schema) = &property_desc.format else {
             return Ok(value);
         };
-        // Use the minimum, max, or current value as base (in that priority order)
-        let base = range.min.or(range.max).unwrap_or(value);
+        if schema.is_empty() {
+            return Ok(value);
+        }
+        let schema = serde_json::from_str::<serde_json::Value>(schema)
+            .map_err(|e| Homie5ValueConversionError::InvalidJsonSchema(e.to_string()))?;
+        let schema = jsonschema::JSONSchema::compile(&schema)
+            .map_err(|e| Homie5ValueConversionError::InvalidJsonSchema(e.to_string()))?;
+        let result = schema.validate(&value).map_err(|errors| {
+            Homie5ValueConversionError::JsonParseError(errors.map(|e| e.to_string()).collect::<Vec<_>>().join(", "))
+        });
+        result.map(|_| value)
+    }
 
-        // Calculate the rounded value based on the step
-        let rounded = match range.step {
-            Some(s) if s > 0.0 => ((value - base) / s).round() * s + base,
-            _ => value,
-        };
+    /// Without the `jsonschema` feature the schema of json properties is not enforced.
+    #[cfg(not(feature = "jsonschema"))]
+    fn validate_json(
+        value: serde_json::Value,
+        _property_desc: &HomiePropertyDescription,
+    ) -> Result<serde_json::Value, Homie5ValueConversionError> {
+        Ok(value)
+    }
+
+    /// Checks the value against the format of the property the same way [`HomieValue::parse`]
+    /// does, values of another datatype than the format are not checked.
+    pub(crate) fn validate_format(
+        &self,
+        property_desc: &HomiePropertyDescription,
+    ) -> Result<(), Homie5ValueConversionError> {
+        match (self, &property_desc.format) {
+            (HomieValue::Integer(value), _) => Self::validate_int(*value, property_desc).map(drop),
+            (HomieValue::Float(value), _) => Self::validate_float(*value, property_desc).map(drop),
+            (HomieValue::Enum(value), HomiePropertyFormat::Enum(values)) => {
+                Self::new_enum(value.as_str(), values).map(drop)
+            }
+            (HomieValue::Color(value), HomiePropertyFormat::Color(formats)) => {
+                Self::new_color(*value, formats).map(drop)
+            }
+            (HomieValue::JSON(value), _) => Self::validate_json(value.clone(), property_desc).map(drop),
+            _ => Ok(()),
+        }
+    }
 
-        // Check if the rounded value is within the min/max bounds
-        if range.min.map_or(true, |m| rounded >= m) && range.max.map_or(true, |m| rounded <= m) {
+    fn validate_float(value: f64, property_desc: &HomiePropertyDescription) -> Result<f64, Homie5ValueConversionError> {
+        let HomiePropertyFormat::FloatRange(range) = &property_desc.format else {
+            return Ok(value);
+        };
+        // Round the value to the closest step and check if it is within the min/max bounds
+        let rounded = range.snap(value);
+        if range.contains(rounded) {
             Ok(rounded)
         } else {
             Err(Homie5ValueConversionError::FloatOutOfRange(value, range.clone()))
@@ -642,20 +1249,33 @@ impl HomieValue {
             return Ok(value);
         };
 
-        // Use the minimum or maximum as the base, or use the current value
-        let base = range.min.or(range.max).unwrap_or(value);
-
-        // Calculate the rounded value based on the step
-        let rounded = match range.step {
-            Some(s) if s > 0 => ((value - base) as f64 / s as f64).round() as i64 * s + base,
-            _ => value,
-        };
-
-        // Check if the rounded value is within the min/max bounds
-        if range.min.map_or(true, |m| rounded >= m) && range.max.map_or(true, |m| rounded <= m) {
+        // Round the value to the closest step and check if it is within the min/max bounds
+        let rounded = range.snap(value);
+        if range.contains(rounded) {
             Ok(rounded)
         } else {
             Err(Homie5ValueConversionError::IntegerOutOfRange(value, range.clone()))
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rgb_hsv_round_trip() {
+        for r in (0..=255).step_by(5) {
+            for g in (0..=255).step_by(3) {
+                for b in (0..=255).step_by(7) {
+                    let (h, s, v) = rgb_to_hsv((r, g, b));
+                    let (r2, g2, b2) = hsv_to_rgb(h, s, v);
+                    assert!(
+                        r.abs_diff(r2) <= 1 && g.abs_diff(g2) <= 1 && b.abs_diff(b2) <= 1,
+                        "({r},{g},{b}) -> ({h},{s},{v}) -> ({r2},{g2},{b2})"
+                    );
+                }
+            }
+        }
+    }
+}
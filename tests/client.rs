@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use homie5::client::*;
+
+#[test]
+fn test_client_types_display() {
+    let sub = Subscription {
+        topic: "homie/5/+/$state".to_owned(),
+        qos: QoS::ExactlyOnce,
+    };
+    assert_eq!(sub.to_string(), "SUB homie/5/+/$state (ExactlyOnce)");
+    assert!(format!("{:?}", sub).contains("homie/5/+/$state"));
+
+    let unsub = Unsubscribe {
+        topic: "homie/5/+/$state".to_owned(),
+    };
+    assert_eq!(unsub.to_string(), "UNSUB homie/5/+/$state");
+    assert!(format!("{:?}", unsub).contains("homie/5/+/$state"));
+
+    let publish = Publish {
+        topic: "homie/5/device/$state".to_owned(),
+        retain: true,
+        payload: b"ready".to_vec(),
+        qos: QoS::AtLeastOnce,
+    };
+    assert_eq!(
+        publish.to_string(),
+        "PUB homie/5/device/$state (AtLeastOnce, retain: true, 5 bytes)"
+    );
+    assert!(format!("{:?}", publish).contains("homie/5/device/$state"));
+}
+
+fn topics(topics: &[&str]) -> HashSet<String> {
+    topics.iter().map(|topic| topic.to_string()).collect()
+}
+
+#[test]
+fn test_subscription_delta() {
+    // adds only
+    let (subs, unsubs) = subscription_delta(
+        &topics(&["homie/5/a/$state"]),
+        &topics(&["homie/5/a/$state", "homie/5/c/$state", "homie/5/b/$state"]),
+    );
+    let sub_topics: Vec<_> = subs.iter().map(|sub| sub.topic.as_str()).collect();
+    assert_eq!(sub_topics, vec!["homie/5/b/$state", "homie/5/c/$state"]);
+    assert!(subs.iter().all(|sub| sub.qos == QoS::ExactlyOnce));
+    assert!(unsubs.is_empty());
+
+    // removes only
+    let (subs, unsubs) = subscription_delta(&topics(&["homie/5/a/$state", "homie/5/b/$state"]), &HashSet::new());
+    assert!(subs.is_empty());
+    let unsub_topics: Vec<_> = unsubs.iter().map(|unsub| unsub.topic.as_str()).collect();
+    assert_eq!(unsub_topics, vec!["homie/5/a/$state", "homie/5/b/$state"]);
+
+    // mixed
+    let (subs, unsubs) = subscription_delta(
+        &topics(&["homie/5/a/$state", "homie/5/b/$state"]),
+        &topics(&["homie/5/b/$state", "homie/5/c/$state"]),
+    );
+    assert_eq!(
+        subs,
+        vec![Subscription {
+            topic: "homie/5/c/$state".to_owned(),
+            qos: QoS::ExactlyOnce
+        }]
+    );
+    assert_eq!(
+        unsubs,
+        vec![Unsubscribe {
+            topic: "homie/5/a/$state".to_owned()
+        }]
+    );
+
+    // no changes
+    let (subs, unsubs) = subscription_delta(&topics(&["homie/5/a/$state"]), &topics(&["homie/5/a/$state"]));
+    assert!(subs.is_empty() && unsubs.is_empty());
+}
+
+#[test]
+fn test_clear_topics() {
+    let topics = vec![
+        "homie/5/device-1/$state".to_owned(),
+        "homie/5/device-1/node/prop".to_owned(),
+    ];
+    let publishes: Vec<_> = clear_topics(topics.clone()).collect();
+
+    assert_eq!(publishes.len(), 2);
+    for (publish, topic) in publishes.iter().zip(topics) {
+        assert_eq!(publish.topic, topic);
+        assert!(publish.retain);
+        assert!(publish.payload.is_empty());
+        assert_eq!(publish.qos, QoS::ExactlyOnce);
+    }
+    assert_eq!(clear_topics(Vec::new()).count(), 0);
+}
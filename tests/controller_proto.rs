@@ -0,0 +1,380 @@
+use homie5::client::{QoS, Subscription};
+use homie5::device_description::*;
+use homie5::*;
+
+#[test]
+fn test_subscribe_device_attrs_description_only() {
+    let protocol = Homie5ControllerProtocol::new();
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+
+    let topics: Vec<_> = protocol
+        .subscribe_device_attrs(&device, &[DeviceAttribute::Description])
+        .map(|s| s.topic)
+        .collect();
+
+    assert_eq!(topics, vec!["homie/5/test-device-1/$description"]);
+}
+
+#[test]
+fn test_subscribe_device_attrs_alert_wildcard() {
+    let protocol = Homie5ControllerProtocol::new();
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+
+    let topics: Vec<_> = protocol
+        .subscribe_device_attrs(&device, &[DeviceAttribute::Alert, DeviceAttribute::Description])
+        .map(|s| s.topic)
+        .collect();
+
+    assert_eq!(
+        topics,
+        vec!["homie/5/test-device-1/$alert/+", "homie/5/test-device-1/$description"]
+    );
+}
+
+#[test]
+fn test_all_subscription_topics() {
+    let protocol = Homie5ControllerProtocol::new();
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("on"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Boolean).build(),
+                )
+                .build(),
+        )
+        .build();
+
+    let topics = protocol.all_subscription_topics(&device, &description);
+    assert_eq!(
+        topics,
+        vec![
+            "homie/5/test-device-1/$alert/+",
+            "homie/5/test-device-1/$description",
+            "homie/5/test-device-1/$log/debug",
+            "homie/5/test-device-1/$log/error",
+            "homie/5/test-device-1/$log/fatal",
+            "homie/5/test-device-1/$log/info",
+            "homie/5/test-device-1/$log/warn",
+            "homie/5/test-device-1/light/on",
+            "homie/5/test-device-1/light/on/$target",
+        ]
+    );
+}
+
+#[test]
+fn test_subscription_qos() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("on"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Boolean).build(),
+                )
+                .build(),
+        )
+        .build();
+    let subscriptions = |protocol: &Homie5ControllerProtocol| -> Vec<Subscription> {
+        protocol
+            .subscribe_device_discovery(&HomieDomain::Default)
+            .chain(protocol.subscribe_device(&device))
+            .chain(protocol.subscribe_props(&device, &description))
+            .chain(protocol.subscribe_broadcast(&HomieDomain::Default))
+            .collect()
+    };
+
+    let protocol = Homie5ControllerProtocol::new();
+    assert_eq!(protocol.subscription_qos(), QoS::ExactlyOnce);
+    assert!(subscriptions(&protocol).iter().all(|s| s.qos == QoS::ExactlyOnce));
+
+    let protocol = Homie5ControllerProtocol::new().with_subscription_qos(QoS::AtMostOnce);
+    let subs = subscriptions(&protocol);
+    assert_eq!(subs.len(), 11);
+    assert!(subs.iter().all(|s| s.qos == QoS::AtMostOnce));
+}
+
+#[test]
+fn test_subscribe_broadcast_subtopic() {
+    let protocol = Homie5ControllerProtocol::new();
+    let topics = |subtopic: &str| -> Vec<String> {
+        protocol
+            .subscribe_broadcast_subtopic(&HomieDomain::Default, subtopic)
+            .unwrap()
+            .map(|s| s.topic)
+            .collect()
+    };
+
+    assert_eq!(topics("alarm"), vec!["homie/5/$broadcast/alarm"]);
+    assert_eq!(topics("alarm/fire"), vec!["homie/5/$broadcast/alarm/fire"]);
+    assert_eq!(topics("alarm/#"), vec!["homie/5/$broadcast/alarm/#"]);
+    assert_eq!(topics("#"), vec!["homie/5/$broadcast/#"]);
+
+    // empty levels and wildcards other than a trailing `#` are rejected
+    for subtopic in [
+        "",
+        "/alarm",
+        "alarm/",
+        "alarm//fire",
+        "alarm/+x",
+        "a/#/b",
+        "alarm/+",
+        "alarm#",
+    ] {
+        assert!(
+            matches!(
+                protocol.subscribe_broadcast_subtopic(&HomieDomain::Default, subtopic),
+                Err(Homie5ProtocolError::InvalidTopicSegment(_))
+            ),
+            "{subtopic}"
+        );
+    }
+
+    let unsubs: Vec<_> = protocol
+        .unsubscribe_broadcast_subtopic(&HomieDomain::Default, "alarm/fire")
+        .unwrap()
+        .map(|u| u.topic)
+        .collect();
+    assert_eq!(unsubs, vec!["homie/5/$broadcast/alarm/fire"]);
+    assert!(protocol
+        .unsubscribe_broadcast_subtopic(&HomieDomain::Default, "a/#/b")
+        .is_err());
+}
+
+#[test]
+fn test_set_command_validated() {
+    let protocol = Homie5ControllerProtocol::new();
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+                            min: Some(0),
+                            max: Some(100),
+                            step: None,
+                        }))
+                        .settable(true)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+    let prop = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-1"),
+        HomieID::new_const("light"),
+        HomieID::new_const("brightness"),
+    );
+
+    let publish = protocol.set_command_validated(&prop, "42", &description).unwrap();
+    assert_eq!(publish.topic, "homie/5/test-device-1/light/brightness/set");
+    assert_eq!(publish.payload, b"42".to_vec());
+    assert!(!publish.retain);
+
+    assert!(matches!(
+        protocol.set_command_validated(&prop, "142", &description),
+        Err(Homie5ProtocolError::InvalidHomieValue(
+            Homie5ValueConversionError::IntegerOutOfRange(142, _)
+        ))
+    ));
+}
+
+#[test]
+fn test_with_version() {
+    let protocol = Homie5ControllerProtocol::new().with_version("4");
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+
+    let topics: Vec<_> = protocol
+        .subscribe_device_discovery(&HomieDomain::Default)
+        .map(|s| s.topic)
+        .collect();
+    assert_eq!(topics, vec!["homie/4/+/$state"]);
+
+    let topics: Vec<_> = protocol
+        .subscribe_device_attrs(&device, &[DeviceAttribute::Alert, DeviceAttribute::Description])
+        .map(|s| s.topic)
+        .collect();
+    assert_eq!(
+        topics,
+        vec!["homie/4/test-device-1/$alert/+", "homie/4/test-device-1/$description"]
+    );
+}
+
+#[test]
+fn test_subscribe_alerts() {
+    let protocol = Homie5ControllerProtocol::new();
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+
+    let subs: Vec<_> = protocol.subscribe_alerts(&device).collect();
+    assert_eq!(subs.len(), 1);
+    assert_eq!(subs[0].topic, "homie/5/test-device-1/$alert/+");
+}
+
+#[test]
+fn test_subscribe_properties() {
+    let protocol = Homie5ControllerProtocol::new();
+    let props = [
+        PropertyRef::new(
+            HomieDomain::Default,
+            HomieID::new_const("device-1"),
+            HomieID::new_const("node-1"),
+            HomieID::new_const("prop-1"),
+        ),
+        PropertyRef::new(
+            HomieDomain::Default,
+            HomieID::new_const("device-2"),
+            HomieID::new_const("node-2"),
+            HomieID::new_const("prop-2"),
+        ),
+    ];
+
+    let topics: Vec<_> = protocol.subscribe_properties(&props).map(|sub| sub.topic).collect();
+    assert_eq!(
+        topics,
+        vec![
+            "homie/5/device-1/node-1/prop-1",
+            "homie/5/device-1/node-1/prop-1/$target",
+            "homie/5/device-2/node-2/prop-2",
+            "homie/5/device-2/node-2/prop-2/$target",
+        ]
+    );
+}
+
+#[test]
+fn test_discovery_stage() {
+    let description = DeviceDescriptionBuilder::new().build();
+
+    let mut stage = DiscoveryStage::from_progress(None, None, false);
+    assert_eq!(stage, DiscoveryStage::Undiscovered);
+
+    // properties cannot be subscribed without a description
+    assert_eq!(
+        DiscoveryStage::from_progress(Some(HomieDeviceStatus::Ready), None, true),
+        DiscoveryStage::StateKnown
+    );
+
+    for (expected, progress) in [
+        (DiscoveryStage::StateKnown, (Some(HomieDeviceStatus::Init), None, false)),
+        (
+            DiscoveryStage::DescriptionKnown,
+            (Some(HomieDeviceStatus::Init), Some(&description), false),
+        ),
+        (
+            DiscoveryStage::PropertiesSubscribed,
+            (Some(HomieDeviceStatus::Ready), Some(&description), true),
+        ),
+    ] {
+        let next = DiscoveryStage::from_progress(progress.0, progress.1, progress.2);
+        assert_eq!(next, expected);
+        assert!(next > stage);
+        assert!(!stage.is_complete());
+        stage = next;
+    }
+    assert!(stage.is_complete());
+}
+
+#[test]
+fn test_load_snapshot() {
+    let (device_protocol, _) = Homie5DeviceProtocol::new(HomieID::new_const("device-1"), HomieDomain::Default);
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer).build(),
+                )
+                .build(),
+        )
+        .build();
+    let description_publish = device_protocol.publish_description(&description).unwrap();
+
+    // values are listed before the description they are parsed with
+    let snapshot = vec![
+        ("homie/5/device-1/light/brightness".to_owned(), b"42".to_vec()),
+        ("homie/5/device-1/light/brightness/$target".to_owned(), b"80".to_vec()),
+        ("homie/5/device-1/light/unknown".to_owned(), b"1".to_vec()),
+        (description_publish.topic, description_publish.payload),
+        ("homie/5/device-1/$state".to_owned(), b"ready".to_vec()),
+        ("homie/5/device-1/$alert/battery".to_owned(), b"low battery".to_vec()),
+        ("homie/5/device-2/$state".to_owned(), b"lost".to_vec()),
+        ("homie/5/device-2/node/prop".to_owned(), b"1".to_vec()),
+        ("not/a/homie/topic".to_owned(), b"1".to_vec()),
+    ];
+
+    let protocol = Homie5ControllerProtocol::new();
+    let devices = protocol.load_snapshot(snapshot);
+    assert_eq!(devices.len(), 2);
+
+    let brightness = PropertyPointer::new(HomieID::new_const("light"), HomieID::new_const("brightness"));
+    let device = &devices[&DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-1"))];
+    assert_eq!(device.state, Some(HomieDeviceStatus::Ready));
+    assert!(device.description.is_some());
+    assert_eq!(device.values.len(), 1);
+    assert_eq!(device.values[&brightness], HomieValue::Integer(42));
+    assert_eq!(device.targets[&brightness], HomieValue::Integer(80));
+    assert_eq!(device.alerts[&HomieID::new_const("battery")], "low battery");
+
+    // without a description values cannot be parsed
+    let device = &devices[&DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-2"))];
+    assert_eq!(device.state, Some(HomieDeviceStatus::Lost));
+    assert!(device.values.is_empty());
+}
+
+#[test]
+fn test_load_snapshot_domains() {
+    let (device_protocol, _) = Homie5DeviceProtocol::new(HomieID::new_const("device-1"), HomieDomain::Default);
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer).build(),
+                )
+                .build(),
+        )
+        .build();
+    let description_publish = device_protocol.publish_description(&description).unwrap();
+
+    // the same device id in two domains, only the default domain has a description
+    let snapshot = vec![
+        (description_publish.topic, description_publish.payload),
+        ("homie/5/device-1/$state".to_owned(), b"ready".to_vec()),
+        ("homie/5/device-1/light/brightness".to_owned(), b"42".to_vec()),
+        ("test/5/device-1/$state".to_owned(), b"init".to_vec()),
+        ("test/5/device-1/light/brightness".to_owned(), b"7".to_vec()),
+        ("test/5/device-1/$alert/battery".to_owned(), b"low battery".to_vec()),
+    ];
+
+    let protocol = Homie5ControllerProtocol::new();
+    let devices = protocol.load_snapshot(snapshot);
+    assert_eq!(devices.len(), 2);
+
+    let brightness = PropertyPointer::new(HomieID::new_const("light"), HomieID::new_const("brightness"));
+    let default_device = &devices[&DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-1"))];
+    assert_eq!(default_device.state, Some(HomieDeviceStatus::Ready));
+    assert_eq!(default_device.values[&brightness], HomieValue::Integer(42));
+    assert!(default_device.alerts.is_empty());
+
+    let test_device = &devices[&DeviceRef::new(HomieDomain::try_from("test").unwrap(), HomieID::new_const("device-1"))];
+    assert_eq!(test_device.state, Some(HomieDeviceStatus::Init));
+    assert!(test_device.description.is_none());
+    assert!(test_device.values.is_empty());
+    assert_eq!(test_device.alerts.len(), 1);
+
+    // removing the device in one domain keeps the other one
+    let devices = protocol.load_snapshot([
+        ("homie/5/device-1/$state".to_owned(), b"ready".to_vec()),
+        ("test/5/device-1/$state".to_owned(), b"ready".to_vec()),
+        ("test/5/device-1/$state".to_owned(), Vec::new()),
+    ]);
+    assert_eq!(devices.len(), 1);
+    assert!(devices.contains_key(&DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-1"))));
+}
@@ -1,3 +1,4 @@
+use homie5::device_description::*;
 use homie5::*;
 use std::collections::HashSet;
 use std::str::FromStr;
@@ -76,3 +77,47 @@ fn test_homie_data_type_hash_and_eq() {
     assert!(set.contains(&HomieDataType::Boolean));
     assert!(!set.contains(&HomieDataType::String));
 }
+
+#[test]
+fn test_unknown_datatype() {
+    // deserialization is strict, unknown datatypes and typos are rejected
+    for datatype in ["quaternion", "intger"] {
+        let json = format!(r#"{{ "datatype": "{}" }}"#, datatype);
+        assert!(serde_json::from_str::<HomiePropertyDescription>(&json).is_err());
+        assert!(datatype.parse::<HomieDataType>().is_err());
+    }
+
+    // the lenient parser keeps properties of unknown datatypes as string properties
+    let (description, issues) = HomieDeviceDescription::from_json_lenient(
+        r#"{
+            "homie": "5.0",
+            "version": 1,
+            "nodes": {
+                "node": {
+                    "properties": {
+                        "orientation": { "datatype": "quaternion", "format": "wxyz" },
+                        "level": { "datatype": "integer" }
+                    }
+                }
+            }
+        }"#,
+    );
+    let desc = description
+        .get_property_by_id(&HomieID::new_const("node"), &HomieID::new_const("orientation"))
+        .unwrap();
+    assert_eq!(desc.datatype, HomieDataType::String);
+    assert_eq!(desc.format, HomiePropertyFormat::Custom("wxyz".to_owned()));
+    assert_eq!(
+        HomieValue::parse("1,0,0,0", desc).ok(),
+        Some(HomieValue::String("1,0,0,0".to_owned()))
+    );
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].prop_id.as_deref(), Some("orientation"));
+    assert!(issues[0].message.contains("quaternion"));
+
+    // known datatypes are not affected
+    let desc = description
+        .get_property_by_id(&HomieID::new_const("node"), &HomieID::new_const("level"))
+        .unwrap();
+    assert_eq!(desc.datatype, HomieDataType::Integer);
+}
@@ -0,0 +1,62 @@
+use chrono::{Duration, TimeZone, Utc};
+use homie5::*;
+
+#[test]
+fn test_device_activity_staleness() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let property = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-1"),
+        HomieID::new_const("node"),
+        HomieID::new_const("prop"),
+    );
+    let max_age = Duration::seconds(60);
+    let mut clock = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    let mut activity = DeviceActivity::new();
+
+    // unknown devices are stale
+    assert!(activity.is_stale(&device, clock, max_age));
+
+    assert!(activity.record_message(
+        &Homie5Message::DeviceState {
+            device: device.clone(),
+            state: HomieDeviceStatus::Ready,
+        },
+        clock,
+    ));
+    assert!(!activity.is_stale(&device, clock, max_age));
+
+    clock += Duration::seconds(50);
+    assert!(activity.record_message(
+        &Homie5Message::PropertyValue {
+            property,
+            value: "1".to_owned(),
+        },
+        clock,
+    ));
+
+    // the value message keeps the device alive past the age of the state message
+    clock += Duration::seconds(50);
+    assert!(!activity.is_stale(&device, clock, max_age));
+    assert_eq!(activity.stale_devices(clock, max_age).count(), 0);
+
+    clock += Duration::seconds(11);
+    assert!(activity.is_stale(&device, clock, max_age));
+    assert_eq!(
+        activity.stale_devices(clock, max_age).collect::<Vec<_>>(),
+        vec![&device]
+    );
+
+    let record = activity.get(&device).unwrap();
+    assert!(record.last_state < record.last_value);
+    assert_eq!(activity.last_seen(&device), record.last_value);
+}
+
+#[test]
+fn test_device_activity_ignores_other_messages() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let mut activity = DeviceActivity::new();
+
+    assert!(!activity.record_message(&Homie5Message::DeviceRemoval { device: device.clone() }, Utc::now()));
+    assert!(activity.get(&device).is_none());
+}
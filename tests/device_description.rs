@@ -0,0 +1,675 @@
+use homie5::device_description::*;
+use homie5::extensions::EXT_META_ID;
+use homie5::*;
+
+fn build_node(prop_ids: &[&'static str]) -> HomieNodeDescription {
+    prop_ids
+        .iter()
+        .fold(NodeDescriptionBuilder::new().name("node"), |builder, id| {
+            builder.add_property(
+                HomieID::new_const(id),
+                PropertyDescriptionBuilder::new(HomieDataType::Integer).build(),
+            )
+        })
+        .build()
+}
+
+#[test]
+fn test_description_serialization_is_stable() {
+    let desc_a = DeviceDescriptionBuilder::new()
+        .name("device")
+        .add_node(HomieID::new_const("node-b"), build_node(&["prop-z", "prop-a"]))
+        .add_node(HomieID::new_const("node-a"), build_node(&["prop-y", "prop-b"]))
+        .build();
+    let desc_b = DeviceDescriptionBuilder::new()
+        .name("device")
+        .add_node(HomieID::new_const("node-a"), build_node(&["prop-b", "prop-y"]))
+        .add_node(HomieID::new_const("node-b"), build_node(&["prop-a", "prop-z"]))
+        .build();
+
+    let json_a = serde_json::to_vec(&desc_a).unwrap();
+    assert_eq!(json_a, serde_json::to_vec(&desc_a).unwrap());
+    assert_eq!(desc_a.content_hash(), desc_b.content_hash());
+
+    // apart from the declared order the serialization does not depend on the insertion order
+    let clear_order = |desc: &HomieDeviceDescription| {
+        let mut desc = desc.clone();
+        desc.set_node_order([]);
+        desc.nodes.values_mut().for_each(|node| node.set_property_order([]));
+        serde_json::to_vec(&desc).unwrap()
+    };
+    assert_eq!(clear_order(&desc_a), clear_order(&desc_b));
+}
+
+#[test]
+fn test_declared_order() {
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node-b"), build_node(&["prop-z", "prop-a"]))
+        .add_node(HomieID::new_const("node-a"), build_node(&["prop-y", "prop-b"]))
+        .build();
+    let ids = |desc: &HomieDeviceDescription| -> Vec<String> {
+        desc.iter()
+            .map(|(node_id, _, prop_id, _)| format!("{}/{}", node_id, prop_id))
+            .collect()
+    };
+    assert_eq!(
+        ids(&description),
+        vec!["node-b/prop-z", "node-b/prop-a", "node-a/prop-y", "node-a/prop-b"]
+    );
+
+    // the order is published with the description
+    let json = serde_json::to_string(&description).unwrap();
+    assert!(json.contains(r#""node_order":["node-b","node-a"]"#));
+    assert!(json.contains(r#""property_order":["prop-z","prop-a"]"#));
+    let parsed: HomieDeviceDescription = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.node_order(), description.node_order());
+    assert_eq!(ids(&parsed), ids(&description));
+
+    // without a declared order everything is iterated in id order and nothing is published
+    let mut unordered = description.clone();
+    unordered.set_node_order([]);
+    unordered
+        .nodes
+        .values_mut()
+        .for_each(|node| node.set_property_order([]));
+    assert_eq!(
+        ids(&unordered),
+        vec!["node-a/prop-b", "node-a/prop-y", "node-b/prop-a", "node-b/prop-z"]
+    );
+    let json = serde_json::to_string(&unordered).unwrap();
+    assert!(!json.contains("node_order") && !json.contains("property_order"));
+
+    // new nodes are appended
+    let extended = DeviceDescriptionBuilder::from_description(&unordered)
+        .add_node(HomieID::new_const("node-0"), build_node(&["prop"]))
+        .build();
+    let nodes: Vec<_> = extended.ordered_nodes().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(nodes, vec!["node-a", "node-b", "node-0"]);
+
+    // unknown ids and duplicates are dropped
+    let mut reordered = description.clone();
+    reordered.set_node_order(
+        ["node-a", "node-x", "node-a", "node-b"]
+            .into_iter()
+            .map(HomieID::new_const),
+    );
+    assert_eq!(
+        reordered.node_order(),
+        &[HomieID::new_const("node-a"), HomieID::new_const("node-b")]
+    );
+
+    // the order is not part of the version hash
+    let (mut a, mut b) = (description.clone(), reordered);
+    a.update_version();
+    b.update_version();
+    assert_eq!(a.version, b.version);
+}
+
+#[test]
+fn test_settable_properties() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("state"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Boolean)
+                        .settable(true)
+                        .build(),
+                )
+                .add_property(
+                    HomieID::new_const("power"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Float).build(),
+                )
+                .build(),
+        )
+        .add_node(
+            HomieID::new_const("sensor"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("interval"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .settable(true)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    let props: Vec<_> = description
+        .settable_properties(&device)
+        .map(|(prop, desc)| (prop.to_topic().build(), desc.datatype))
+        .collect();
+
+    assert_eq!(
+        props,
+        vec![
+            ("homie/5/test-device-1/light/state".to_owned(), HomieDataType::Boolean),
+            (
+                "homie/5/test-device-1/sensor/interval".to_owned(),
+                HomieDataType::Integer
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_fields_are_preserved() {
+    let json = r#"{"homie":"5.0","version":1,"future-field":{"a":[1,2]},"nodes":{}}"#;
+
+    let description: HomieDeviceDescription = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        description.extra.get("future-field"),
+        Some(&serde_json::json!({"a": [1, 2]}))
+    );
+
+    let republished: serde_json::Value = serde_json::to_value(&description).unwrap();
+    assert_eq!(republished["future-field"], serde_json::json!({"a": [1, 2]}));
+    assert!(DeviceDescriptionBuilder::new().build().extra.is_empty());
+}
+
+#[test]
+fn test_property_archetypes() {
+    let controllable = PropertyDescriptionBuilder::new(HomieDataType::String)
+        .settable(false)
+        .retained(false)
+        .controllable(HomieDataType::Float, HOMIE_UNIT_DEGREE_CELSIUS)
+        .build();
+    assert_eq!(controllable.datatype, HomieDataType::Float);
+    assert!(controllable.settable);
+    assert!(controllable.retained);
+    assert_eq!(controllable.unit.as_deref(), Some(HOMIE_UNIT_DEGREE_CELSIUS));
+
+    let readonly = PropertyDescriptionBuilder::new(HomieDataType::String)
+        .settable(true)
+        .readonly(HomieDataType::Integer, HOMIE_UNIT_WATT)
+        .build();
+    assert_eq!(readonly.datatype, HomieDataType::Integer);
+    assert!(!readonly.settable);
+    assert!(readonly.retained);
+    assert_eq!(readonly.unit.as_deref(), Some(HOMIE_UNIT_WATT));
+}
+
+#[test]
+fn test_property_try_build() {
+    for datatype in [HomieDataType::Enum, HomieDataType::Color] {
+        assert!(matches!(
+            PropertyDescriptionBuilder::new(datatype).try_build(),
+            Err(Homie5ProtocolError::MissingRequiredFormat(dt)) if dt == datatype
+        ));
+    }
+
+    let enumeration = PropertyDescriptionBuilder::new(HomieDataType::Enum)
+        .format(HomiePropertyFormat::Enum(vec!["low".to_owned(), "high".to_owned()]))
+        .try_build()
+        .unwrap();
+    assert_eq!(enumeration.datatype, HomieDataType::Enum);
+
+    let boolean = PropertyDescriptionBuilder::new(HomieDataType::Boolean)
+        .try_build()
+        .unwrap();
+    assert_eq!(boolean.format, HomiePropertyFormat::Empty);
+    assert_eq!(PropertyDescriptionBuilder::new(HomieDataType::Boolean).build(), boolean);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "format is required")]
+fn test_property_build_panics_without_required_format() {
+    PropertyDescriptionBuilder::new(HomieDataType::Enum).build();
+}
+
+#[test]
+fn test_from_json_lenient() {
+    let json = r#"{
+        "homie": "5.0",
+        "version": 1,
+        "nodes": {
+            "light": {
+                "properties": {
+                    "state": { "datatype": "boolean", "settable": true },
+                    "color": { "datatype": "color", "format": "rgb,bogus" },
+                    "Invalid_Id": { "datatype": "integer" },
+                    "level": { "datatype": 5 }
+                }
+            }
+        }
+    }"#;
+
+    let (description, issues) = HomieDeviceDescription::from_json_lenient(json);
+
+    let node = description.get_node(&HomieID::new_const("light")).unwrap();
+    let props: Vec<_> = node.properties.keys().map(|id| id.as_str()).collect();
+    assert_eq!(props, vec!["color", "state"]);
+    assert_eq!(
+        node.properties.get(&HomieID::new_const("color")).unwrap().format,
+        HomiePropertyFormat::Custom("rgb,bogus".to_owned())
+    );
+
+    let issue_props: Vec<_> = issues.iter().map(|issue| issue.prop_id.as_deref()).collect();
+    assert_eq!(issue_props, vec![Some("Invalid_Id"), Some("color"), Some("level")]);
+    assert!(issues.iter().all(|issue| issue.node_id.as_deref() == Some("light")));
+}
+
+#[test]
+fn test_validate_snapshot_unadvertised_extension() {
+    let mut description = DeviceDescriptionBuilder::new().build();
+    let snapshot: std::collections::HashMap<String, Vec<u8>> = [
+        ("homie/5/test-device-1/$state", "ready"),
+        ("homie/5/test-device-1/$meta/$mainkey-ids", "1"),
+        // topics of other devices are not validated against this description
+        ("homie/5/test-device-2/$meta/$mainkey-ids", "1"),
+    ]
+    .into_iter()
+    .map(|(topic, value)| (topic.to_owned(), value.as_bytes().to_vec()))
+    .collect();
+
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let issues = description.validate_snapshot(&device, &snapshot);
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(
+        &issues[0],
+        SnapshotIssue::UnadvertisedExtension { topic, extension }
+            if topic == "homie/5/test-device-1/$meta/$mainkey-ids" && *extension == EXT_META_ID
+    ));
+
+    description.extensions = vec!["org.homie.meta:0.1.0:[5.x]".to_owned()];
+    assert!(description.has_extension(EXT_META_ID));
+    assert!(description.validate_snapshot(&device, &snapshot).is_empty());
+}
+
+#[test]
+fn test_validate_snapshot() {
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+                            min: Some(0),
+                            max: Some(100),
+                            step: None,
+                        }))
+                        .build(),
+                )
+                .add_property(
+                    HomieID::new_const("state"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Boolean).build(),
+                )
+                .add_property(
+                    HomieID::new_const("power"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Float).build(),
+                )
+                .build(),
+        )
+        .build();
+
+    let snapshot: std::collections::HashMap<String, Vec<u8>> = [
+        ("homie/5/test-device-1/$state", "ready"),
+        ("homie/5/test-device-1/light/brightness", "142"),
+        ("homie/5/test-device-1/light/state", "true"),
+        ("homie/5/test-device-1/light/color", "rgb,1,2,3"),
+        // topics of other devices and domains are ignored
+        ("homie/5/test-device-2/light/state", "invalid"),
+        ("test/5/test-device-1/light/unknown", "1"),
+    ]
+    .into_iter()
+    .map(|(topic, value)| (topic.to_owned(), value.as_bytes().to_vec()))
+    .collect();
+
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let issues = description.validate_snapshot(&device, &snapshot);
+
+    assert_eq!(issues.len(), 3);
+    assert!(matches!(
+        &issues[0],
+        SnapshotIssue::InvalidValue { topic, .. } if topic == "homie/5/test-device-1/light/brightness"
+    ));
+    assert!(matches!(
+        &issues[1],
+        SnapshotIssue::UnknownProperty { topic } if topic == "homie/5/test-device-1/light/color"
+    ));
+    assert!(matches!(
+        &issues[2],
+        SnapshotIssue::MissingValue { node_id, prop_id } if node_id.as_str() == "light" && prop_id.as_str() == "power"
+    ));
+}
+
+#[test]
+fn test_property_refs() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("light"), build_node(&["state", "brightness"]))
+        .build();
+
+    let refs: Vec<_> = description.property_refs(&device).map(|(prop, _)| prop).collect();
+
+    assert_eq!(
+        refs,
+        vec![
+            PropertyRef::new(
+                HomieDomain::Default,
+                HomieID::new_const("test-device-1"),
+                HomieID::new_const("light"),
+                HomieID::new_const("state"),
+            ),
+            PropertyRef::new(
+                HomieDomain::Default,
+                HomieID::new_const("test-device-1"),
+                HomieID::new_const("light"),
+                HomieID::new_const("brightness"),
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_placeholder_value() {
+    let integer = PropertyDescriptionBuilder::new(HomieDataType::Integer)
+        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+            min: Some(10),
+            max: Some(20),
+            step: None,
+        }))
+        .build();
+    assert_eq!(integer.placeholder_value(), Some(HomieValue::Integer(10)));
+
+    let negative = PropertyDescriptionBuilder::new(HomieDataType::Integer)
+        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+            min: None,
+            max: Some(-5),
+            step: None,
+        }))
+        .build();
+    assert_eq!(negative.placeholder_value(), Some(HomieValue::Integer(-5)));
+
+    // the min is always on a step
+    let stepped = PropertyDescriptionBuilder::new(HomieDataType::Integer)
+        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+            min: Some(-10),
+            max: Some(10),
+            step: Some(3),
+        }))
+        .build();
+    assert_eq!(stepped.placeholder_value(), Some(HomieValue::Integer(-10)));
+
+    let float = PropertyDescriptionBuilder::new(HomieDataType::Float)
+        .format(HomiePropertyFormat::FloatRange(FloatRange {
+            min: Some(-1.0),
+            max: Some(1.0),
+            step: Some(0.75),
+        }))
+        .build();
+    assert_eq!(float.placeholder_value(), Some(HomieValue::Float(-1.0)));
+
+    let enumeration = PropertyDescriptionBuilder::new(HomieDataType::Enum)
+        .format(HomiePropertyFormat::Enum(vec!["low".to_owned(), "high".to_owned()]))
+        .build();
+    assert_eq!(
+        enumeration.placeholder_value(),
+        Some(HomieValue::Enum("low".to_owned()))
+    );
+
+    let boolean = PropertyDescriptionBuilder::new(HomieDataType::Boolean).build();
+    assert_eq!(boolean.placeholder_value(), Some(HomieValue::Bool(false)));
+
+    let json = PropertyDescriptionBuilder::new(HomieDataType::JSON).build();
+    assert_eq!(json.placeholder_value(), None);
+}
+
+#[test]
+fn test_is_recommended_unit() {
+    let recommended = PropertyDescriptionBuilder::new(HomieDataType::Float)
+        .unit(HOMIE_UNIT_DEGREE_CELSIUS)
+        .build();
+    assert!(recommended.is_recommended_unit());
+
+    let custom = PropertyDescriptionBuilder::new(HomieDataType::Float)
+        .unit("celsius")
+        .build();
+    assert!(!custom.is_recommended_unit());
+
+    let no_unit = PropertyDescriptionBuilder::new(HomieDataType::Float).build();
+    assert!(no_unit.is_recommended_unit());
+}
+
+#[test]
+fn test_description_from_nodes_map() {
+    let mut nodes = HomieNodes::new();
+    nodes.insert(HomieID::new_const("node-a"), build_node(&["prop-a"]));
+    nodes.insert(HomieID::new_const("node-b"), build_node(&["prop-b"]));
+
+    let desc = HomieDeviceDescription::with_nodes(nodes.clone());
+    let incremental = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node-a"), build_node(&["prop-a"]))
+        .add_node(HomieID::new_const("node-b"), build_node(&["prop-b"]))
+        .build();
+    assert_eq!(
+        serde_json::to_value(&desc).unwrap(),
+        serde_json::to_value(&incremental).unwrap()
+    );
+    assert_eq!(desc.version, incremental.version);
+
+    let json = serde_json::to_value(DeviceDescriptionBuilder::new().name("dev").nodes(nodes).build()).unwrap();
+    assert_eq!(json["name"], "dev");
+    assert!(json["nodes"]["node-a"]["properties"]["prop-a"].is_object());
+    assert!(json["nodes"]["node-b"]["properties"]["prop-b"].is_object());
+}
+
+#[test]
+fn test_root_and_child_devices() {
+    let root = DeviceDescriptionBuilder::new()
+        .add_child(HomieID::new_const("child-1"))
+        .add_child(HomieID::new_const("child-2"))
+        .build();
+    assert!(root.is_root());
+    assert!(!root.is_child());
+    let children: Vec<_> = root.child_refs(&HomieDomain::Default).collect();
+    assert_eq!(
+        children,
+        vec![
+            DeviceRef::new(HomieDomain::Default, HomieID::new_const("child-1")),
+            DeviceRef::new(HomieDomain::Default, HomieID::new_const("child-2")),
+        ]
+    );
+
+    let child = DeviceDescriptionBuilder::new()
+        .root(HomieID::new_const("root-device"))
+        .build();
+    assert!(child.is_child());
+    assert!(!child.is_root());
+    assert_eq!(child.child_refs(&HomieDomain::Default).count(), 0);
+}
+
+#[test]
+fn test_check_topic_collisions() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("root-device"));
+    let description = DeviceDescriptionBuilder::new()
+        .add_child(HomieID::new_const("child-1"))
+        .add_child(HomieID::new_const("child-2"))
+        .add_node(HomieID::new_const("node-a"), build_node(&["prop-a", "prop-b"]))
+        .build();
+    assert_eq!(description.check_topic_collisions(&device), Ok(()));
+
+    // a child listed twice and a child sharing the id of the root device
+    let colliding = DeviceDescriptionBuilder::from_description(&description)
+        .add_child(HomieID::new_const("child-1"))
+        .add_child(HomieID::new_const("root-device"))
+        .build();
+    assert_eq!(
+        colliding.check_topic_collisions(&device),
+        Err(vec![
+            "homie/5/child-1/$description".to_owned(),
+            "homie/5/child-1/$state".to_owned(),
+            "homie/5/root-device/$description".to_owned(),
+            "homie/5/root-device/$state".to_owned(),
+        ])
+    );
+}
+
+#[test]
+fn test_property_eq_ignoring_name() {
+    let prop = PropertyDescriptionBuilder::new(HomieDataType::Integer)
+        .name("Brightness")
+        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+            min: Some(0),
+            max: Some(100),
+            step: None,
+        }))
+        .build();
+
+    let renamed = PropertyDescriptionBuilder::from_description(&prop)
+        .name("Dimmer")
+        .build();
+    assert_ne!(prop, renamed);
+    assert!(prop.eq_ignoring_name(&renamed));
+
+    let reformatted = PropertyDescriptionBuilder::from_description(&prop)
+        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+            min: Some(0),
+            max: Some(255),
+            step: None,
+        }))
+        .build();
+    assert!(!prop.eq_ignoring_name(&reformatted));
+}
+
+#[test]
+fn test_description_json_round_trip() {
+    let description = DeviceDescriptionBuilder::new()
+        .name("light")
+        .add_node(HomieID::new_const("node"), build_node(&["on", "brightness"]))
+        .build();
+
+    let json = description.to_json().unwrap();
+    let parsed = HomieDeviceDescription::from_json(&json).unwrap();
+    assert_eq!(parsed.version, description.version);
+    assert_eq!(parsed.to_json().unwrap(), json);
+
+    let homie_4 = r#"{"homie":"4.0","version":1,"name":"old device"}"#;
+    assert!(matches!(
+        HomieDeviceDescription::from_json(homie_4),
+        Err(Homie5ProtocolError::InvalidDeviceDescription)
+    ));
+
+    let homie_5_2 = r#"{"homie":"5.2","version":1,"name":"new device"}"#;
+    let description = HomieDeviceDescription::from_json(homie_5_2).unwrap();
+    assert_eq!(description.homie, "5.2");
+
+    assert!(matches!(
+        HomieDeviceDescription::from_json("{"),
+        Err(Homie5ProtocolError::InvalidDeviceDescription)
+    ));
+}
+
+#[test]
+fn test_device_fingerprint() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-1"));
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node"), build_node(&["on"]))
+        .build();
+    let fingerprint = device.fingerprint(&description);
+    assert_eq!(fingerprint.device(), &device);
+
+    // same content, restored from persisted state
+    let json = serde_json::to_string(&fingerprint).unwrap();
+    let restored: DeviceFingerprint = serde_json::from_str(&json).unwrap();
+    assert!(restored.unchanged_since(&fingerprint));
+    let mut republished = description.clone();
+    republished.version += 1;
+    assert!(description
+        .fingerprint(&device)
+        .unchanged_since(&republished.fingerprint(&device)));
+
+    // changed description
+    let changed = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node"), build_node(&["on", "brightness"]))
+        .build();
+    assert!(!device.fingerprint(&changed).unchanged_since(&fingerprint));
+
+    // same description for another device
+    let other = DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-2"));
+    assert!(!other.fingerprint(&description).unchanged_since(&fingerprint));
+
+    // the content hash is specified (FNV-1a of the serialized description), so it can be persisted
+    // {"version":0,"homie":"5.0"}
+    assert_eq!(
+        DeviceDescriptionBuilder::new().build().content_hash(),
+        0xc841_f774_a277_952e
+    );
+}
+
+#[test]
+fn test_description_diff() {
+    let old = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node-a"), build_node(&["on", "removed", "level"]))
+        .add_node(HomieID::new_const("node-b"), build_node(&["on"]))
+        .build();
+    assert!(old.diff(&old).is_empty());
+
+    let mut node_a = build_node(&["on", "level"]);
+    node_a
+        .properties
+        .get_mut(&HomieID::new_const("level"))
+        .unwrap()
+        .settable = true;
+    let mut node_b = build_node(&["on"]);
+    node_b.name = Some("renamed".to_owned());
+    let new = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node-a"), node_a)
+        .add_node(HomieID::new_const("node-b"), node_b)
+        .add_node(HomieID::new_const("node-c"), build_node(&["on"]))
+        .build();
+
+    let pointer = |node_id, prop_id| PropertyPointer::new(HomieID::new_const(node_id), HomieID::new_const(prop_id));
+    let diff = old.diff(&new);
+    assert_eq!(diff.added_nodes, vec![HomieID::new_const("node-c")]);
+    assert!(diff.removed_nodes.is_empty());
+    assert_eq!(diff.changed_nodes, vec![HomieID::new_const("node-b")]);
+    assert_eq!(diff.added_properties, vec![pointer("node-c", "on")]);
+    assert_eq!(diff.removed_properties, vec![pointer("node-a", "removed")]);
+    assert_eq!(diff.changed_properties, vec![pointer("node-a", "level")]);
+
+    // the reverse diff
+    let diff = new.diff(&old);
+    assert_eq!(diff.removed_nodes, vec![HomieID::new_const("node-c")]);
+    assert_eq!(diff.removed_properties, vec![pointer("node-c", "on")]);
+    assert_eq!(diff.added_properties, vec![pointer("node-a", "removed")]);
+}
+
+#[test]
+fn test_description_topics_changed() {
+    let old = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node-a"), build_node(&["on", "level"]))
+        .build();
+    assert!(!old.topics_changed(&old));
+
+    // format and name only changes keep the topics
+    let mut node = build_node(&["on", "level"]);
+    node.name = Some("renamed".to_owned());
+    node.properties.get_mut(&HomieID::new_const("level")).unwrap().format =
+        HomiePropertyFormat::IntegerRange(IntegerRange {
+            min: Some(0),
+            max: Some(100),
+            step: None,
+        });
+    let format_changed = DeviceDescriptionBuilder::new()
+        .name("renamed")
+        .add_node(HomieID::new_const("node-a"), node)
+        .build();
+    assert!(!old.topics_changed(&format_changed));
+
+    let added_property = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node-a"), build_node(&["on", "level", "color"]))
+        .build();
+    assert!(old.topics_changed(&added_property));
+    assert!(added_property.topics_changed(&old));
+
+    let renamed_node = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node-b"), build_node(&["on", "level"]))
+        .build();
+    assert!(old.topics_changed(&renamed_node));
+}
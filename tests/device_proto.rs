@@ -0,0 +1,550 @@
+use homie5::device_description::*;
+use homie5::*;
+
+fn create_protocol() -> Homie5DeviceProtocol {
+    let (protocol, _) = Homie5DeviceProtocol::new("test-device-1".try_into().unwrap(), HomieDomain::Default);
+    protocol
+}
+
+#[test]
+fn test_clear_alerts() {
+    let protocol = create_protocol();
+    let publishes = protocol.clear_alerts(&["battery", "sensor-fault"]).unwrap();
+
+    assert_eq!(publishes.len(), 2);
+    assert_eq!(publishes[0].topic, "homie/5/test-device-1/$alert/battery");
+    assert_eq!(publishes[1].topic, "homie/5/test-device-1/$alert/sensor-fault");
+    for p in publishes {
+        assert!(p.retain);
+        assert!(p.payload.is_empty());
+    }
+
+    assert!(matches!(
+        protocol.clear_alerts(&["battery", "sensor/fault"]),
+        Err(Homie5ProtocolError::InvalidHomieID(_))
+    ));
+}
+
+#[test]
+fn test_clear_logs() {
+    let protocol = create_protocol();
+
+    let topics: Vec<_> = protocol.clear_logs().map(|p| p.topic).collect();
+
+    assert_eq!(
+        topics,
+        vec![
+            "homie/5/test-device-1/$log/debug",
+            "homie/5/test-device-1/$log/info",
+            "homie/5/test-device-1/$log/warn",
+            "homie/5/test-device-1/$log/error",
+            "homie/5/test-device-1/$log/fatal",
+        ]
+    );
+}
+
+#[test]
+fn test_validate_set() {
+    let protocol = create_protocol();
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+                            min: Some(0),
+                            max: Some(100),
+                            step: None,
+                        }))
+                        .settable(true)
+                        .build(),
+                )
+                .add_property(
+                    HomieID::new_const("power"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Float).build(),
+                )
+                .build(),
+        )
+        .build();
+    let prop = |id: &'static str| {
+        PropertyRef::new(
+            HomieDomain::Default,
+            protocol.id().clone(),
+            HomieID::new_const("light"),
+            HomieID::new_const(id),
+        )
+    };
+
+    assert_eq!(
+        protocol.validate_set(&prop("brightness"), "42", &description).ok(),
+        Some(HomieValue::Integer(42))
+    );
+    assert!(matches!(
+        protocol.validate_set(&prop("brightness"), "142", &description),
+        Err(Homie5ProtocolError::InvalidHomieValue(_))
+    ));
+    assert!(matches!(
+        protocol.validate_set(&prop("power"), "1.0", &description),
+        Err(Homie5ProtocolError::PropertyNotSettable)
+    ));
+    assert!(matches!(
+        protocol.validate_set(&prop("color"), "1.0", &description),
+        Err(Homie5ProtocolError::PropertyNotFound)
+    ));
+}
+
+#[test]
+fn test_empty_alert_and_log_payload() {
+    let protocol = create_protocol();
+
+    let alert = protocol.publish_alert(&HomieID::new_const("battery"), "");
+    assert_eq!(alert.payload, vec![0_u8]);
+
+    let log = protocol.publish_log(DeviceLogLevel::Info, "");
+    assert_eq!(log.payload, vec![0_u8]);
+
+    let alert = protocol.publish_alert(&HomieID::new_const("battery"), "Battery is low!");
+    assert_eq!(alert.payload, b"Battery is low!".to_vec());
+}
+
+#[test]
+fn test_reconfigure_state() {
+    let protocol = create_protocol();
+
+    let begin = protocol.begin_reconfigure();
+    assert_eq!(begin.topic, "homie/5/test-device-1/$state");
+    assert_eq!(begin.payload, b"init".to_vec());
+    assert!(begin.retain);
+
+    let end = protocol.end_reconfigure();
+    assert_eq!(end.topic, "homie/5/test-device-1/$state");
+    assert_eq!(end.payload, b"ready".to_vec());
+}
+
+#[test]
+fn test_publish_value_from_desc() {
+    let protocol = create_protocol();
+    let node_id = HomieID::new_const("light");
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            node_id.clone(),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("state"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Boolean).build(),
+                )
+                .add_property(
+                    HomieID::new_const("action"),
+                    PropertyDescriptionBuilder::new(HomieDataType::String)
+                        .retained(false)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    let publish = protocol
+        .publish_value_from_desc(&node_id, &HomieID::new_const("state"), "true", &description)
+        .unwrap();
+    assert_eq!(publish.topic, "homie/5/test-device-1/light/state");
+    assert!(publish.retain);
+
+    let publish = protocol
+        .publish_value_from_desc(&node_id, &HomieID::new_const("action"), "toggle", &description)
+        .unwrap();
+    assert!(!publish.retain);
+
+    assert!(matches!(
+        protocol.publish_value_from_desc(&node_id, &HomieID::new_const("missing"), "1", &description),
+        Err(Homie5ProtocolError::PropertyNotFound)
+    ));
+}
+
+#[test]
+fn test_publish_target_from_desc() {
+    let protocol = create_protocol();
+    let node_id = HomieID::new_const("light");
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            node_id.clone(),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .settable(true)
+                        .build(),
+                )
+                .add_property(
+                    HomieID::new_const("action"),
+                    PropertyDescriptionBuilder::new(HomieDataType::String)
+                        .settable(true)
+                        .retained(false)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    let publish = protocol
+        .publish_target_from_desc(&node_id, &HomieID::new_const("brightness"), "80", &description)
+        .unwrap();
+    assert_eq!(publish.topic, "homie/5/test-device-1/light/brightness/$target");
+    assert_eq!(publish.payload, b"80");
+    assert!(publish.retain);
+
+    let publish = protocol
+        .publish_target_from_desc(&node_id, &HomieID::new_const("action"), "toggle", &description)
+        .unwrap();
+    assert_eq!(publish.topic, "homie/5/test-device-1/light/action/$target");
+    assert!(!publish.retain);
+
+    assert!(matches!(
+        protocol.publish_target_from_desc(&node_id, &HomieID::new_const("missing"), "1", &description),
+        Err(Homie5ProtocolError::PropertyNotFound)
+    ));
+}
+
+#[test]
+fn test_property_ref_publishes() {
+    let protocol = create_protocol();
+    let prop = protocol.property_ref(HomieID::new_const("light"), HomieID::new_const("state"));
+
+    let publish = protocol.publish_value_prop(&prop, "true", true).unwrap();
+    assert_eq!(publish.topic, "homie/5/test-device-1/light/state");
+    assert_eq!(publish.payload, b"true");
+    assert!(publish.retain);
+
+    let publish = protocol.publish_target_prop(&prop, "false", false).unwrap();
+    assert_eq!(publish.topic, "homie/5/test-device-1/light/state/$target");
+    assert!(!publish.retain);
+
+    let subscription = protocol.subscribe_prop(&prop).unwrap();
+    assert_eq!(subscription.topic, "homie/5/test-device-1/light/state/set");
+
+    let other_device = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-2"),
+        HomieID::new_const("light"),
+        HomieID::new_const("state"),
+    );
+    let other_domain = PropertyRef::new(
+        HomieDomain::try_from("test").unwrap(),
+        HomieID::new_const("test-device-1"),
+        HomieID::new_const("light"),
+        HomieID::new_const("state"),
+    );
+    for prop in [other_device, other_domain] {
+        assert!(matches!(
+            protocol.publish_value_prop(&prop, "true", true),
+            Err(Homie5ProtocolError::PropertyDeviceMismatch)
+        ));
+        assert!(matches!(
+            protocol.publish_target_prop(&prop, "true", true),
+            Err(Homie5ProtocolError::PropertyDeviceMismatch)
+        ));
+        assert!(matches!(
+            protocol.subscribe_prop(&prop),
+            Err(Homie5ProtocolError::PropertyDeviceMismatch)
+        ));
+    }
+}
+
+#[test]
+fn test_with_version() {
+    let protocol = create_protocol().with_version("4");
+    assert_eq!(protocol.version(), "4");
+
+    let publish = protocol.publish_state(HomieDeviceStatus::Ready);
+    assert_eq!(publish.topic, "homie/4/test-device-1/$state");
+    assert_eq!(protocol.last_will().topic, "homie/4/test-device-1/$state");
+
+    assert_eq!(create_protocol().version(), HOMIE_VERSION);
+}
+
+#[test]
+fn test_node_and_property_ref() {
+    let protocol = create_protocol();
+
+    let node = protocol.node_ref(HomieID::new_const("node"));
+    assert_eq!(node.device_ref(), protocol.device_ref());
+    assert_eq!(node.node_id().as_str(), "node");
+
+    let prop = protocol.property_ref(HomieID::new_const("node"), HomieID::new_const("prop"));
+    assert_eq!(prop.homie_domain(), protocol.homie_domain());
+    assert_eq!(prop.device_id(), protocol.id());
+    assert_eq!(prop.node_id().as_str(), "node");
+    assert_eq!(prop.prop_id().as_str(), "prop");
+}
+
+#[test]
+fn test_validate_plan() {
+    let protocol = create_protocol();
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .settable(true)
+                        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+                            min: Some(0),
+                            max: Some(100),
+                            step: None,
+                        }))
+                        .build(),
+                )
+                .add_property(
+                    HomieID::new_const("power"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Boolean)
+                        .settable(true)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    let valid = protocol.validate_plan(&description, |_, prop_id| match prop_id.as_str() {
+        "brightness" => Some(HomieValue::Integer(50)),
+        _ => Some(HomieValue::Bool(true)),
+    });
+    assert!(valid.is_ok());
+
+    let issues = protocol
+        .validate_plan(&description, |_, prop_id| match prop_id.as_str() {
+            "brightness" => Some(HomieValue::Integer(50)),
+            _ => None,
+        })
+        .unwrap_err();
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(
+        &issues[0],
+        PlanIssue::MissingValue { prop_id, .. } if prop_id.as_str() == "power"
+    ));
+
+    // values are checked against the format of their property
+    let issues = protocol
+        .validate_plan(&description, |_, prop_id| match prop_id.as_str() {
+            "brightness" => Some(HomieValue::Integer(150)),
+            _ => Some(HomieValue::Integer(1)),
+        })
+        .unwrap_err();
+    assert_eq!(issues.len(), 2);
+    assert!(matches!(
+        &issues[0],
+        PlanIssue::InvalidValue {
+            prop_id,
+            error: Homie5ValueConversionError::IntegerOutOfRange(150, _),
+            ..
+        } if prop_id.as_str() == "brightness"
+    ));
+    assert!(matches!(
+        &issues[1],
+        PlanIssue::InvalidValue {
+            prop_id,
+            error: Homie5ValueConversionError::DatatypeMismatch(HomieDataType::Boolean, Some(HomieDataType::Integer)),
+            ..
+        } if prop_id.as_str() == "power"
+    ));
+}
+
+#[test]
+fn test_reconfigure_subscriptions() {
+    let protocol = create_protocol();
+    let node = |props: &[(&'static str, bool)]| {
+        props
+            .iter()
+            .fold(NodeDescriptionBuilder::new(), |builder, (id, settable)| {
+                builder.add_property(
+                    HomieID::new_const(id),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .settable(*settable)
+                        .build(),
+                )
+            })
+            .build()
+    };
+    let old = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("node"),
+            node(&[
+                ("kept", true),
+                ("removed", true),
+                ("flipped-on", false),
+                ("flipped-off", true),
+            ]),
+        )
+        .build();
+    let new = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("node"),
+            node(&[("kept", true), ("flipped-on", true), ("flipped-off", false)]),
+        )
+        .add_node(HomieID::new_const("added"), node(&[("level", true), ("sensor", false)]))
+        .build();
+
+    let (subs, unsubs) = protocol.reconfigure_subscriptions(&old, &new).unwrap();
+    let sub_topics: Vec<_> = subs.iter().map(|s| s.topic.as_str()).collect();
+    let unsub_topics: Vec<_> = unsubs.iter().map(|u| u.topic.as_str()).collect();
+    assert_eq!(
+        sub_topics,
+        vec![
+            "homie/5/test-device-1/added/level/set",
+            "homie/5/test-device-1/node/flipped-on/set",
+        ]
+    );
+    assert_eq!(
+        unsub_topics,
+        vec![
+            "homie/5/test-device-1/node/flipped-off/set",
+            "homie/5/test-device-1/node/removed/set",
+        ]
+    );
+
+    let (subs, unsubs) = protocol.reconfigure_subscriptions(&new, &new).unwrap();
+    assert!(subs.is_empty() && unsubs.is_empty());
+}
+
+#[test]
+fn test_clear_removed_properties() {
+    let protocol = create_protocol();
+    let node = |props: &[(&'static str, bool)]| {
+        props
+            .iter()
+            .fold(NodeDescriptionBuilder::new(), |builder, (id, retained)| {
+                builder.add_property(
+                    HomieID::new_const(id),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .retained(*retained)
+                        .build(),
+                )
+            })
+            .build()
+    };
+    let old = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("node"),
+            node(&[("kept", true), ("removed", true), ("event", false)]),
+        )
+        .build();
+    let new = DeviceDescriptionBuilder::new()
+        .add_node(HomieID::new_const("node"), node(&[("kept", true)]))
+        .build();
+
+    let publishes: Vec<_> = protocol.clear_removed_properties(&old, &new).collect();
+    let topics: Vec<_> = publishes.iter().map(|p| p.topic.as_str()).collect();
+    assert_eq!(
+        topics,
+        vec![
+            "homie/5/test-device-1/node/event/$target",
+            "homie/5/test-device-1/node/removed",
+            "homie/5/test-device-1/node/removed/$target",
+        ]
+    );
+    assert!(publishes.iter().all(|p| p.retain && p.payload.is_empty()));
+}
+
+#[test]
+fn test_graceful_disconnect() {
+    let protocol = create_protocol();
+    let description = DeviceDescriptionBuilder::new().build();
+
+    let publishes = protocol.graceful_disconnect(&description).unwrap();
+    assert_eq!(publishes.len(), 1);
+    assert_eq!(publishes[0].topic, "homie/5/test-device-1/$state");
+    assert_eq!(publishes[0].payload, b"disconnected");
+    assert!(publishes[0].retain);
+}
+
+#[test]
+fn test_sleep_lifecycle() {
+    let protocol = create_protocol();
+    let description = DeviceDescriptionBuilder::new().build();
+
+    let sleep = protocol.enter_sleep();
+    assert_eq!(sleep.topic, "homie/5/test-device-1/$state");
+    assert_eq!(sleep.payload, b"sleeping");
+    assert!(sleep.retain);
+
+    let wake = protocol.exit_sleep(&description).unwrap();
+    let topics: Vec<_> = wake.iter().map(|p| p.topic.as_str()).collect();
+    assert_eq!(
+        topics,
+        vec![
+            "homie/5/test-device-1/$state",
+            "homie/5/test-device-1/$description",
+            "homie/5/test-device-1/$state"
+        ]
+    );
+    assert_eq!(wake[0].payload, b"init");
+    assert_eq!(wake[2].payload, b"ready");
+    assert!(wake.iter().all(|p| p.retain));
+}
+
+#[test]
+fn test_birth_bundle() {
+    let protocol = create_protocol();
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("state"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Boolean)
+                        .settable(true)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    let bundle = protocol
+        .birth_bundle(&description, |_, _| Some(HomieValue::Bool(true)))
+        .unwrap();
+
+    assert_eq!(bundle.last_will, protocol.last_will());
+    let publishes: Vec<_> = bundle
+        .publishes
+        .iter()
+        .map(|p| (p.topic.as_str(), String::from_utf8_lossy(&p.payload).into_owned()))
+        .collect();
+    assert_eq!(publishes.len(), 4);
+    assert_eq!(publishes[0], ("homie/5/test-device-1/$state", "init".to_owned()));
+    assert_eq!(publishes[1].0, "homie/5/test-device-1/$description");
+    assert_eq!(publishes[2], ("homie/5/test-device-1/light/state", "true".to_owned()));
+    assert_eq!(publishes[3], ("homie/5/test-device-1/$state", "ready".to_owned()));
+    assert_eq!(bundle.subscriptions.len(), 1);
+    assert_eq!(bundle.subscriptions[0].topic, "homie/5/test-device-1/light/state/set");
+
+    let json = serde_json::to_string(&bundle).unwrap();
+    assert_eq!(serde_json::from_str::<BirthBundle>(&json).unwrap(), bundle);
+}
+
+#[test]
+fn test_publish_description_invalid_format() {
+    let protocol = create_protocol();
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("node"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("prop"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Float)
+                        .format(HomiePropertyFormat::FloatRange(FloatRange {
+                            min: None,
+                            max: None,
+                            step: Some(f64::NAN),
+                        }))
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    assert!(matches!(
+        protocol.publish_description(&description),
+        Err(Homie5ProtocolError::InvalidDeviceDescription)
+    ));
+}
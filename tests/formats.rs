@@ -1,7 +1,13 @@
 mod common;
 
 use common::{run_homietests, HomieTest};
-use homie5::device_description::HomiePropertyDescription;
+use homie5::device_description::{
+    ColorFormat, FloatRange, HomiePropertyDescription, HomiePropertyFormat, HomiePropertyFormatError, IntegerRange,
+    PropertyDescriptionBuilder,
+};
+use homie5::{HomieColorValue, HomieDataType, HomieValue};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 #[test]
 fn test_homie_formats_boolean() {
@@ -15,3 +21,130 @@ fn test_homie_formats_boolean() {
 
     assert!(result.is_ok(), "{:?}", result);
 }
+
+#[test]
+fn test_color_format_parse() {
+    assert_eq!(
+        HomiePropertyFormat::parse("rgb,hsv", &HomieDataType::Color),
+        Ok(HomiePropertyFormat::Color(vec![ColorFormat::Rgb, ColorFormat::Hsv]))
+    );
+    // duplicates are dropped and the formats are brought into canonical order
+    assert_eq!(
+        HomiePropertyFormat::parse("xyz,rgb,xyz,rgb", &HomieDataType::Color),
+        Ok(HomiePropertyFormat::Color(vec![ColorFormat::Rgb, ColorFormat::Xyz]))
+    );
+    assert_eq!(
+        HomiePropertyFormat::parse("rgb,bogus", &HomieDataType::Color),
+        Err(HomiePropertyFormatError::ColorFormatError)
+    );
+}
+
+#[test]
+fn test_color_format_all() {
+    let formats: Vec<_> = ColorFormat::ALL.iter().map(|cf| cf.as_str()).collect();
+    assert_eq!(formats, vec!["rgb", "hsv", "xyz"]);
+
+    for cf in ColorFormat::ALL {
+        assert_eq!(cf.as_str().parse::<ColorFormat>(), Ok(cf.clone()));
+        assert_eq!(cf.to_string(), cf.as_str());
+    }
+}
+
+#[test]
+fn test_format_canonical_order() {
+    let hash = |format: &HomiePropertyFormat| {
+        let mut hasher = DefaultHasher::new();
+        format.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // color formats have no semantic order and are serialized canonically
+    let hsv_rgb = HomiePropertyFormat::Color(vec![ColorFormat::Hsv, ColorFormat::Rgb]);
+    let rgb_hsv = HomiePropertyFormat::Color(vec![ColorFormat::Rgb, ColorFormat::Hsv]);
+    assert_eq!(hsv_rgb.to_string(), "rgb,hsv");
+    assert_eq!(hsv_rgb.to_string(), rgb_hsv.to_string());
+    assert_eq!(hash(&hsv_rgb), hash(&rgb_hsv));
+    assert_eq!(hsv_rgb, rgb_hsv);
+    assert_ne!(hsv_rgb, HomiePropertyFormat::Color(vec![ColorFormat::Rgb]));
+
+    // the placeholder uses the first format in canonical order
+    let desc = PropertyDescriptionBuilder::new(HomieDataType::Color)
+        .format(hsv_rgb)
+        .build();
+    assert_eq!(
+        desc.placeholder_value(),
+        Some(HomieValue::Color(HomieColorValue::RGB(0, 0, 0)))
+    );
+
+    // enum values keep their order
+    let off_on = HomiePropertyFormat::Enum(vec!["off".to_owned(), "on".to_owned()]);
+    let on_off = HomiePropertyFormat::Enum(vec!["on".to_owned(), "off".to_owned()]);
+    assert_eq!(on_off.to_string(), "on,off");
+    assert_ne!(hash(&off_on), hash(&on_off));
+    assert_ne!(off_on, on_off);
+}
+
+#[test]
+fn test_format_is_compatible_with() {
+    let datatypes = [
+        HomieDataType::Integer,
+        HomieDataType::Float,
+        HomieDataType::Boolean,
+        HomieDataType::String,
+        HomieDataType::Enum,
+        HomieDataType::Color,
+        HomieDataType::Datetime,
+        HomieDataType::Duration,
+        HomieDataType::JSON,
+    ];
+    // each format with the only datatype it is compatible with, `None` for all datatypes
+    let formats = [
+        (
+            HomiePropertyFormat::FloatRange(FloatRange {
+                min: Some(0.0),
+                max: None,
+                step: None,
+            }),
+            Some(HomieDataType::Float),
+        ),
+        (
+            HomiePropertyFormat::IntegerRange(IntegerRange {
+                min: Some(0),
+                max: None,
+                step: None,
+            }),
+            Some(HomieDataType::Integer),
+        ),
+        (
+            HomiePropertyFormat::Enum(vec!["a".to_owned()]),
+            Some(HomieDataType::Enum),
+        ),
+        (
+            HomiePropertyFormat::Color(vec![ColorFormat::Rgb]),
+            Some(HomieDataType::Color),
+        ),
+        (
+            HomiePropertyFormat::Boolean {
+                false_val: "off".to_owned(),
+                true_val: "on".to_owned(),
+            },
+            Some(HomieDataType::Boolean),
+        ),
+        (HomiePropertyFormat::Json("{}".to_owned()), Some(HomieDataType::JSON)),
+        (HomiePropertyFormat::Custom("custom".to_owned()), None),
+        (HomiePropertyFormat::Empty, None),
+    ];
+
+    for (format, compatible) in &formats {
+        for datatype in &datatypes {
+            let expected = compatible.as_ref().is_none_or(|compatible| compatible == datatype);
+            assert_eq!(
+                format.is_compatible_with(datatype),
+                expected,
+                "{:?} with {:?}",
+                format,
+                datatype
+            );
+        }
+    }
+}
@@ -221,6 +221,70 @@ fn test_device_description_msg() {
     }
 }
 
+#[test]
+fn test_device_description_extensions_round_trip() {
+    let (protocol, _) = Homie5DeviceProtocol::new(HomieID::new_const("test-device-1"), HomieDomain::Default);
+    let description = device_description::DeviceDescriptionBuilder::new()
+        .add_extension("org.homie.meta:0.1.0:[5.x]")
+        .add_extension("com.example.extension:1.0.0:[5.x]")
+        .build();
+
+    let publish = protocol.publish_description(&description).unwrap();
+    let event = parse_mqtt_message(&publish.topic, &publish.payload).unwrap();
+
+    let Homie5Message::DeviceDescription {
+        description: parsed, ..
+    } = event
+    else {
+        panic!(
+            "Expected Homie5Message::DeviceDescription. Instead received: {:#?}",
+            event
+        );
+    };
+    assert_eq!(parsed.extensions, description.extensions);
+    assert!(parsed.has_extension("org.homie.meta"));
+    assert!(parsed.has_extension("com.example.extension"));
+    assert!(!parsed.has_extension("com.example"));
+}
+
+#[test]
+fn test_strict_parse_rejects_enum_without_format() {
+    let topic = "homie/5/test-device-1/$description";
+    let description_json = r#"{
+        "homie": "5.0",
+        "version": 1,
+        "nodes": {
+            "node": {
+                "properties": {
+                    "mode": { "datatype": "enum" },
+                    "state": { "datatype": "boolean" }
+                }
+            }
+        }
+    }"#;
+
+    let Ok(Homie5Message::DeviceDescription { description, .. }) =
+        parse_mqtt_message(topic, description_json.as_bytes())
+    else {
+        panic!("Expected parse_mqtt_message to accept the description");
+    };
+    assert!(!description.has_required_formats());
+    assert!(matches!(
+        parse_mqtt_message_strict(topic, description_json.as_bytes()),
+        Err(Homie5ProtocolError::InvalidDeviceDescription)
+    ));
+
+    let conforming = description_json.replace(r#""datatype": "enum""#, r#""datatype": "enum", "format": "a,b""#);
+    assert!(matches!(
+        parse_mqtt_message_strict(topic, conforming.as_bytes()),
+        Ok(Homie5Message::DeviceDescription { .. })
+    ));
+    assert!(matches!(
+        parse_mqtt_message_strict("homie/5/test-device-1/$state", b"ready"),
+        Ok(Homie5Message::DeviceState { .. })
+    ));
+}
+
 #[test]
 fn test_device_log_msg() {
     let p = rumqttc::Publish {
@@ -334,3 +398,388 @@ fn test_device_removal_msg() {
         );
     }
 }
+
+#[test]
+fn test_device_attributes_cleared() {
+    for attribute in [
+        DeviceAttribute::Description,
+        DeviceAttribute::Log,
+        DeviceAttribute::Alert,
+    ] {
+        let topic = format!("homie/5/test-device-1/{}", attribute);
+        let event = parse_mqtt_message(&topic, b"");
+        if let Ok(Homie5Message::DeviceAttributeCleared {
+            device,
+            attribute: cleared,
+        }) = event
+        {
+            assert_eq!(device.device_id().as_str(), "test-device-1");
+            assert_eq!(cleared, attribute);
+        } else {
+            panic!(
+                "Expected Homie5Message::DeviceAttributeCleared. Instead received: {:#?}",
+                event
+            );
+        }
+    }
+
+    // a non empty payload is still parsed as description
+    assert!(matches!(
+        parse_mqtt_message("homie/5/test-device-1/$description", b"{invalid"),
+        Err(Homie5ProtocolError::InvalidPayload)
+    ));
+}
+
+#[test]
+fn test_remove_device_sequence_parses() {
+    use device_description::*;
+
+    let (protocol, _) = Homie5DeviceProtocol::new(HomieID::new_const("test-device-1"), HomieDomain::Default);
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .settable(true)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+
+    let messages = protocol
+        .remove_device(&description)
+        .unwrap()
+        .map(|p| parse_mqtt_message(&p.topic, &p.payload))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert!(matches!(messages[0], Homie5Message::DeviceRemoval { .. }));
+    let cleared = messages
+        .iter()
+        .filter(|m| matches!(m, Homie5Message::DeviceAttributeCleared { .. }))
+        .count();
+    assert_eq!(cleared, 3);
+}
+
+#[test]
+fn test_to_publish_round_trip() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("test-device-1"));
+    let property = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-1"),
+        HomieID::new_const("node"),
+        HomieID::new_const("prop"),
+    );
+    let messages = vec![
+        Homie5Message::DeviceState {
+            device: device.clone(),
+            state: HomieDeviceStatus::Ready,
+        },
+        Homie5Message::DeviceDescription {
+            device: device.clone(),
+            description: device_description::DeviceDescriptionBuilder::new()
+                .name("Test device")
+                .build(),
+        },
+        Homie5Message::DeviceLog {
+            device: device.clone(),
+            level: DeviceLogLevel::Warn,
+            log_msg: "low battery".to_owned(),
+        },
+        Homie5Message::DeviceAlert {
+            device: device.clone(),
+            alert_id: HomieID::new_const("battery"),
+            alert_msg: "Battery is low!".to_owned(),
+        },
+        Homie5Message::DeviceAlertCleared {
+            device: device.clone(),
+            alert_id: HomieID::new_const("battery"),
+        },
+        Homie5Message::PropertyValue {
+            property: property.clone(),
+            value: "21.5".to_owned(),
+        },
+        Homie5Message::PropertyValue {
+            property: property.clone(),
+            value: String::new(),
+        },
+        Homie5Message::PropertyTarget {
+            property: property.clone(),
+            target: "22".to_owned(),
+        },
+        Homie5Message::PropertySet {
+            property,
+            set_value: "22".to_owned(),
+        },
+        Homie5Message::Broadcast {
+            homie_domain: HomieDomain::Default,
+            subtopic: "alarm/fire".to_owned(),
+            data: "kitchen".to_owned(),
+        },
+        Homie5Message::DeviceAttributeCleared {
+            device: device.clone(),
+            attribute: DeviceAttribute::Description,
+        },
+        Homie5Message::DeviceRemoval { device },
+    ];
+
+    for message in messages {
+        let publish = message.to_publish().unwrap();
+        let parsed = parse_mqtt_message(&publish.topic, &publish.payload).unwrap();
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", message));
+    }
+}
+
+#[test]
+fn test_property_event_from_message() {
+    use device_description::*;
+
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .settable(true)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+    let property = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-1"),
+        HomieID::new_const("light"),
+        HomieID::new_const("brightness"),
+    );
+
+    let cases = [
+        (
+            Homie5Message::PropertyValue {
+                property: property.clone(),
+                value: "10".to_owned(),
+            },
+            PropertyEventKind::Value,
+        ),
+        (
+            Homie5Message::PropertyTarget {
+                property: property.clone(),
+                target: "20".to_owned(),
+            },
+            PropertyEventKind::Target,
+        ),
+        (
+            Homie5Message::PropertySet {
+                property: property.clone(),
+                set_value: "30".to_owned(),
+            },
+            PropertyEventKind::Set,
+        ),
+    ];
+    for ((msg, kind), expected) in cases.iter().zip([10, 20, 30]) {
+        let event = PropertyEvent::from_message(msg, &description).unwrap().unwrap();
+        assert_eq!(event.prop_ref, property);
+        assert_eq!(event.kind, *kind);
+        assert_eq!(event.value, HomieValue::Integer(expected));
+    }
+
+    let invalid = Homie5Message::PropertyValue {
+        property: property.clone(),
+        value: "bright".to_owned(),
+    };
+    assert!(matches!(
+        PropertyEvent::from_message(&invalid, &description),
+        Err(Homie5ProtocolError::InvalidHomieValue(_))
+    ));
+
+    let state = Homie5Message::DeviceState {
+        device: property.device_ref().clone(),
+        state: HomieDeviceStatus::Ready,
+    };
+    assert!(PropertyEvent::from_message(&state, &description).unwrap().is_none());
+}
+
+#[test]
+fn test_enrich() {
+    use device_description::*;
+    use std::collections::HashMap;
+
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .settable(true)
+                        .build(),
+                )
+                .build(),
+        )
+        .build();
+    let devices = HashMap::from([(HomieID::new_const("test-device-1"), description)]);
+
+    let known = parse_mqtt_message("homie/5/test-device-1/light/brightness/set", b"42").unwrap();
+    let enriched = enrich(&known, &devices).unwrap();
+    assert_eq!(enriched.prop_ref.prop_id().as_str(), "brightness");
+    assert_eq!(enriched.kind, PropertyEventKind::Set);
+    assert_eq!(enriched.description.datatype, HomieDataType::Integer);
+    assert_eq!(enriched.value, HomieValue::Integer(42));
+
+    let unknown_prop = parse_mqtt_message("homie/5/test-device-1/light/color", b"42").unwrap();
+    assert!(enrich(&unknown_prop, &devices).is_none());
+    let unknown_device = parse_mqtt_message("homie/5/test-device-2/light/brightness", b"42").unwrap();
+    assert!(enrich(&unknown_device, &devices).is_none());
+    let invalid = parse_mqtt_message("homie/5/test-device-1/light/brightness", b"bright").unwrap();
+    assert!(enrich(&invalid, &devices).is_none());
+}
+
+#[test]
+fn test_classify_with_description() {
+    use device_description::*;
+
+    let description = DeviceDescriptionBuilder::new()
+        .add_node(
+            HomieID::new_const("light"),
+            NodeDescriptionBuilder::new()
+                .add_property(
+                    HomieID::new_const("brightness"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Integer)
+                        .settable(true)
+                        .build(),
+                )
+                .add_property(
+                    HomieID::new_const("power"),
+                    PropertyDescriptionBuilder::new(HomieDataType::Float).build(),
+                )
+                .build(),
+        )
+        .build();
+    let classify = |topic: &str| {
+        let msg = parse_mqtt_message(topic, b"42").unwrap();
+        classify_with_description(&msg, &description)
+    };
+    let prop_ref = |prop_id: &'static str| {
+        PropertyRef::new(
+            HomieDomain::Default,
+            HomieID::new_const("test-device-1"),
+            HomieID::new_const("light"),
+            HomieID::new_const(prop_id),
+        )
+    };
+
+    assert!(classify("homie/5/test-device-1/light/brightness").is_empty());
+    assert!(classify("homie/5/test-device-1/light/brightness/$target").is_empty());
+    assert!(classify("homie/5/test-device-1/light/brightness/set").is_empty());
+    assert!(classify("homie/5/test-device-1/light/power").is_empty());
+    let state = parse_mqtt_message("homie/5/test-device-1/$state", b"ready").unwrap();
+    assert!(classify_with_description(&state, &description).is_empty());
+
+    assert_eq!(
+        classify("homie/5/test-device-1/light/power/$target"),
+        vec![MessageDiagnostic::TargetOnNonSettable(prop_ref("power"))]
+    );
+    assert_eq!(
+        classify("homie/5/test-device-1/light/power/set"),
+        vec![MessageDiagnostic::SetOnNonSettable(prop_ref("power"))]
+    );
+    assert_eq!(
+        classify("homie/5/test-device-1/light/color"),
+        vec![MessageDiagnostic::UnknownProperty(prop_ref("color"))]
+    );
+    assert_eq!(
+        classify("homie/5/test-device-1/light/color/set"),
+        vec![MessageDiagnostic::UnknownProperty(prop_ref("color"))]
+    );
+}
+
+#[test]
+fn test_parse_value_with() {
+    use device_description::*;
+
+    let int_desc = PropertyDescriptionBuilder::new(HomieDataType::Integer).build();
+    let value = parse_mqtt_message("homie/5/test-device-1/light/brightness", b"42").unwrap();
+    assert_eq!(
+        value.parse_value_with(&int_desc).unwrap().unwrap(),
+        HomieValue::Integer(42)
+    );
+    let target = parse_mqtt_message("homie/5/test-device-1/light/brightness/$target", b"50").unwrap();
+    assert_eq!(
+        target.parse_value_with(&int_desc).unwrap().unwrap(),
+        HomieValue::Integer(50)
+    );
+    let invalid = parse_mqtt_message("homie/5/test-device-1/light/brightness", b"bright").unwrap();
+    assert!(invalid.parse_value_with(&int_desc).unwrap().is_err());
+
+    let enum_desc = PropertyDescriptionBuilder::new(HomieDataType::Enum)
+        .format(HomiePropertyFormat::Enum(vec!["low".to_owned(), "high".to_owned()]))
+        .settable(true)
+        .build();
+    let set = parse_mqtt_message("homie/5/test-device-1/light/mode/set", b"high").unwrap();
+    assert_eq!(
+        set.parse_value_with(&enum_desc).unwrap().unwrap(),
+        HomieValue::Enum("high".to_owned())
+    );
+    let set = parse_mqtt_message("homie/5/test-device-1/light/mode/set", b"medium").unwrap();
+    assert!(set.parse_value_with(&enum_desc).unwrap().is_err());
+
+    // not a property message
+    let state = parse_mqtt_message("homie/5/test-device-1/$state", b"ready").unwrap();
+    assert!(state.parse_value_with(&int_desc).is_none());
+}
+
+#[test]
+fn test_device_alert_cleared() {
+    let topic = "homie/5/test-device-1/$alert/battery";
+
+    let event = parse_mqtt_message(topic, b"");
+    if let Ok(Homie5Message::DeviceAlertCleared { device, alert_id }) = event {
+        assert_eq!(device.device_id().as_str(), "test-device-1");
+        assert_eq!(alert_id.as_str(), "battery");
+    } else {
+        panic!(
+            "Expected Homie5Message::DeviceAlertCleared. Instead received: {:#?}",
+            event
+        );
+    }
+
+    // a single 0 byte is an alert with an empty message
+    let event = parse_mqtt_message(topic, &[0]);
+    assert!(
+        matches!(&event, Ok(Homie5Message::DeviceAlert { alert_msg, .. }) if alert_msg.is_empty()),
+        "{:#?}",
+        event
+    );
+}
+
+#[test]
+fn test_status_and_log_level_bytes() {
+    for state in [
+        HomieDeviceStatus::Init,
+        HomieDeviceStatus::Ready,
+        HomieDeviceStatus::Disconnected,
+        HomieDeviceStatus::Sleeping,
+        HomieDeviceStatus::Lost,
+    ] {
+        assert_eq!(state.as_bytes(), state.as_str().as_bytes());
+        assert_eq!(state.as_bytes(), state.to_string().as_bytes());
+
+        let event = parse_mqtt_message("homie/5/test-device-1/$state", state.as_bytes()).unwrap();
+        assert!(matches!(event, Homie5Message::DeviceState { state: parsed, .. } if parsed == state));
+    }
+
+    for level in [
+        DeviceLogLevel::Debug,
+        DeviceLogLevel::Info,
+        DeviceLogLevel::Warn,
+        DeviceLogLevel::Error,
+        DeviceLogLevel::Fatal,
+    ] {
+        assert_eq!(level.as_bytes(), level.as_str().as_bytes());
+        assert_eq!(level.as_bytes(), level.to_string().as_bytes());
+    }
+}
@@ -14,3 +14,32 @@ fn test_homie_id_from_file() {
 
     assert!(result.is_ok(), "{:?}", result);
 }
+
+#[test]
+fn test_homie_id_reserved_names() {
+    // attribute names can never be ids
+    assert!(HomieID::validate("$state").is_err());
+    assert!(HomieID::try_from("$target".to_owned()).is_err());
+
+    // an id equal to the version segment is valid and parsed by position
+    assert!(HomieID::validate("5").is_ok());
+    let topic = parse_topic("homie/5/5/$state").unwrap();
+    assert_eq!(topic.device_id.unwrap().as_str(), "5");
+    assert_eq!(topic.attributes, vec!["$state"]);
+
+    assert!(HomieID::validate("device-01").is_ok());
+}
+
+#[test]
+fn test_homie_id_new_unchecked() {
+    const ID: HomieID = HomieID::new_unchecked("sensor-01");
+    assert_eq!(ID.as_str(), "sensor-01");
+    assert_eq!(ID, HomieID::new_const("sensor-01"));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic]
+fn test_homie_id_new_unchecked_invalid_in_debug() {
+    let _ = HomieID::new_unchecked("node/prop");
+}
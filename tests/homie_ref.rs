@@ -0,0 +1,100 @@
+use homie5::*;
+
+#[test]
+fn test_property_ref_topics() {
+    let prop = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-1"),
+        HomieID::new_const("light"),
+        HomieID::new_const("brightness"),
+    );
+
+    assert_eq!(prop.value_topic(), "homie/5/test-device-1/light/brightness");
+    assert_eq!(prop.target_topic(), "homie/5/test-device-1/light/brightness/$target");
+}
+
+#[test]
+fn test_prepared_property_topics_match_unprepared() {
+    let prop_ref = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-1"),
+        HomieID::new_const("node"),
+        HomieID::new_const("prop"),
+    );
+    let prepared = PreparedProperty::new(prop_ref.clone());
+    assert_eq!(prepared.value_topic(), prop_ref.value_topic());
+    assert_eq!(prepared.target_topic(), prop_ref.target_topic());
+    assert_eq!(prepared.set_topic(), "homie/5/test-device-1/node/prop/set");
+
+    let (protocol, _) = Homie5DeviceProtocol::new(HomieID::new_const("test-device-1"), HomieDomain::Default);
+    let prepared = protocol.prepare_property(prop_ref.clone()).unwrap();
+    for value in ["1", "2", "3"] {
+        let publish = protocol.publish_value_prepared(&prepared, value, true);
+        assert_eq!(
+            publish.topic,
+            protocol
+                .publish_value(prop_ref.node_id(), prop_ref.prop_id(), value, true)
+                .topic
+        );
+        assert_eq!(publish.payload, value.as_bytes());
+    }
+
+    let controller = Homie5ControllerProtocol::new();
+    assert_eq!(
+        controller
+            .set_command_prepared(&controller.prepare_property(prop_ref.clone()), &HomieValue::Integer(1))
+            .topic,
+        controller.set_command(&prop_ref, &HomieValue::Integer(1)).topic
+    );
+}
+
+#[test]
+fn test_prepare_property_applies_protocol() {
+    let prop_ref = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-1"),
+        HomieID::new_const("node"),
+        HomieID::new_const("prop"),
+    );
+
+    let (protocol, _) = Homie5DeviceProtocol::new(HomieID::new_const("test-device-1"), HomieDomain::Default);
+    let protocol = protocol.with_version("6");
+    let prepared = protocol.prepare_property(prop_ref.clone()).unwrap();
+    assert_eq!(prepared.value_topic(), "homie/6/test-device-1/node/prop");
+    assert_eq!(
+        protocol.publish_target_prepared(&prepared, "1", true).topic,
+        protocol
+            .publish_target(prop_ref.node_id(), prop_ref.prop_id(), "1", true)
+            .topic
+    );
+
+    let controller = Homie5ControllerProtocol::new().with_version("6");
+    assert_eq!(
+        controller.prepare_property(prop_ref.clone()).set_topic(),
+        "homie/6/test-device-1/node/prop/set"
+    );
+
+    // properties of other devices cannot be prepared by a device
+    let other = PropertyRef::new(
+        HomieDomain::Default,
+        HomieID::new_const("test-device-2"),
+        HomieID::new_const("node"),
+        HomieID::new_const("prop"),
+    );
+    assert!(matches!(
+        protocol.prepare_property(other),
+        Err(Homie5ProtocolError::PropertyDeviceMismatch)
+    ));
+}
+
+#[test]
+fn test_ref_navigation() {
+    let device = DeviceRef::new(HomieDomain::Default, HomieID::new_const("device-1"));
+    let node = NodeRef::from_device(device.clone(), HomieID::new_const("node-1"));
+    let property = PropertyRef::from_node(node.clone(), HomieID::new_const("prop-1"));
+
+    assert_eq!(node.device_ref(), &device);
+    assert_eq!(property.device_ref(), &device);
+    assert_eq!(property.node_ref(), node);
+    assert_eq!(property.node_ref().device_ref(), &device);
+}
@@ -84,6 +84,110 @@ fn test_homie_color_value_from_str_xyz() {
     assert_eq!(color, HomieColorValue::XYZ(0.3, 0.4, 0.3));
 }
 
+#[test]
+fn test_homie_color_value_from_str_channel_ranges() {
+    for valid in [
+        "rgb,0,0,0",
+        "rgb,255,255,255",
+        "hsv,0,0,0",
+        "hsv,360,100,100",
+        "xyz,0.0,0.0",
+        "xyz,1.0,1.0",
+    ] {
+        assert!(valid.parse::<HomieColorValue>().is_ok(), "{}", valid);
+    }
+    for invalid in [
+        "rgb,256,0,0",
+        "rgb,0,-1,0",
+        "rgb,999,0,0",
+        "hsv,361,0,0",
+        "hsv,400,0,0",
+        "hsv,0,101,0",
+        "hsv,0,0,-1",
+        "xyz,1.1,0.0",
+        "xyz,0.0,-0.1",
+    ] {
+        assert_eq!(
+            invalid.parse::<HomieColorValue>(),
+            Err(Homie5ValueConversionError::InvalidColorFormat(invalid.to_owned())),
+            "{}",
+            invalid
+        );
+    }
+}
+
+#[test]
+fn test_homie_color_value_xyz_equality_ignores_z() {
+    let parsed = "xyz,0.3,0.4".parse::<HomieColorValue>().unwrap();
+    // z is inconsistent with x and y, but z is derived and not part of the value
+    let constructed = HomieColorValue::XYZ(0.3, 0.4, 0.0);
+    assert_eq!(constructed, parsed);
+    assert_eq!(constructed, HomieColorValue::new_xyz(0.3, 0.4));
+    assert_eq!(constructed.to_string(), parsed.to_string());
+
+    assert_ne!(HomieColorValue::XYZ(0.3, 0.5, 0.2), parsed);
+}
+
+#[test]
+fn test_homie_color_value_conversions() {
+    let primaries = [
+        ((255, 0, 0), (0.0, 100.0, 100.0)),
+        ((0, 255, 0), (120.0, 100.0, 100.0)),
+        ((0, 0, 255), (240.0, 100.0, 100.0)),
+    ];
+    for ((r, g, b), hsv) in primaries {
+        assert_eq!(HomieColorValue::RGB(r, g, b).to_hsv(), hsv);
+        let (h, s, v) = hsv;
+        assert_eq!(
+            HomieColorValue::HSV(h as i64, s as i64, v as i64).to_rgb(),
+            (r as u8, g as u8, b as u8)
+        );
+    }
+
+    for grey in [0u8, 64, 128, 255] {
+        let (h, s, v) = HomieColorValue::RGB(grey as i64, grey as i64, grey as i64).to_hsv();
+        assert_eq!((h, s), (0.0, 0.0));
+        assert!((v - grey as f64 / 2.55).abs() < 1e-9);
+    }
+    assert_eq!(HomieColorValue::HSV(200, 0, 50).to_rgb(), (128, 128, 128));
+    assert_eq!(HomieColorValue::HSV(0, 0, 0).to_rgb(), (0, 0, 0));
+}
+
+#[test]
+fn test_homie_color_value_xyz_conversions() {
+    // D65 white point and the sRGB primaries
+    assert_eq!(HomieColorValue::new_xyz(0.3127, 0.329).to_rgb(), (255, 255, 255));
+    assert_eq!(HomieColorValue::new_xyz(0.64, 0.33).to_rgb(), (255, 0, 0));
+    assert_eq!(HomieColorValue::new_xyz(0.30, 0.60).to_rgb(), (0, 255, 0));
+    assert_eq!(HomieColorValue::new_xyz(0.15, 0.06).to_rgb(), (0, 0, 255));
+    // z is reconstructed from x and y, an inconsistent z is ignored
+    assert_eq!(HomieColorValue::XYZ(0.64, 0.33, 0.5).to_rgb(), (255, 0, 0));
+
+    let (x, y, z) = HomieColorValue::RGB(255, 0, 0).to_xyz();
+    assert!((x - 0.64).abs() < 1e-3 && (y - 0.33).abs() < 1e-3);
+    assert!((x + y + z - 1.0).abs() < 1e-9);
+    // greys all have the chromaticity of the white point, black included
+    for grey in [0, 128, 255] {
+        let (x, y, _) = HomieColorValue::RGB(grey, grey, grey).to_xyz();
+        assert!((x - 0.3127).abs() < 1e-3 && (y - 0.329).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn test_homie_color_value_normalized() {
+    assert_eq!(HomieColorValue::RGB(255, 0, 0).to_normalized_rgb(), [1.0, 0.0, 0.0]);
+    assert_eq!(HomieColorValue::HSV(120, 100, 100).to_normalized_rgb(), [0.0, 1.0, 0.0]);
+    assert_eq!(HomieColorValue::HSV(240, 100, 0).to_normalized_rgb(), [0.0, 0.0, 0.0]);
+    assert_eq!(
+        HomieColorValue::RGB(51, 102, 255).to_normalized_rgba(0.5),
+        [0.2, 0.4, 1.0, 0.5]
+    );
+    assert_eq!(
+        HomieColorValue::RGB(0, 0, 255).to_normalized_rgba(2.0),
+        [0.0, 0.0, 1.0, 1.0]
+    );
+}
+
 #[test]
 fn test_homie_color_value_from_str_invalid() {
     let color_str = "invalid,255,100,50";
@@ -136,6 +240,46 @@ fn test_homie_value_parse_float() {
     assert!(HomieValue::parse("200.0", &desc).is_err());
 }
 
+#[test]
+fn test_homie_value_to_payload_bytes_labeled_boolean() {
+    let desc = create_prop_desc(
+        HomieDataType::Boolean,
+        HomiePropertyFormat::Boolean {
+            false_val: "off".to_owned(),
+            true_val: "on".to_owned(),
+        },
+    );
+    let value = HomieValue::Bool(true);
+
+    // the labels are for display only, the payload stays parseable
+    assert_eq!(value.to_payload_bytes(&desc), b"true".to_vec());
+    assert_eq!(HomieValue::parse("true", &desc).unwrap(), value);
+    assert_eq!(value.to_display_string(&desc), "on");
+    assert_eq!(HomieValue::Bool(false).to_display_string(&desc), "off");
+}
+
+#[test]
+fn test_homie_value_to_mqtt_payload() {
+    assert_eq!(HomieValue::String(String::new()).to_mqtt_payload(), vec![0_u8]);
+    assert_eq!(HomieValue::Empty.to_mqtt_payload(), vec![0_u8]);
+    assert_eq!(
+        HomieValue::String("test".to_owned()).to_mqtt_payload(),
+        b"test".to_vec()
+    );
+    assert_eq!(HomieValue::Integer(-42).to_mqtt_payload(), b"-42".to_vec());
+}
+
+#[test]
+fn test_homie_value_to_payload_bytes_empty_string() {
+    let desc = create_prop_desc(HomieDataType::String, HomiePropertyFormat::Empty);
+    assert_eq!(HomieValue::String(String::new()).to_payload_bytes(&desc), vec![0_u8]);
+    assert_eq!(HomieValue::Empty.to_payload_bytes(&desc), vec![0_u8]);
+    assert_eq!(
+        HomieValue::String("test".to_owned()).to_payload_bytes(&desc),
+        b"test".to_vec()
+    );
+}
+
 #[test]
 fn test_homie_value_parse_bool() {
     let desc = create_prop_desc(HomieDataType::Boolean, HomiePropertyFormat::Empty);
@@ -461,16 +605,22 @@ fn test_enum_ok() {
     );
 }
 
+/// A color property without a format as published by a non-conforming device, the builder
+/// rejects it
+fn color_desc_without_format() -> HomiePropertyDescription {
+    serde_json::from_str(r#"{ "datatype": "color" }"#).unwrap()
+}
+
 #[test]
 fn test_color_ok() {
-    let desc = PropertyDescriptionBuilder::new(HomieDataType::Color).build();
+    let desc = color_desc_without_format();
     assert_eq!(
         HomieValue::parse("rgb,12,55,14", &desc).ok(),
         Some(HomieValue::Color(HomieColorValue::RGB(12, 55, 14)))
     );
     assert_eq!(
-        HomieValue::parse("hsv,112,155,55", &desc).ok(),
-        Some(HomieValue::Color(HomieColorValue::HSV(112, 155, 55)))
+        HomieValue::parse("hsv,112,100,55", &desc).ok(),
+        Some(HomieValue::Color(HomieColorValue::HSV(112, 100, 55)))
     );
     assert_eq!(
         HomieValue::parse("xyz,0.33453,0.123456", &desc).ok(),
@@ -482,13 +632,47 @@ fn test_color_ok() {
     );
 }
 
+#[test]
+fn test_new_enum() {
+    let variants = vec!["low".to_owned(), "high".to_owned()];
+    assert_eq!(
+        HomieValue::new_enum("high", &variants),
+        Ok(HomieValue::Enum("high".to_owned()))
+    );
+    assert_eq!(
+        HomieValue::new_enum("medium", &variants),
+        Err(Homie5ValueConversionError::InvalidEnumFormat(
+            "medium".to_owned(),
+            variants.clone()
+        ))
+    );
+}
+
+#[test]
+fn test_new_color() {
+    let rgb = HomieColorValue::RGB(255, 0, 0);
+    assert_eq!(
+        HomieValue::new_color(rgb, &[ColorFormat::Rgb, ColorFormat::Hsv]),
+        Ok(HomieValue::Color(rgb))
+    );
+    assert_eq!(HomieValue::new_color(rgb, &[]), Ok(HomieValue::Color(rgb)));
+    assert_eq!(
+        HomieValue::new_color(rgb, &[ColorFormat::Xyz]),
+        Err(Homie5ValueConversionError::UnsupportedColorFormat(
+            ColorFormat::Rgb,
+            vec![ColorFormat::Xyz]
+        ))
+    );
+}
+
 #[test]
 fn test_color_nok() {
-    let desc = PropertyDescriptionBuilder::new(HomieDataType::Color).build();
+    let desc = color_desc_without_format();
     assert!(HomieValue::parse("rgb,12,55", &desc).is_err());
     assert!(HomieValue::parse("HSV,12,55,14", &desc).is_err());
     assert!(HomieValue::parse("rgb ,12,55,14", &desc).is_err());
     assert!(HomieValue::parse("xyz/12,55", &desc).is_err());
+    assert!(HomieValue::parse("hsv,112,155,55", &desc).is_err());
 }
 
 #[test]
@@ -626,3 +810,298 @@ fn test_validation_integer_nok() {
     let json = HomieValue::parse("{ \"test\": failure }", &desc);
     assert!(json.is_err());
 }
+
+#[test]
+fn test_matches_description() {
+    let desc = PropertyDescriptionBuilder::new(HomieDataType::Enum)
+        .format(HomiePropertyFormat::Enum(vec!["on".to_owned(), "off".to_owned()]))
+        .build();
+
+    assert_eq!(HomieValue::Enum("on".to_owned()).matches_description(&desc), Ok(()));
+    assert_eq!(HomieValue::Empty.matches_description(&desc), Ok(()));
+    assert_eq!(
+        HomieValue::String("on".to_owned()).matches_description(&desc),
+        Err((HomieDataType::Enum, HomieDataType::String))
+    );
+    assert_eq!(
+        HomieValue::Bool(true).matches_description(&desc),
+        Err((HomieDataType::Enum, HomieDataType::Boolean))
+    );
+}
+
+#[test]
+fn test_duration_secs_roundtrip() {
+    for secs in [0, 1, 59, 3600, 43546, -90] {
+        let value = HomieValue::duration_from_secs(secs);
+        assert_eq!(value, HomieValue::Duration(Duration::seconds(secs)));
+        assert_eq!(value.duration_as_secs(), Some(secs));
+    }
+
+    let value = HomieValue::duration_from_std(std::time::Duration::from_millis(12_500));
+    assert_eq!(value.duration_as_secs(), Some(12));
+    assert_eq!(value.to_string(), "PT12.5S");
+
+    assert_eq!(HomieValue::Integer(5).duration_as_secs(), None);
+}
+
+#[test]
+fn test_try_from_homie_value() {
+    let n: i64 = HomieValue::Integer(42).try_into().unwrap();
+    assert_eq!(n, 42);
+    let f: f64 = HomieValue::Float(1.5).try_into().unwrap();
+    assert_eq!(f, 1.5);
+    let b: bool = HomieValue::Bool(true).try_into().unwrap();
+    assert!(b);
+    let c: HomieColorValue = HomieValue::Color(HomieColorValue::RGB(1, 2, 3)).try_into().unwrap();
+    assert_eq!(c, HomieColorValue::RGB(1, 2, 3));
+    let j: serde_json::Value = HomieValue::JSON(json!({"a": 1})).try_into().unwrap();
+    assert_eq!(j, json!({"a": 1}));
+
+    assert_eq!(
+        i64::try_from(HomieValue::Float(1.0)),
+        Err(Homie5ValueConversionError::DatatypeMismatch(
+            HomieDataType::Integer,
+            Some(HomieDataType::Float)
+        ))
+    );
+    assert_eq!(
+        bool::try_from(HomieValue::Empty),
+        Err(Homie5ValueConversionError::DatatypeMismatch(
+            HomieDataType::Boolean,
+            None
+        ))
+    );
+}
+
+#[test]
+fn test_parse_reporting() {
+    let desc = create_float_desc(Some(2.0), Some(6.0), Some(2.0));
+    assert_eq!(
+        HomieValue::parse_reporting("4", &desc).unwrap(),
+        (HomieValue::Float(4.0), Adjustment::Exact)
+    );
+    assert_eq!(
+        HomieValue::parse_reporting("3.5", &desc).unwrap(),
+        (
+            HomieValue::Float(4.0),
+            Adjustment::Rounded {
+                from: HomieValue::Float(3.5)
+            }
+        )
+    );
+    assert_eq!(
+        HomieValue::parse_reporting("1.5", &desc).unwrap(),
+        (
+            HomieValue::Float(2.0),
+            Adjustment::Clamped {
+                from: HomieValue::Float(1.5)
+            }
+        )
+    );
+    assert!(HomieValue::parse_reporting("0.9", &desc).is_err());
+
+    let desc = create_integer_desc(Some(0), Some(10), Some(5));
+    assert_eq!(
+        HomieValue::parse_reporting("5", &desc).unwrap(),
+        (HomieValue::Integer(5), Adjustment::Exact)
+    );
+    assert_eq!(
+        HomieValue::parse_reporting("7", &desc).unwrap(),
+        (
+            HomieValue::Integer(5),
+            Adjustment::Rounded {
+                from: HomieValue::Integer(7)
+            }
+        )
+    );
+    assert_eq!(
+        HomieValue::parse_reporting("11", &desc).unwrap(),
+        (
+            HomieValue::Integer(10),
+            Adjustment::Clamped {
+                from: HomieValue::Integer(11)
+            }
+        )
+    );
+    assert!(HomieValue::parse_reporting("13", &desc).is_err());
+
+    // values of other types are never adjusted
+    let desc = create_prop_desc(HomieDataType::String, HomiePropertyFormat::Empty);
+    assert_eq!(
+        HomieValue::parse_reporting("3.5", &desc).unwrap(),
+        (HomieValue::String("3.5".to_owned()), Adjustment::Exact)
+    );
+}
+
+#[test]
+fn test_parse_unvalidated() {
+    let int_desc = create_prop_desc(
+        HomieDataType::Integer,
+        HomiePropertyFormat::IntegerRange(IntegerRange {
+            min: Some(0),
+            max: Some(100),
+            step: Some(10),
+        }),
+    );
+    assert!(HomieValue::parse("150", &int_desc).is_err());
+    assert_eq!(
+        HomieValue::parse_unvalidated("150", &int_desc).unwrap(),
+        HomieValue::Integer(150)
+    );
+    // no rounding to the step either
+    assert_eq!(HomieValue::parse("42", &int_desc).unwrap(), HomieValue::Integer(40));
+    assert_eq!(
+        HomieValue::parse_unvalidated("42", &int_desc).unwrap(),
+        HomieValue::Integer(42)
+    );
+    // the datatype is still enforced
+    assert!(HomieValue::parse_unvalidated("4.2", &int_desc).is_err());
+
+    let float_desc = create_prop_desc(
+        HomieDataType::Float,
+        HomiePropertyFormat::FloatRange(FloatRange {
+            min: Some(0.0),
+            max: Some(1.0),
+            step: None,
+        }),
+    );
+    assert!(HomieValue::parse("-0.5", &float_desc).is_err());
+    assert_eq!(
+        HomieValue::parse_unvalidated("-0.5", &float_desc).unwrap(),
+        HomieValue::Float(-0.5)
+    );
+
+    let color_desc = create_prop_desc(HomieDataType::Color, HomiePropertyFormat::Color(vec![ColorFormat::Rgb]));
+    assert!(HomieValue::parse("hsv,120,50,50", &color_desc).is_err());
+    assert_eq!(
+        HomieValue::parse_unvalidated("hsv,120,50,50", &color_desc).unwrap(),
+        HomieValue::Color(HomieColorValue::HSV(120, 50, 50))
+    );
+}
+
+#[test]
+fn test_integer_value_step_rounding_extremes() {
+    let desc = create_integer_desc(Some(i64::MIN), Some(i64::MAX), Some(10));
+    assert_eq!(
+        HomieValue::parse(&i64::MAX.to_string(), &desc).unwrap(),
+        HomieValue::Integer(i64::MAX - 5)
+    );
+    assert_eq!(
+        HomieValue::parse(&(i64::MIN + 6).to_string(), &desc).unwrap(),
+        HomieValue::Integer(i64::MIN + 10)
+    );
+}
+
+#[test]
+fn test_coerce() {
+    // int -> float
+    let desc = create_float_desc(Some(0.0), Some(10.0), Some(0.5));
+    assert_eq!(HomieValue::Integer(5).coerce(&desc), Ok(HomieValue::Float(5.0)));
+    assert!(matches!(
+        HomieValue::Integer(11).coerce(&desc),
+        Err(Homie5ValueConversionError::FloatOutOfRange(..))
+    ));
+
+    // float -> int is lossy and rejected
+    let desc = create_integer_desc(None, None, None);
+    assert_eq!(
+        HomieValue::Float(5.0).coerce(&desc),
+        Err(Homie5ValueConversionError::DatatypeMismatch(
+            HomieDataType::Integer,
+            Some(HomieDataType::Float)
+        ))
+    );
+
+    // color/enum/bool -> string
+    let desc = create_prop_desc(HomieDataType::String, HomiePropertyFormat::Empty);
+    assert_eq!(
+        HomieValue::Color(HomieColorValue::RGB(255, 100, 50)).coerce(&desc),
+        Ok(HomieValue::String("rgb,255,100,50".to_owned()))
+    );
+    assert_eq!(
+        HomieValue::Enum("on".to_owned()).coerce(&desc),
+        Ok(HomieValue::String("on".to_owned()))
+    );
+    assert_eq!(
+        HomieValue::Bool(true).coerce(&desc),
+        Ok(HomieValue::String("true".to_owned()))
+    );
+
+    // same datatype is checked against the target format
+    let desc = create_prop_desc(
+        HomieDataType::Enum,
+        HomiePropertyFormat::Enum(vec!["on".to_owned(), "off".to_owned()]),
+    );
+    assert!(HomieValue::Enum("on".to_owned()).coerce(&desc).is_ok());
+    assert!(HomieValue::Enum("dim".to_owned()).coerce(&desc).is_err());
+    assert!(HomieValue::String("on".to_owned()).coerce(&desc).is_err());
+    assert_eq!(HomieValue::Empty.coerce(&desc), Ok(HomieValue::Empty));
+}
+
+#[test]
+fn test_deserialize_with_runtime_datatype() {
+    use serde::de::DeserializeSeed;
+
+    let mut json = serde_json::Deserializer::from_str("42");
+    assert_eq!(
+        HomieValue::deserialize_as(&HomieDataType::Integer, &mut json).unwrap(),
+        HomieValue::Integer(42)
+    );
+    let mut json = serde_json::Deserializer::from_str(r#""42""#);
+    assert_eq!(
+        HomieValue::deserialize_as(&HomieDataType::Integer, &mut json).unwrap(),
+        HomieValue::Integer(42)
+    );
+    let mut json = serde_json::Deserializer::from_str("4.2");
+    assert!(HomieValue::deserialize_as(&HomieDataType::Integer, &mut json).is_err());
+
+    let color = HomieValueSeed(&HomieDataType::Color)
+        .deserialize(serde_yaml::Deserializer::from_str("hsv,120,100,50"))
+        .unwrap();
+    assert_eq!(color, HomieValue::Color(HomieColorValue::HSV(120, 100, 50)));
+    assert!(HomieValueSeed(&HomieDataType::Color)
+        .deserialize(serde_yaml::Deserializer::from_str("rgb,300,0,0"))
+        .is_err());
+
+    let mut json = serde_json::Deserializer::from_str(r#"{"a": 1}"#);
+    assert_eq!(
+        HomieValue::deserialize_as(&HomieDataType::JSON, &mut json).unwrap(),
+        HomieValue::JSON(serde_json::json!({"a": 1}))
+    );
+}
+
+#[test]
+fn test_json_schema_validation() {
+    let schema = r#"{"type": "object", "properties": {"a": {"type": "integer"}}, "required": ["a"]}"#;
+    let desc = create_prop_desc(HomieDataType::JSON, HomiePropertyFormat::Json(schema.to_owned()));
+
+    assert_eq!(
+        HomieValue::parse(r#"{"a": 1}"#, &desc).unwrap(),
+        HomieValue::JSON(serde_json::json!({"a": 1}))
+    );
+    // the schema is never checked by the unvalidated parser
+    assert!(HomieValue::parse_unvalidated(r#"{"a": "one"}"#, &desc).is_ok());
+
+    let mismatch = HomieValue::parse(r#"{"a": "one"}"#, &desc);
+    let invalid_schema = HomieValue::parse(
+        r#"{"a": 1}"#,
+        &create_prop_desc(HomieDataType::JSON, HomiePropertyFormat::Json("{not json".to_owned())),
+    );
+    if cfg!(feature = "jsonschema") {
+        assert!(matches!(
+            mismatch,
+            Err(Homie5ProtocolError::InvalidHomieValue(
+                Homie5ValueConversionError::JsonParseError(_)
+            ))
+        ));
+        assert!(matches!(
+            invalid_schema,
+            Err(Homie5ProtocolError::InvalidHomieValue(
+                Homie5ValueConversionError::InvalidJsonSchema(_)
+            ))
+        ));
+    } else {
+        assert!(mismatch.is_ok());
+        assert!(invalid_schema.is_ok());
+    }
+}
@@ -0,0 +1,101 @@
+use homie5::extensions::*;
+use homie5::*;
+
+#[test]
+fn test_meta_builder() {
+    let meta_proto = MetaDeviceProtocol::new(HomieID::new_const("test-device-1"), HomieDomain::Default);
+
+    let (meta, tags) = MetaBuilder::new()
+        .add_meta("room", "kitchen")
+        .add_meta("vendor", "acme")
+        .do_if(false, |b| b.add_meta("ignored", "value"))
+        .add_tag("light")
+        .add_tag("indoor")
+        .add_tag("light")
+        .build();
+
+    assert_eq!(meta.len(), 2);
+    assert_eq!(meta.get("room").map(String::as_str), Some("kitchen"));
+    assert_eq!(tags, vec!["light".to_owned(), "indoor".to_owned()]);
+
+    let publish = meta_proto.publish_meta_device(meta_proto.id(), &meta).unwrap();
+    assert_eq!(publish.topic, "homie/5/test-device-1/$meta");
+    let published: std::collections::HashMap<String, String> = serde_json::from_slice(&publish.payload).unwrap();
+    assert_eq!(published, meta);
+
+    let publish = meta_proto.publish_tags_device(meta_proto.id(), &tags).unwrap();
+    assert_eq!(publish.topic, "homie/5/test-device-1/$tags");
+    assert_eq!(publish.payload, br#"["light","indoor"]"#.to_vec());
+}
+
+#[test]
+fn test_meta_extension_in_publish_steps() {
+    let (protocol, _) = Homie5DeviceProtocol::new(HomieID::new_const("test-device-1"), HomieDomain::Default);
+    let meta_protocol = MetaDeviceProtocol::from(&protocol)
+        .with_device_meta(MetaBuilder::new().add_meta("room", "kitchen").add_tag("light").build());
+    let description = device_description::DeviceDescriptionBuilder::new().build();
+
+    let mut topics = Vec::new();
+    for step in homie_device_publish_steps() {
+        match step {
+            DevicePublishStep::DeviceStateInit => topics.push(protocol.publish_state(HomieDeviceStatus::Init).topic),
+            DevicePublishStep::DeviceDescription => {
+                topics.push(protocol.publish_description(&description).unwrap().topic);
+                let publishes = meta_protocol.extension_publishes(protocol.id()).unwrap();
+                topics.extend(publishes.into_iter().map(|p| p.topic));
+            }
+            DevicePublishStep::PropertyValues | DevicePublishStep::SubscribeProperties => {}
+            DevicePublishStep::DeviceStateReady => topics.push(protocol.publish_state(HomieDeviceStatus::Ready).topic),
+        }
+    }
+
+    assert_eq!(
+        topics,
+        vec![
+            "homie/5/test-device-1/$state",
+            "homie/5/test-device-1/$description",
+            "homie/5/test-device-1/$meta",
+            "homie/5/test-device-1/$tags",
+            "homie/5/test-device-1/$state",
+        ]
+    );
+}
+
+#[test]
+fn test_meta_extension_publishes_empty() {
+    let meta_protocol = MetaDeviceProtocol::new(HomieID::new_const("test-device-1"), HomieDomain::Default);
+    assert!(meta_protocol
+        .extension_publishes(meta_protocol.id())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_clear_meta_and_tags() {
+    let meta_proto = MetaDeviceProtocol::new(HomieID::new_const("test-device-1"), HomieDomain::Default);
+    let node_id = HomieID::new_const("node-1");
+
+    let publish = meta_proto.clear_meta_device(meta_proto.id());
+    assert_eq!(publish.topic, "homie/5/test-device-1/$meta");
+    assert!(publish.retain);
+    assert!(publish.payload.is_empty());
+    let Ok(MetaExtMessage::DeviceMeta { device, meta }) =
+        MetaExtMessage::from_mqtt_message(&publish.topic, &publish.payload)
+    else {
+        panic!("Expected cleared device meta to parse as MetaExtMessage::DeviceMeta");
+    };
+    assert_eq!(device.device_id(), meta_proto.id());
+    assert!(meta.is_empty());
+
+    let publish = meta_proto.clear_tags_node(meta_proto.id(), &node_id);
+    assert_eq!(publish.topic, "homie/5/test-device-1/node-1/$tags");
+    assert!(publish.retain);
+    assert!(publish.payload.is_empty());
+    let Ok(MetaExtMessage::NodeTags { node, tags }) =
+        MetaExtMessage::from_mqtt_message(&publish.topic, &publish.payload)
+    else {
+        panic!("Expected cleared node tags to parse as MetaExtMessage::NodeTags");
+    };
+    assert_eq!(node.node_id(), &node_id);
+    assert!(tags.is_empty());
+}
@@ -1,4 +1,5 @@
 use homie5::device_description::*;
+use homie5::HomieDataType;
 
 #[test]
 fn test_float_range_parse_normal_cases() {
@@ -201,3 +202,258 @@ fn test_integer_range_display() {
     };
     assert_eq!(range.to_string(), "::3");
 }
+
+#[test]
+fn test_integer_range_snap_contains() {
+    let range = IntegerRange {
+        min: Some(-6),
+        max: Some(6),
+        step: Some(3),
+    };
+    assert_eq!(range.bounds(), (Some(-6), Some(6)));
+    assert_eq!(range.snap(2), 3);
+    assert_eq!(range.snap(-5), -6);
+    assert_eq!(range.snap(7), 6);
+    assert!(range.contains(range.snap(7)));
+    assert!(!range.contains(range.snap(8)));
+
+    let unbounded = IntegerRange {
+        min: None,
+        max: None,
+        step: Some(5),
+    };
+    assert_eq!(unbounded.snap(7), 7);
+    assert!(unbounded.contains(i64::MAX));
+}
+
+#[test]
+fn test_integer_range_snap_extremes() {
+    // the distance between value and base exceeds the i64 range, the closest step (MAX + 5) is
+    // not representable so the closest step below is used
+    let from_min = IntegerRange {
+        min: Some(i64::MIN),
+        max: None,
+        step: Some(10),
+    };
+    assert_eq!(from_min.snap(i64::MAX), i64::MAX - 5);
+    assert_eq!(from_min.snap(i64::MIN), i64::MIN);
+    assert_eq!(from_min.snap(i64::MIN + 4), i64::MIN);
+
+    let from_max = IntegerRange {
+        min: None,
+        max: Some(i64::MAX),
+        step: Some(10),
+    };
+    assert_eq!(from_max.snap(i64::MIN), i64::MIN + 5);
+    assert_eq!(from_max.snap(i64::MAX - 6), i64::MAX - 10);
+
+    // large values are rounded exactly
+    let near_max = IntegerRange {
+        min: Some(i64::MAX - 10),
+        max: Some(i64::MAX),
+        step: Some(3),
+    };
+    assert_eq!(near_max.snap(i64::MAX), i64::MAX - 1);
+    assert_eq!(near_max.snap(i64::MAX - 8), i64::MAX - 7);
+
+    // the span of the full range does not overflow when validating the step
+    let full = IntegerRange::parse(&format!("{}:{}:10", i64::MIN, i64::MAX)).unwrap();
+    assert_eq!(full.snap(i64::MAX), i64::MAX - 5);
+}
+
+#[test]
+fn test_float_range_snap_contains() {
+    let range = FloatRange {
+        min: None,
+        max: Some(1.0),
+        step: Some(0.5),
+    };
+    assert_eq!(range.bounds(), (None, Some(1.0)));
+    assert_eq!(range.snap(0.2), 0.0);
+    assert_eq!(range.snap(-0.8), -1.0);
+    assert!(range.contains(-100.0));
+    assert!(!range.contains(1.1));
+    assert!(!range.is_empty());
+}
+
+#[test]
+fn test_integer_range_round_trip() {
+    let cases = [
+        (Some(5), None, None, "5:"),
+        (None, Some(10), None, ":10"),
+        (None, None, Some(5), "::5"),
+        (Some(1), None, Some(2), "1::2"),
+        (None, Some(10), Some(2), ":10:2"),
+        (Some(1), Some(10), None, "1:10"),
+        (Some(1), Some(10), Some(2), "1:10:2"),
+        (None, None, None, ""),
+    ];
+    for (min, max, step, expected) in cases {
+        let range = IntegerRange { min, max, step };
+        assert_eq!(range.to_string(), expected);
+        assert_eq!(IntegerRange::parse(expected).unwrap(), range);
+    }
+}
+
+#[test]
+fn test_float_range_round_trip() {
+    let cases = [
+        (Some(0.5), None, None, "0.5:"),
+        (None, Some(10.5), None, ":10.5"),
+        (None, None, Some(0.5), "::0.5"),
+        (Some(1.5), Some(10.5), Some(0.5), "1.5:10.5:0.5"),
+        (None, None, None, ""),
+    ];
+    for (min, max, step, expected) in cases {
+        let range = FloatRange { min, max, step };
+        assert_eq!(range.to_string(), expected);
+        assert_eq!(FloatRange::parse(expected).unwrap(), range);
+    }
+}
+
+#[test]
+fn test_empty_range_is_omitted() {
+    let desc = PropertyDescriptionBuilder::new(homie5::HomieDataType::Integer)
+        .format(HomiePropertyFormat::IntegerRange(IntegerRange {
+            min: None,
+            max: None,
+            step: None,
+        }))
+        .build();
+    let json = serde_json::to_value(&desc).unwrap();
+    assert!(json.get("format").is_none());
+}
+
+fn int_range(min: Option<i64>, max: Option<i64>, step: Option<i64>) -> IntegerRange {
+    IntegerRange { min, max, step }
+}
+
+fn float_range(min: Option<f64>, max: Option<f64>, step: Option<f64>) -> FloatRange {
+    FloatRange { min, max, step }
+}
+
+#[test]
+fn test_integer_range_intersect() {
+    // overlapping
+    assert_eq!(
+        int_range(Some(0), Some(100), None).intersect(&int_range(Some(50), None, None)),
+        Some(int_range(Some(50), Some(100), None))
+    );
+    // disjoint
+    assert_eq!(
+        int_range(Some(0), Some(10), None).intersect(&int_range(Some(20), Some(30), None)),
+        None
+    );
+    // the coarser step is kept and the bounds are moved onto its grid
+    assert_eq!(
+        int_range(Some(0), Some(100), Some(10)).intersect(&int_range(Some(15), Some(95), Some(5))),
+        Some(int_range(Some(20), Some(90), Some(10)))
+    );
+    // a single step of the base range lies within the sub range
+    assert_eq!(
+        int_range(Some(0), Some(100), Some(10)).intersect(&int_range(Some(15), Some(25), None)),
+        Some(int_range(Some(20), Some(20), None))
+    );
+    // step mismatch: 4 is not a multiple of 3
+    assert_eq!(
+        int_range(Some(0), Some(100), Some(3)).intersect(&int_range(Some(0), Some(100), Some(4))),
+        None
+    );
+    // step mismatch: the grids are not aligned
+    assert_eq!(
+        int_range(Some(0), Some(100), Some(4)).intersect(&int_range(Some(1), Some(100), Some(2))),
+        None
+    );
+}
+
+#[test]
+fn test_integer_range_union() {
+    // overlapping
+    assert_eq!(
+        int_range(Some(0), Some(50), None).union(&int_range(Some(25), Some(100), None)),
+        int_range(Some(0), Some(100), None)
+    );
+    // disjoint ranges are combined including the gap, an unbounded side stays unbounded
+    assert_eq!(
+        int_range(Some(0), Some(10), None).union(&int_range(Some(20), None, None)),
+        int_range(Some(0), None, None)
+    );
+    // the finer step is kept if the grids are aligned
+    assert_eq!(
+        int_range(Some(0), Some(50), Some(10)).union(&int_range(Some(20), Some(100), Some(5))),
+        int_range(Some(0), Some(100), Some(5))
+    );
+    // step mismatch: the step is dropped
+    assert_eq!(
+        int_range(Some(0), Some(50), Some(3)).union(&int_range(Some(20), Some(100), Some(4))),
+        int_range(Some(0), Some(100), None)
+    );
+}
+
+#[test]
+fn test_float_range_intersect_union() {
+    // overlapping
+    assert_eq!(
+        float_range(Some(0.0), Some(1.0), None).intersect(&float_range(Some(0.5), Some(2.0), None)),
+        Some(float_range(Some(0.5), Some(1.0), None))
+    );
+    // disjoint
+    assert_eq!(
+        float_range(Some(0.0), Some(1.0), None).intersect(&float_range(Some(1.5), Some(2.0), None)),
+        None
+    );
+    // the coarser step is kept and the bounds are moved onto its grid
+    assert_eq!(
+        float_range(Some(0.0), Some(10.0), Some(0.5)).intersect(&float_range(Some(0.3), Some(9.9), Some(0.1))),
+        Some(float_range(Some(0.5), Some(9.5), Some(0.5)))
+    );
+    // step mismatch
+    assert_eq!(
+        float_range(Some(0.0), Some(10.0), Some(0.3)).intersect(&float_range(Some(0.0), Some(10.0), Some(0.5))),
+        None
+    );
+
+    assert_eq!(
+        float_range(Some(0.0), Some(1.0), Some(0.5)).union(&float_range(Some(2.0), Some(3.0), Some(0.25))),
+        float_range(Some(0.0), Some(3.0), Some(0.25))
+    );
+    assert_eq!(
+        float_range(Some(0.0), Some(1.0), Some(0.3)).union(&float_range(Some(2.0), Some(3.0), Some(0.5))),
+        float_range(Some(0.0), Some(3.0), None)
+    );
+}
+
+#[test]
+fn test_range_invalid_steps() {
+    for step in [-1.0, 0.0, f64::NAN, f64::INFINITY] {
+        let range = float_range(Some(0.0), Some(10.0), Some(step));
+        assert_eq!(range.validate(), Err(HomiePropertyFormatError::RangeFormatError));
+        assert_eq!(
+            HomiePropertyFormat::FloatRange(range).validate(),
+            Err(HomiePropertyFormatError::RangeFormatError)
+        );
+    }
+    assert!(float_range(Some(f64::NAN), None, None).validate().is_err());
+    assert!(float_range(Some(0.0), Some(10.0), Some(0.5)).validate().is_ok());
+
+    for step in [-1, 0] {
+        assert_eq!(
+            int_range(Some(0), Some(10), Some(step)).validate(),
+            Err(HomiePropertyFormatError::RangeFormatError)
+        );
+    }
+    assert!(int_range(Some(0), Some(10), Some(2)).validate().is_ok());
+
+    // parsing rejects the same ranges
+    for raw in ["0:10:-1", "0:10:0", "0:10:NaN", "::inf", "NaN:"] {
+        assert_eq!(
+            HomiePropertyFormat::parse(raw, &HomieDataType::Float),
+            Err(HomiePropertyFormatError::RangeFormatError),
+            "{raw}"
+        );
+    }
+    assert_eq!(
+        HomiePropertyFormat::parse("0:10:-1", &HomieDataType::Integer),
+        Err(HomiePropertyFormatError::RangeFormatError)
+    );
+}
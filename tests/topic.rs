@@ -0,0 +1,201 @@
+use homie5::extensions::*;
+use homie5::*;
+
+#[test]
+fn test_parse_topic_device_attribute() {
+    let topic = parse_topic("homie/5/test-device-1/$log/debug").unwrap();
+
+    assert_eq!(topic.homie_domain, HomieDomain::Default);
+    assert_eq!(topic.device_id, Some(HomieID::new_const("test-device-1")));
+    assert_eq!(topic.node_id, None);
+    assert_eq!(topic.prop_id, None);
+    assert_eq!(topic.attributes, vec!["$log", "debug"]);
+}
+
+#[test]
+fn test_parse_topic_property() {
+    let topic = parse_topic("homie/5/test-device-1/node/prop").unwrap();
+    assert_eq!(topic.node_id, Some(HomieID::new_const("node")));
+    assert_eq!(topic.prop_id, Some(HomieID::new_const("prop")));
+    assert!(topic.attributes.is_empty());
+
+    let topic = parse_topic("homie/5/test-device-1/node/prop/set").unwrap();
+    assert_eq!(topic.attributes, vec!["set"]);
+}
+
+#[test]
+fn test_parse_topic_broadcast() {
+    let topic = parse_topic("custom/5/$broadcast/alarm/fire").unwrap();
+
+    assert!(topic.is_broadcast());
+    assert_eq!(topic.homie_domain.as_str(), "custom");
+    assert_eq!(topic.attributes, vec!["alarm", "fire"]);
+}
+
+#[test]
+fn test_parse_topic_invalid() {
+    assert!(matches!(
+        parse_topic("homie/5/test-device-1"),
+        Err(Homie5ProtocolError::InvalidTopic)
+    ));
+    assert!(matches!(
+        parse_topic("homie/4/test-device-1/$state"),
+        Err(Homie5ProtocolError::InvalidTopic)
+    ));
+    assert!(matches!(
+        parse_topic("homie/5/Invalid_Device/$state"),
+        Err(Homie5ProtocolError::InvalidHomieID(_))
+    ));
+}
+
+#[test]
+fn test_meta_ext_message_from_topic() {
+    let msg = MetaExtMessage::from_mqtt_message("homie/5/test-device-1/node/prop/$tags", br#"["a","b"]"#).unwrap();
+    if let MetaExtMessage::PropertyTags { property, tags } = msg {
+        assert_eq!(property.prop_id().as_str(), "prop");
+        assert_eq!(tags, vec!["a".to_owned(), "b".to_owned()]);
+    } else {
+        panic!("Expected MetaExtMessage::PropertyTags");
+    }
+
+    let msg = MetaExtMessage::from_mqtt_message("homie/5/test-device-1/$meta", br#"{"k":"v"}"#).unwrap();
+    assert!(matches!(msg, MetaExtMessage::DeviceMeta { .. }));
+}
+
+#[test]
+fn test_topic_builder_with_version() {
+    let device_id = HomieID::new_const("test-device-1");
+    let topic = TopicBuilder::new_for_device(&HomieDomain::Default, &device_id)
+        .with_version("4")
+        .add_attr("$state")
+        .build();
+    assert_eq!(topic, "homie/4/test-device-1/$state");
+
+    // the version can be replaced after the rest of the topic has been built
+    let topic = TopicBuilder::new_for_device(&HomieDomain::Default, &device_id)
+        .add_id(&HomieID::new_const("node"))
+        .with_version("5.1")
+        .with_version("6")
+        .build();
+    assert_eq!(topic, "homie/6/test-device-1/node");
+}
+
+#[test]
+fn test_try_add_attr() {
+    let builder = TopicBuilder::new_for_device(&HomieDomain::Default, &HomieID::new_const("test-device-1"));
+    assert_eq!(
+        builder.clone().try_add_attr("$meta").unwrap().build(),
+        "homie/5/test-device-1/$meta"
+    );
+
+    for attr in ["$meta/extra", "+", "$tags#", ""] {
+        assert!(matches!(
+            builder.clone().try_add_attr(attr),
+            Err(Homie5ProtocolError::InvalidTopicSegment(segment)) if segment == attr
+        ));
+    }
+}
+
+#[test]
+fn test_classify_topic() {
+    assert_eq!(classify_topic("homie/5/$broadcast/alarm"), Some(TopicKind::Broadcast));
+    assert_eq!(
+        classify_topic("homie/5/test-device-1/$state"),
+        Some(TopicKind::DeviceAttribute)
+    );
+    assert_eq!(
+        classify_topic("homie/5/test-device-1/$log/info"),
+        Some(TopicKind::DeviceAttribute)
+    );
+    assert_eq!(
+        classify_topic("homie/5/test-device-1/node/$meta"),
+        Some(TopicKind::NodeAttribute)
+    );
+    assert_eq!(
+        classify_topic("homie/5/test-device-1/node/prop"),
+        Some(TopicKind::PropertyValue)
+    );
+    assert_eq!(
+        classify_topic("homie/5/test-device-1/node/prop/set"),
+        Some(TopicKind::PropertySet)
+    );
+    assert_eq!(
+        classify_topic("homie/5/test-device-1/node/prop/$target"),
+        Some(TopicKind::PropertyTarget)
+    );
+    assert_eq!(
+        classify_topic("homie/5/test-device-1/node/prop/$meta"),
+        Some(TopicKind::PropertyAttribute)
+    );
+}
+
+#[test]
+fn test_classify_topic_invalid() {
+    assert_eq!(classify_topic("homie/4/test-device-1/$state"), None);
+    assert_eq!(classify_topic("homie/5/test-device-1"), None);
+    assert_eq!(classify_topic("homie/5/test-device-1/node"), None);
+    assert_eq!(classify_topic("homie/5/$broadcast"), None);
+    assert_eq!(classify_topic("homie/5/test-device-1/node/prop/set/more"), None);
+}
+
+#[test]
+fn test_topic_matches_filter() {
+    let filter = "homie/5/+/$state";
+    assert!(topic_matches_filter("homie/5/test-device-1/$state", filter));
+    assert!(topic_matches_filter("homie/5/other-device/$state", filter));
+    assert!(!topic_matches_filter("homie/5/test-device-1/$description", filter));
+    assert!(!topic_matches_filter("homie/5/test-device-1/node/$state", filter));
+    assert!(!topic_matches_filter("homie/5/$state", filter));
+    assert!(!topic_matches_filter("homie/4/test-device-1/$state", filter));
+    assert!(!topic_matches_filter("homie/5/test-device-1/$state/more", filter));
+}
+
+#[test]
+fn test_topic_matches_filter_multi_level() {
+    assert!(topic_matches_filter(
+        "homie/5/test-device-1/$log/info",
+        "homie/5/test-device-1/#"
+    ));
+    assert!(topic_matches_filter("homie/5/test-device-1", "homie/5/test-device-1/#"));
+    assert!(topic_matches_filter("homie/5/test-device-1/$state", "#"));
+    assert!(!topic_matches_filter(
+        "homie/5/test-device-2/$state",
+        "homie/5/test-device-1/#"
+    ));
+
+    // exact match
+    assert!(topic_matches_filter(
+        "homie/5/test-device-1/$state",
+        "homie/5/test-device-1/$state"
+    ));
+
+    // `+` matches empty levels, but not missing levels
+    assert!(topic_matches_filter("homie//$state", "homie/+/$state"));
+    assert!(!topic_matches_filter("homie/5", "homie/5/+"));
+
+    // `$` topics are not matched by leading wildcards
+    assert!(!topic_matches_filter("$SYS/broker/uptime", "#"));
+    assert!(topic_matches_filter("$SYS/broker/uptime", "$SYS/#"));
+
+    // invalid filters never match
+    assert!(!topic_matches_filter("homie/5/test-device-1/$state", "homie/#/$state"));
+    assert!(!topic_matches_filter(
+        "homie/5/test-device-1/$state",
+        "homie/5/test+/$state"
+    ));
+}
+
+#[test]
+fn test_topic_device() {
+    let (domain, device_id) = topic_device("homie/5/test-device-1/node/prop/$target").unwrap();
+    assert_eq!(domain, HomieDomain::Default);
+    assert_eq!(device_id.unwrap().as_str(), "test-device-1");
+
+    let (domain, device_id) = topic_device("my-domain/5/$broadcast/alarm").unwrap();
+    assert_eq!(domain.as_str(), "my-domain");
+    assert!(device_id.is_none());
+
+    assert!(topic_device("homie/5/test-device-1").is_none());
+    assert!(topic_device("homie/4/test-device-1/$state").is_none());
+    assert!(topic_device("homie/5/Invalid_Device/$state").is_none());
+}
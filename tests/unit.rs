@@ -0,0 +1,30 @@
+use homie5::*;
+
+#[test]
+fn test_known_units_round_trip() {
+    assert_eq!(HomieUnit::KNOWN.len(), HOMIE_UNITS.len());
+    for (unit, unit_str) in HomieUnit::KNOWN.iter().zip(HOMIE_UNITS) {
+        assert_eq!(unit.as_str(), unit_str);
+        assert_eq!(unit.to_string(), unit_str);
+        assert_eq!(unit_str.parse::<HomieUnit>().unwrap(), *unit);
+        assert!(!unit.is_custom());
+        assert!(unit.dimension().is_some());
+    }
+}
+
+#[test]
+fn test_custom_unit_round_trip() {
+    let unit = "ppm".parse::<HomieUnit>().unwrap();
+    assert_eq!(unit, HomieUnit::Custom("ppm".to_owned()));
+    assert_eq!(unit.as_str(), "ppm");
+    assert!(unit.is_custom());
+    assert_eq!(unit.dimension(), None);
+}
+
+#[test]
+fn test_unit_dimension() {
+    assert_eq!(HomieUnit::Kelvin.dimension(), Some(UnitDimension::Temperature));
+    assert_eq!(HomieUnit::Kilowatt.dimension(), Some(UnitDimension::Power));
+    assert_eq!(HomieUnit::KilowattHour.dimension(), Some(UnitDimension::Energy));
+    assert_eq!(HomieUnit::Feet.dimension(), Some(UnitDimension::Length));
+}